@@ -1,10 +1,56 @@
-use crate::settings::JobSettings;
+use crate::db::ProfileScore;
+use crate::embedding;
+use crate::llm::{self, LlmExtraction, LlmFit};
+use crate::mcp;
+use crate::scoring::{
+    self, match_listing, settings_hash, ExtractedListing, MatchResult, ScoreWeights, SCORER_VERSION,
+};
+use crate::settings::{JobSettings, ProfileStore};
+use crate::text;
+use crate::timezone;
+use crate::titles;
+use crate::truncate;
 use regex::Regex;
 use scraper::{Html, Selector};
 use serde_json::{json, Value};
 use std::io::{BufRead, BufReader, Write};
 use std::net::TcpStream;
+use std::process::{Child, Command};
+use std::sync::OnceLock;
 use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+use uuid::Uuid;
+
+const READ_TIMEOUT: Duration = Duration::from_secs(20);
+
+/// Starts the agent for `url` as a child process and emits `analysis:started`. A background
+/// analysis saves its match without pointing the webview at the listing.
+pub fn launch(
+    app: &AppHandle,
+    mcp_port: u16,
+    url: &str,
+    background: bool,
+) -> Result<(String, Child), String> {
+    let analysis_id = Uuid::new_v4().to_string();
+    let exe = std::env::current_exe().map_err(|err| format!("locate executable: {err}"))?;
+    let mut command = Command::new(exe);
+    command
+        .arg("--analysis-agent")
+        .env("JOB_HUNTER_MCP_PORT", mcp_port.to_string())
+        .env("JOB_HUNTER_TARGET_URL", url)
+        .env("JOB_HUNTER_ANALYSIS_ID", analysis_id.clone());
+    if background {
+        command.env("JOB_HUNTER_BACKGROUND", "1");
+    }
+    let child = command
+        .spawn()
+        .map_err(|err| format!("spawn analysis agent: {err}"))?;
+    let _ = app.emit(
+        "analysis:started",
+        json!({ "analysisId": analysis_id, "mcpPort": mcp_port, "url": url, "background": background }),
+    );
+    Ok((analysis_id, child))
+}
 
 pub fn run() {
     if let Err(err) = run_inner() {
@@ -20,6 +66,7 @@ fn run_inner() -> Result<(), String> {
     let url = std::env::var("JOB_HUNTER_TARGET_URL")
         .map_err(|_| "missing JOB_HUNTER_TARGET_URL".to_string())?;
     let analysis_id = std::env::var("JOB_HUNTER_ANALYSIS_ID").ok();
+    let background = std::env::var_os("JOB_HUNTER_BACKGROUND").is_some();
 
     let mut client = McpClient::connect(port)?;
     let _ = client.send("initialize", json!({}))?;
@@ -33,11 +80,45 @@ fn run_inner() -> Result<(), String> {
         .cloned()
         .and_then(|value| serde_json::from_value::<JobSettings>(value).ok())
         .unwrap_or_default();
+    let profiles = client
+        .send(
+            "call_tool",
+            json!({ "name": "list_profiles", "arguments": {} }),
+        )
+        .ok()
+        .and_then(|value| serde_json::from_value::<ProfileStore>(value).ok())
+        .unwrap_or_else(|| ProfileStore::with_default(settings.clone()));
 
-    let content_value = client.send(
+    let content_value = match client.send_detailed(
         "call_tool",
-        json!({ "name": "fetch_content", "arguments": { "url": url, "maxLength": 120000 } }),
-    )?;
+        json!({
+          "name": "fetch_content",
+          "arguments": {
+            "url": url,
+            "maxLength": 120000,
+            "analysisId": analysis_id,
+            "automated": background
+          }
+        }),
+    ) {
+        Ok(value) => value,
+        Err(err) => {
+            // Lets the UI tell a block or dead link from a transient failure.
+            let _ = client.send(
+                "call_tool",
+                json!({
+                  "name": "report_analysis_error",
+                  "arguments": {
+                    "analysisId": analysis_id,
+                    "url": url,
+                    "message": err.message,
+                    "category": err.category
+                  }
+                }),
+            );
+            return Err(err.message);
+        }
+    };
 
     let html = content_value
         .get("html")
@@ -55,16 +136,93 @@ fn run_inner() -> Result<(), String> {
         .unwrap_or("")
         .to_string();
 
-    let extracted = extract_listing(&html, &text, &default_title);
-    let scored = match_listing(&extracted, &settings);
+    let mut extracted = extract_listing(&html, &text, &default_title);
+    let llm_timeout = llm_stage_timeout(&mut client);
+    let listing_text = document_text(&html).unwrap_or_else(|| text.clone());
+    let mut llm_extraction = None;
+    if let Some(timeout) = llm_timeout {
+        match run_llm_extraction(&mut client, &listing_text, timeout) {
+            Ok(value) => {
+                apply_llm_extraction(&mut extracted, &value);
+                llm_extraction = Some(value);
+            }
+            Err(err) => eprintln!("llm extraction failed, using heuristic results: {err}"),
+        }
+    }
+
+    let mut content_hash = None;
+    let mut active_score: Option<(MatchResult, String)> = None;
+    let mut profile_scores = Vec::new();
+    for profile in &profiles.profiles {
+        let reference = embedding::profile_text(&profile.settings);
+        let semantic = semantic_similarity(&mut client, &listing_text, &reference);
+        let similarity = semantic.as_ref().map(|(similarity, _)| *similarity);
+        if content_hash.is_none() {
+            content_hash = semantic.map(|(_, content_hash)| content_hash);
+        }
+        let weights = scoring_weights(&mut client, &profile.name);
+        let rules = scoring::compile_rules(&profile.settings);
+        let result = match_listing(&extracted, &profile.settings, &rules, similarity, &weights);
+        let hash = settings_hash(&profile.settings, &weights);
+        profile_scores.push(ProfileScore {
+            profile: profile.name.clone(),
+            match_score: result.match_score,
+            score_breakdown: Some(result.breakdown.clone()),
+            rejection: result.rejection.clone(),
+            previous_score: None,
+            settings_hash: Some(hash.clone()),
+            scorer_version: Some(SCORER_VERSION),
+        });
+        if profile.name == profiles.active {
+            active_score = Some((result, hash));
+        }
+    }
+    let (scored, scored_hash) = match active_score {
+        Some(scored) => scored,
+        None => {
+            let weights = scoring_weights(&mut client, &profiles.active);
+            let rules = scoring::compile_rules(&settings);
+            (
+                match_listing(&extracted, &settings, &rules, None, &weights),
+                settings_hash(&settings, &weights),
+            )
+        }
+    };
+    let llm_fit = llm_timeout.and_then(|timeout| {
+        run_llm_fit(&mut client, &listing_text, &settings, timeout)
+            .map_err(|err| eprintln!("llm fit summary failed, using heuristic summary: {err}"))
+            .ok()
+    });
+    let insights = if llm_extraction.is_some() || llm_fit.is_some() {
+        Some(json!({
+          "source": "llm",
+          "arrangement": llm_extraction.as_ref().and_then(|value| value.arrangement.clone()),
+          "skills": llm_extraction.map(|value| value.skills).unwrap_or_default(),
+          "fitSummary": llm_fit.as_ref().map(|fit| fit.fit_summary.clone()),
+          "pros": llm_fit.as_ref().map(|fit| fit.pros.clone()).unwrap_or_default(),
+          "cons": llm_fit.as_ref().map(|fit| fit.cons.clone()).unwrap_or_default()
+        }))
+    } else {
+        None
+    };
+    let summary = match &llm_fit {
+        Some(fit) => fit.fit_summary.clone(),
+        None => scored.summary,
+    };
+
     let analysis = AnalysisResult {
         url: url.clone(),
         title: extracted.title.clone(),
         company: extracted.company.clone(),
         location: extracted.location.clone(),
-        summary: scored.summary,
+        summary,
         match_score: scored.match_score,
         raw_excerpt: extracted.raw_excerpt.clone(),
+        insights,
+        content_hash,
+        score_breakdown: scored.breakdown,
+        profile_scores,
+        rejection: scored.rejection,
     };
     let AnalysisResult {
         url,
@@ -74,6 +232,11 @@ fn run_inner() -> Result<(), String> {
         summary,
         match_score,
         raw_excerpt,
+        insights,
+        content_hash,
+        score_breakdown,
+        profile_scores,
+        rejection,
     } = analysis;
 
     let url_for_query = url.clone();
@@ -91,11 +254,24 @@ fn run_inner() -> Result<(), String> {
             "location": location,
             "match_score": match_score,
             "summary": summary,
-            "raw_excerpt": raw_excerpt
+            "raw_excerpt": raw_excerpt,
+            "insights": insights,
+            "content_hash": content_hash,
+            "score_breakdown": score_breakdown,
+            "profile_scores": profile_scores,
+            "rejection": rejection,
+            "listing": extracted,
+            "role_family": extracted.title.as_deref().and_then(titles::role_family),
+            "settings_hash": scored_hash,
+            "scorer_version": SCORER_VERSION
           }
         }),
     )?;
 
+    // Background analyses, such as those started from feeds, leave the webview alone.
+    if background {
+        return Ok(());
+    }
     let _ = client.send(
         "call_tool",
         json!({
@@ -114,6 +290,12 @@ fn run_inner() -> Result<(), String> {
     Ok(())
 }
 
+/// A tool call's error payload.
+struct ToolFailure {
+    message: String,
+    category: Option<String>,
+}
+
 struct McpClient {
     reader: BufReader<TcpStream>,
     writer: TcpStream,
@@ -125,7 +307,7 @@ impl McpClient {
         let stream =
             TcpStream::connect(("127.0.0.1", port)).map_err(|err| format!("connect mcp: {err}"))?;
         stream
-            .set_read_timeout(Some(Duration::from_secs(20)))
+            .set_read_timeout(Some(READ_TIMEOUT))
             .map_err(|err| format!("timeout: {err}"))?;
         stream
             .set_write_timeout(Some(Duration::from_secs(10)))
@@ -139,31 +321,219 @@ impl McpClient {
     }
 
     fn send(&mut self, method: &str, params: Value) -> Result<Value, String> {
+        self.send_detailed(method, params)
+            .map_err(|failure| failure.message)
+    }
+
+    /// Like `send`, keeping the error category reported by the tool.
+    fn send_detailed(&mut self, method: &str, params: Value) -> Result<Value, ToolFailure> {
+        let failure = |message: String| ToolFailure {
+            message,
+            category: None,
+        };
         let id = self.next_id;
         self.next_id += 1;
         let request = json!({ "id": id.to_string(), "method": method, "params": params });
         self.writer
             .write_all(format!("{request}\n").as_bytes())
-            .map_err(|err| format!("mcp write: {err}"))?;
+            .map_err(|err| failure(format!("mcp write: {err}")))?;
         self.writer
             .flush()
-            .map_err(|err| format!("mcp flush: {err}"))?;
+            .map_err(|err| failure(format!("mcp flush: {err}")))?;
 
         let mut line = String::new();
         self.reader
             .read_line(&mut line)
-            .map_err(|err| format!("mcp read: {err}"))?;
-        let response: Value =
-            serde_json::from_str(line.trim()).map_err(|err| format!("mcp parse: {err}"))?;
+            .map_err(|err| failure(format!("mcp read: {err}")))?;
+        let response: Value = serde_json::from_str(line.trim())
+            .map_err(|err| failure(format!("mcp parse: {err}")))?;
         if let Some(error) = response.get("error") {
-            return Err(error
-                .get("message")
-                .and_then(|v| v.as_str())
-                .unwrap_or("unknown mcp error")
-                .to_string());
+            return Err(ToolFailure {
+                message: error
+                    .get("message")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("unknown mcp error")
+                    .to_string(),
+                category: error
+                    .get("category")
+                    .and_then(|v| v.as_str())
+                    .map(str::to_string),
+            });
         }
         Ok(response.get("result").cloned().unwrap_or(Value::Null))
     }
+
+    /// Sends a request whose reply may take longer than the default read timeout.
+    fn send_with_timeout(
+        &mut self,
+        method: &str,
+        params: Value,
+        timeout: Duration,
+    ) -> Result<Value, String> {
+        self.writer
+            .set_read_timeout(Some(timeout))
+            .map_err(|err| format!("timeout: {err}"))?;
+        let result = self.send(method, params);
+        self.writer
+            .set_read_timeout(Some(READ_TIMEOUT))
+            .map_err(|err| format!("timeout: {err}"))?;
+        result
+    }
+}
+
+/// Embeds the listing and compares it to a profile's text; `None` if the tool is unavailable.
+fn semantic_similarity(
+    client: &mut McpClient,
+    listing_text: &str,
+    reference: &str,
+) -> Option<(f64, String)> {
+    let result = client
+        .send(
+            "call_tool",
+            json!({
+              "name": "semantic_similarity",
+              "arguments": { "text": listing_text, "reference": reference }
+            }),
+        )
+        .map_err(|err| eprintln!("semantic similarity failed: {err}"))
+        .ok()?;
+    let similarity = result.get("similarity").and_then(|v| v.as_f64())?;
+    let content_hash = result.get("contentHash").and_then(|v| v.as_str())?;
+    Some((similarity, content_hash.to_string()))
+}
+
+/// Loads a profile's learned weights, falling back to the defaults.
+fn scoring_weights(client: &mut McpClient, profile: &str) -> ScoreWeights {
+    client
+        .send(
+            "call_tool",
+            json!({ "name": "get_scoring_weights", "arguments": { "profile": profile } }),
+        )
+        .ok()
+        .and_then(|value| value.get("effective").cloned())
+        .and_then(|value| serde_json::from_value(value).ok())
+        .unwrap_or_else(scoring::default_weights)
+}
+
+/// Returns the per-call timeout for the LLM stage, or `None` when the stage is disabled.
+fn llm_stage_timeout(client: &mut McpClient) -> Option<Duration> {
+    let config = client
+        .send(
+            "call_tool",
+            json!({ "name": "get_llm_settings", "arguments": {} }),
+        )
+        .ok()?;
+    if !config
+        .get("enabled")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false)
+    {
+        return None;
+    }
+    let timeout_secs = config
+        .get("timeoutSecs")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(60);
+    Some(Duration::from_secs(timeout_secs + 5))
+}
+
+fn llm_complete(
+    client: &mut McpClient,
+    system: &str,
+    prompt: String,
+    timeout: Duration,
+) -> Result<Value, String> {
+    let result = client.send_with_timeout(
+        "call_tool",
+        json!({ "name": "llm_complete", "arguments": { "system": system, "prompt": prompt } }),
+        timeout,
+    )?;
+    result
+        .get("content")
+        .cloned()
+        .ok_or_else(|| "llm_complete returned no content".to_string())
+}
+
+fn run_llm_extraction(
+    client: &mut McpClient,
+    listing_text: &str,
+    timeout: Duration,
+) -> Result<LlmExtraction, String> {
+    let content = llm_complete(
+        client,
+        llm::EXTRACTION_SYSTEM_PROMPT,
+        llm::extraction_prompt(listing_text),
+        timeout,
+    )?;
+    llm::parse_extraction(content)
+}
+
+fn run_llm_fit(
+    client: &mut McpClient,
+    listing_text: &str,
+    settings: &JobSettings,
+    timeout: Duration,
+) -> Result<LlmFit, String> {
+    let content = llm_complete(
+        client,
+        llm::FIT_SYSTEM_PROMPT,
+        llm::fit_prompt(listing_text, &json!(settings)),
+        timeout,
+    )?;
+    llm::parse_fit(content)
+}
+
+fn apply_llm_extraction(extracted: &mut ExtractedListing, value: &LlmExtraction) {
+    let non_empty = |field: &Option<String>| {
+        field
+            .as_ref()
+            .map(|text| text.trim().to_string())
+            .filter(|text| !text.is_empty())
+    };
+    if let Some(title) = non_empty(&value.title) {
+        extracted.title = Some(title);
+    }
+    if let Some(company) = non_empty(&value.company) {
+        extracted.company = Some(company);
+    }
+    if let Some(location) = non_empty(&value.location) {
+        extracted.location = Some(location);
+    }
+    if let Some(arrangement) = non_empty(&value.arrangement) {
+        let arrangement = arrangement.to_lowercase();
+        if matches!(arrangement.as_str(), "remote" | "hybrid" | "onsite") {
+            extracted.arrangement = Some(arrangement);
+        }
+    }
+}
+
+/// Extracts a listing from a complete page the way an analysis would, using the `<title>` and
+/// the same text preview that `fetch_content` returns.
+pub fn extract_page(html: &str) -> ExtractedListing {
+    let document = Html::parse_document(html);
+    let title = Selector::parse("title")
+        .ok()
+        .and_then(|selector| {
+            document
+                .select(&selector)
+                .next()
+                .map(|node| node.text().collect::<String>())
+        })
+        .unwrap_or_default();
+    let text = document_text(html).unwrap_or_default();
+    let preview = truncate::at_sentence_boundary(&text, mcp::TEXT_EXCERPT_BYTES);
+    extract_listing(html, preview, &title)
+}
+
+fn document_text(html: &str) -> Option<String> {
+    let document = Html::parse_document(html);
+    let text_raw = document.root_element().text().collect::<Vec<_>>().join(" ");
+    let text = text::collapse_whitespace(&text_raw);
+    if text.is_empty() {
+        None
+    } else {
+        Some(text)
+    }
 }
 
 struct AnalysisResult {
@@ -174,19 +544,11 @@ struct AnalysisResult {
     summary: String,
     match_score: f64,
     raw_excerpt: Option<String>,
-}
-
-struct ExtractedListing {
-    title: Option<String>,
-    company: Option<String>,
-    location: Option<String>,
-    text: String,
-    raw_excerpt: Option<String>,
-}
-
-struct MatchResult {
-    summary: String,
-    match_score: f64,
+    insights: Option<Value>,
+    content_hash: Option<String>,
+    score_breakdown: scoring::ScoreBreakdown,
+    profile_scores: Vec<ProfileScore>,
+    rejection: Option<scoring::Rejection>,
 }
 
 fn extract_listing(html: &str, text: &str, default_title: &str) -> ExtractedListing {
@@ -212,12 +574,14 @@ fn extract_listing(html: &str, text: &str, default_title: &str) -> ExtractedList
     });
 
     let location = extract_location(text);
-    let excerpt = if text.len() > 400 {
-        Some(text[..400].to_string())
-    } else if text.is_empty() {
+    // Salary and arrangement often sit below the fold, past the fetched text preview.
+    let full_text = document_text(html);
+    let detail_text = full_text.as_deref().unwrap_or(text);
+    let salary = extract_salary(detail_text);
+    let excerpt = if text.is_empty() {
         None
     } else {
-        Some(text.to_string())
+        Some(truncate::at_sentence_boundary(text, 400).to_string())
     };
 
     ExtractedListing {
@@ -226,72 +590,13 @@ fn extract_listing(html: &str, text: &str, default_title: &str) -> ExtractedList
         location,
         text: text.to_string(),
         raw_excerpt: excerpt,
-    }
-}
-
-fn match_listing(extracted: &ExtractedListing, settings: &JobSettings) -> MatchResult {
-    let text_lower = extracted.text.to_lowercase();
-    let mut hits = 0.0;
-    for keyword in &settings.keywords {
-        if text_lower.contains(&keyword.to_lowercase()) {
-            hits += 1.0;
-        }
-    }
-    let mut score = if settings.keywords.is_empty() {
-        50.0
-    } else {
-        (hits / settings.keywords.len() as f64) * 100.0
-    };
-
-    if let Some(title_value) = &extracted.title {
-        let title_lower = title_value.to_lowercase();
-        if settings
-            .preferred_titles
-            .iter()
-            .any(|value| !value.is_empty() && title_lower.contains(&value.to_lowercase()))
-        {
-            score += 10.0;
-        }
-    }
-
-    if let Some(location_value) = &extracted.location {
-        let location_lower = location_value.to_lowercase();
-        if settings
-            .locations
-            .iter()
-            .any(|value| !value.is_empty() && location_lower.contains(&value.to_lowercase()))
-        {
-            score += 6.0;
-        }
-    }
-
-    if settings.remote_only && text_lower.contains("remote") {
-        score += 8.0;
-    }
-    if let Some(company_name) = &extracted.company {
-        if settings
-            .company_blacklist
-            .iter()
-            .any(|c| !c.is_empty() && company_name.to_lowercase().contains(&c.to_lowercase()))
-        {
-            score -= 15.0;
-        }
-    }
-    score = score.clamp(0.0, 100.0);
-
-    let summary = format!(
-        "Matched {:.0}% of keywords. Remote preference: {}. Title signal: {}.",
-        score,
-        if settings.remote_only { "on" } else { "off" },
-        extracted
-            .title
-            .clone()
-            .unwrap_or_else(|| "unknown".to_string())
-    );
-
-    MatchResult {
-        summary,
-        match_score: score,
+        arrangement: extract_arrangement(detail_text),
+        employment_type: extract_employment_type(detail_text),
+        salary_min: salary.as_ref().map(|salary| salary.0),
+        salary_max: salary.as_ref().map(|salary| salary.1),
+        salary_currency: salary.and_then(|salary| salary.2),
+        visa_sponsorship: extract_visa_sponsorship(detail_text),
+        timezone: timezone::extract_requirement(detail_text),
     }
 }
 
@@ -333,3 +638,254 @@ fn extract_location(text: &str) -> Option<String> {
         Some(location.to_string())
     }
 }
+
+/// Finds a salary range such as `$120k - $150k` or `EUR 80.000 – 95.000`; returns min, max and
+/// currency. Amounts below 10,000 are ignored since they are usually hourly rates or noise.
+fn extract_salary(text: &str) -> Option<(i64, i64, Option<String>)> {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    let regex = PATTERN.get_or_init(|| {
+        Regex::new(
+            r"(?i)(\$|€|£|USD|EUR|GBP|CAD|AUD)?\s?(\d{2,3}(?:[,.]\d{3})*|\d+)\s?(k)?\s*(?:-|–|—|to)\s*(\$|€|£|USD|EUR|GBP|CAD|AUD)?\s?(\d{2,3}(?:[,.]\d{3})*|\d+)\s?(k)?",
+        )
+        .expect("salary pattern")
+    });
+    for caps in regex.captures_iter(text) {
+        let currency = caps.get(1).or_else(|| caps.get(4)).map(|m| m.as_str());
+        let thousands = caps.get(3).is_some() || caps.get(6).is_some();
+        if currency.is_none() && !thousands {
+            continue;
+        }
+        let parse = |digits: &str, suffix: bool| -> Option<i64> {
+            let value: i64 = digits.replace([',', '.'], "").parse().ok()?;
+            Some(if suffix || (thousands && value < 1000) {
+                value * 1000
+            } else {
+                value
+            })
+        };
+        let (Some(min), Some(max)) = (
+            parse(caps.get(2)?.as_str(), caps.get(3).is_some()),
+            parse(caps.get(5)?.as_str(), caps.get(6).is_some()),
+        ) else {
+            continue;
+        };
+        if min < 10_000 || max < min {
+            continue;
+        }
+        let currency = currency.map(|symbol| {
+            match symbol {
+                "$" => "USD",
+                "€" => "EUR",
+                "£" => "GBP",
+                other => other,
+            }
+            .to_uppercase()
+        });
+        return Some((min, max, currency));
+    }
+    None
+}
+
+/// Labels such as `Workplace type: Hybrid` or `Location type - Remote`.
+fn arrangement_label_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| {
+        Regex::new(
+            r"(?i)\b(?:workplace|work|location|job|position)\s+(?:type|arrangement|model|setup)\s*[:\-–]\s*(hybrid|remote|on-?site|in[- ]office|office[- ]based)\b",
+        )
+        .expect("arrangement label pattern")
+    })
+}
+
+/// Arrangement words, with an optional negation before them (`no remote work`) and an
+/// optional noun after them that makes the mention about other openings (`remote roles`).
+fn arrangement_mention_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| {
+        Regex::new(
+            r"(?i)\b(?P<negation>(?:no|not|non|without|never)[\s-]+(?:\w+\s+)?)?(?P<kind>hybrid|remote(?:ly)?|on-?site|in[- ]office|office[- ]based)\b(?P<others>[\s-]+(?:roles|positions|jobs|opportunities|openings|vacancies))?",
+        )
+        .expect("arrangement mention pattern")
+    })
+}
+
+fn arrangement_kind(word: &str) -> &'static str {
+    let word = word.to_lowercase();
+    if word == "hybrid" {
+        "hybrid"
+    } else if word.starts_with("remote") {
+        "remote"
+    } else {
+        "onsite"
+    }
+}
+
+/// An explicit label wins. Otherwise mentions are used, skipping negated ones and those about
+/// other openings; hybrid wins over the remote and office days it describes, and a page that
+/// mentions both remote and onsite work is left unknown.
+fn extract_arrangement(text: &str) -> Option<String> {
+    if let Some(caps) = arrangement_label_pattern().captures(text) {
+        return Some(arrangement_kind(&caps[1]).to_string());
+    }
+    let mut kinds = Vec::new();
+    for caps in arrangement_mention_pattern().captures_iter(text) {
+        if caps.name("negation").is_some() || caps.name("others").is_some() {
+            continue;
+        }
+        let kind = arrangement_kind(&caps["kind"]);
+        if !kinds.contains(&kind) {
+            kinds.push(kind);
+        }
+    }
+    let arrangement = if kinds.contains(&"hybrid") {
+        "hybrid"
+    } else {
+        match kinds.as_slice() {
+            [kind] => kind,
+            _ => return None,
+        }
+    };
+    Some(arrangement.to_string())
+}
+
+fn extract_visa_sponsorship(text: &str) -> Option<bool> {
+    static DENIED: OnceLock<Regex> = OnceLock::new();
+    static OFFERED: OnceLock<Regex> = OnceLock::new();
+    let denied = DENIED.get_or_init(|| {
+        Regex::new(
+            r"(?i)\b(no|not|unable to|cannot|can't|won't|will not|does not|do not)\s+(offer\s+|provide\s+)?(visa\s+)?sponsor",
+        )
+        .expect("sponsorship denied pattern")
+    });
+    if denied.is_match(text) {
+        return Some(false);
+    }
+    let offered = OFFERED.get_or_init(|| {
+        Regex::new(
+            r"(?i)\b(visa sponsorship (is )?(available|offered|provided)|will sponsor|sponsorship available)",
+        )
+        .expect("sponsorship offered pattern")
+    });
+    offered.is_match(text).then_some(true)
+}
+
+/// Labels such as `Employment type: Contract` or `Job type - Full-time`.
+fn employment_label_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| {
+        Regex::new(
+            r"(?i)\b(?:employment|job|contract|position|work|engagement)\s+type\s*[:\-–]\s*(full[- ]?time|permanent|part[- ]?time|contract(?:or)?|freelance|intern(?:ship)?|temporary|temp|fixed[- ]term)\b",
+        )
+        .expect("employment label pattern")
+    })
+}
+
+/// Whole-word mentions per type, in order of preference. Bare words that often mean something
+/// else (`smart contract`, `temporary access`, `permanent residency`) need a job noun after them.
+fn employment_mention_patterns() -> &'static [(&'static str, Regex)] {
+    static PATTERNS: OnceLock<Vec<(&'static str, Regex)>> = OnceLock::new();
+    PATTERNS.get_or_init(|| {
+        [
+            (
+                "full-time",
+                r"\bfull[- ]?time\b|\bpermanent\s+(?:role|position|job|employment|contract)\b",
+            ),
+            ("part-time", r"\bpart[- ]?time\b"),
+            (
+                "contract",
+                r"\bcontract(?:or)?\s+(?:role|position|job|basis|assignment|engagement|opportunity)\b|\bcontract[- ]to[- ]hire\b|\bon\s+a\s+contract\b|\bfreelance(?:r)?\b",
+            ),
+            ("internship", r"\binternships?\b|\bintern\b"),
+            (
+                "temporary",
+                r"\btemporary\s+(?:role|position|job|contract|assignment|employment)\b|\bfixed[- ]term\b",
+            ),
+        ]
+        .into_iter()
+        .map(|(kind, pattern)| {
+            let regex = Regex::new(&format!("(?i){pattern}")).expect("employment pattern");
+            (kind, regex)
+        })
+        .collect()
+    })
+}
+
+/// An explicit label wins over mentions in the body text.
+fn extract_employment_type(text: &str) -> Option<String> {
+    if let Some(caps) = employment_label_pattern().captures(text) {
+        let label = caps[1].to_lowercase();
+        let kind = if label.starts_with("full") || label == "permanent" {
+            "full-time"
+        } else if label.starts_with("part") {
+            "part-time"
+        } else if label.starts_with("contract") || label == "freelance" {
+            "contract"
+        } else if label.starts_with("intern") {
+            "internship"
+        } else {
+            "temporary"
+        };
+        return Some(kind.to_string());
+    }
+    employment_mention_patterns()
+        .iter()
+        .find(|(_, regex)| regex.is_match(text))
+        .map(|(kind, _)| kind.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn employment_type_matches_whole_words() {
+        for text in [
+            "We audit smart contracts for DeFi teams.",
+            "Contractual obligations and contract review are part of the job.",
+            "Build internal tools.",
+            "You will get temporary access to production.",
+            "Permanent residency is not required.",
+        ] {
+            assert_eq!(extract_employment_type(text), None, "{text}");
+        }
+        assert_eq!(
+            extract_employment_type("This is a contract role for six months."),
+            Some("contract".to_string())
+        );
+        assert_eq!(
+            extract_employment_type("Summer intern wanted"),
+            Some("internship".to_string())
+        );
+    }
+
+    #[test]
+    fn employment_label_wins_over_body_text() {
+        let text = "Employment type: Contract\nMost of our team works full-time.";
+        assert_eq!(extract_employment_type(text), Some("contract".to_string()));
+        assert_eq!(
+            extract_employment_type("Job type - Part time"),
+            Some("part-time".to_string())
+        );
+    }
+
+    #[test]
+    fn visa_sponsorship_reads_offers_and_refusals() {
+        assert_eq!(
+            extract_visa_sponsorship("Unfortunately we do not offer visa sponsorship."),
+            Some(false)
+        );
+        assert_eq!(
+            extract_visa_sponsorship("No sponsorship is available for this role."),
+            Some(false)
+        );
+        assert_eq!(
+            extract_visa_sponsorship("Visa sponsorship available for the right candidate."),
+            Some(true)
+        );
+        assert_eq!(
+            extract_visa_sponsorship("We will sponsor your visa."),
+            Some(true)
+        );
+        assert_eq!(extract_visa_sponsorship("Great benefits."), None);
+    }
+}
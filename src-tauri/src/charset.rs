@@ -0,0 +1,97 @@
+use chardetng::EncodingDetector;
+use encoding_rs::{Encoding, UTF_8};
+use regex::bytes::Regex;
+use std::sync::OnceLock;
+
+/// How far into the body to look for a `<meta>` charset, as browsers do.
+const META_PRESCAN_BYTES: usize = 4096;
+
+/// A decoded body and the encoding it was read as.
+pub struct Decoded {
+    pub text: String,
+    /// WHATWG name of the encoding, such as `UTF-8` or `Shift_JIS`.
+    pub encoding: &'static str,
+}
+
+fn meta_charset_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| {
+        // Matches both `<meta charset="...">` and `<meta http-equiv="Content-Type" content="...; charset=...">`.
+        Regex::new(r#"(?i)<meta\b[^>]*?charset\s*=\s*["']?\s*([a-z0-9_:.\-]+)"#)
+            .expect("meta charset pattern")
+    })
+}
+
+/// The charset named in a `Content-Type` header.
+fn header_encoding(content_type: Option<&str>) -> Option<&'static Encoding> {
+    let content_type = content_type?;
+    content_type.split(';').skip(1).find_map(|param| {
+        let (name, value) = param.split_once('=')?;
+        if !name.trim().eq_ignore_ascii_case("charset") {
+            return None;
+        }
+        Encoding::for_label(value.trim().trim_matches(['"', '\'']).as_bytes())
+    })
+}
+
+fn meta_encoding(bytes: &[u8]) -> Option<&'static Encoding> {
+    let head = &bytes[..bytes.len().min(META_PRESCAN_BYTES)];
+    let label = meta_charset_pattern().captures(head)?.get(1)?;
+    let encoding = Encoding::for_label(label.as_bytes())?;
+    // A page can only declare an ASCII-compatible encoding from inside itself; UTF-16 labels
+    // in a meta tag mean UTF-8, per the HTML spec.
+    Some(if encoding.is_ascii_compatible() {
+        encoding
+    } else {
+        UTF_8
+    })
+}
+
+/// Guesses the encoding from byte statistics, using the host's top-level domain as a hint
+/// (`.jp` favors Shift_JIS, `.kr` EUC-KR).
+fn detected_encoding(bytes: &[u8], host: Option<&str>) -> &'static Encoding {
+    let mut detector = EncodingDetector::new();
+    detector.feed(bytes, true);
+    let tld = host.and_then(|host| host.rsplit('.').next());
+    detector.guess(tld.map(str::as_bytes), true)
+}
+
+/// Decodes a page body, choosing the encoding from the byte-order mark, then the
+/// `Content-Type` charset, then a `<meta>` charset, then valid UTF-8, then statistical detection.
+/// `truncated` bodies may end mid-character; the incomplete tail is dropped.
+pub fn decode(
+    bytes: &[u8],
+    content_type: Option<&str>,
+    host: Option<&str>,
+    truncated: bool,
+) -> Decoded {
+    let (encoding, bom_length) = match Encoding::for_bom(bytes) {
+        Some((encoding, length)) => (encoding, length),
+        None => {
+            let encoding = header_encoding(content_type)
+                .or_else(|| meta_encoding(bytes))
+                .unwrap_or_else(|| match std::str::from_utf8(bytes) {
+                    Ok(_) => UTF_8,
+                    // Valid UTF-8 cut off mid-character by the byte cap.
+                    Err(err) if truncated && err.error_len().is_none() => UTF_8,
+                    Err(_) => detected_encoding(bytes, host),
+                });
+            (encoding, 0)
+        }
+    };
+
+    let body = &bytes[bom_length..];
+    let mut decoder = encoding.new_decoder_without_bom_handling();
+    let mut text = String::with_capacity(
+        decoder
+            .max_utf8_buffer_length(body.len())
+            .unwrap_or(body.len()),
+    );
+    // Not marking the input as last leaves a cut-off trailing character pending instead of
+    // turning it into a replacement character.
+    let _ = decoder.decode_to_string(body, &mut text, !truncated);
+    Decoded {
+        text,
+        encoding: encoding.name(),
+    }
+}
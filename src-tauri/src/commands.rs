@@ -1,9 +1,28 @@
-use crate::db::JobMatch;
-use crate::settings::{load_settings, save_settings, JobSettings};
+use crate::analysis_agent;
+use crate::crawler::{self, CrawlSummary};
+use crate::credentials::{CookieFormat, DomainCredentials, ImportSummary};
+use crate::db::{
+    Decision, Feed, JobMatch, ListingChange, MatchQuery, MatchSort, ScoreRecord, SimilarJob,
+    Snapshot,
+};
+use crate::feedback::{self, Rating, WeightsReport};
+use crate::feeds::{self, PollSummary};
+use crate::rescore::{self, RescoreSummary};
+use crate::settings::{
+    load_archive_settings, load_crawl_settings, load_feed_settings, load_llm_settings,
+    load_network_settings, load_profiles, load_settings, load_watch_settings,
+    save_archive_settings, save_crawl_settings, save_feed_settings, save_llm_settings,
+    save_network_settings, save_settings, save_watch_settings, update_profiles, ArchiveSettings,
+    CrawlSettings, FeedSettings, JobSettings, LlmSettings, NetworkSettings, ProfileStore,
+    WatchSettings,
+};
+use crate::skills::{self, ReportFormat, ReportOutput, SkillReportOptions};
+use crate::snapshots::{self, Reextraction};
 use crate::state::AppState;
+use crate::watch::{self, WatchSummary};
 use serde::Serialize;
-use tauri::{AppHandle, Emitter, State};
-use uuid::Uuid;
+use std::collections::BTreeMap;
+use tauri::{AppHandle, State};
 
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -22,13 +41,395 @@ pub fn update_settings(app: AppHandle, settings: JobSettings) -> Result<JobSetti
     save_settings(&app, &settings)
 }
 
+#[tauri::command]
+pub fn get_llm_settings(app: AppHandle) -> Result<LlmSettings, String> {
+    load_llm_settings(&app)
+}
+
+#[tauri::command]
+pub fn update_llm_settings(app: AppHandle, settings: LlmSettings) -> Result<LlmSettings, String> {
+    save_llm_settings(&app, &settings)
+}
+
+#[tauri::command]
+pub fn get_network_settings(app: AppHandle) -> Result<NetworkSettings, String> {
+    load_network_settings(&app)
+}
+
+/// Applies the network settings to the shared HTTP client, then saves them, so a proxy or CA
+/// bundle that does not load is never stored.
+#[tauri::command]
+pub fn update_network_settings(
+    app: AppHandle,
+    state: State<AppState>,
+    settings: NetworkSettings,
+) -> Result<NetworkSettings, String> {
+    state.http.configure(&settings)?;
+    save_network_settings(&app, &settings)
+}
+
+/// Imports cookies from a Netscape `cookies.txt` or JSON export; the format is detected when
+/// not given.
+#[tauri::command]
+pub fn import_cookies(
+    state: State<AppState>,
+    content: String,
+    format: Option<CookieFormat>,
+) -> Result<ImportSummary, String> {
+    state.http.credentials().import_cookies(&content, format)
+}
+
+/// Replaces the extra headers sent to a domain and its subdomains; an empty map removes them.
+#[tauri::command]
+pub fn set_domain_headers(
+    state: State<AppState>,
+    domain: String,
+    headers: BTreeMap<String, String>,
+) -> Result<(), String> {
+    state.http.credentials().set_headers(&domain, headers)
+}
+
+/// Forgets stored cookies and headers for one domain, or for all of them.
+#[tauri::command]
+pub fn clear_credentials(state: State<AppState>, domain: Option<String>) -> Result<(), String> {
+    state.http.credentials().clear(domain.as_deref())
+}
+
+#[tauri::command]
+pub fn list_credential_domains(state: State<AppState>) -> Result<Vec<DomainCredentials>, String> {
+    state.http.credentials().domains()
+}
+
+#[tauri::command]
+pub fn get_archive_settings(app: AppHandle) -> Result<ArchiveSettings, String> {
+    load_archive_settings(&app)
+}
+
+/// Saves the archive settings and prunes the archive to the new size limit right away.
+#[tauri::command]
+pub fn update_archive_settings(
+    app: AppHandle,
+    state: State<AppState>,
+    settings: ArchiveSettings,
+) -> Result<ArchiveSettings, String> {
+    let saved = save_archive_settings(&app, &settings)?;
+    state.db.prune_snapshots(saved.max_total_bytes)?;
+    Ok(saved)
+}
+
+/// Loads an archived page by snapshot id, or the latest one behind a match.
+#[tauri::command]
+pub fn get_snapshot(
+    state: State<AppState>,
+    snapshot_id: Option<String>,
+    match_id: Option<String>,
+) -> Result<Snapshot, String> {
+    snapshots::load(&state.db, snapshot_id.as_deref(), match_id.as_deref(), None)
+}
+
+#[tauri::command]
+pub fn get_watch_settings(app: AppHandle) -> Result<WatchSettings, String> {
+    load_watch_settings(&app)
+}
+
+#[tauri::command]
+pub fn update_watch_settings(
+    app: AppHandle,
+    settings: WatchSettings,
+) -> Result<WatchSettings, String> {
+    save_watch_settings(&app, &settings)
+}
+
+/// Refetches one match, or every watched match now, regardless of when it was last checked.
+#[tauri::command]
+pub async fn check_listing_changes(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    match_id: Option<String>,
+) -> Result<WatchSummary, String> {
+    watch::check(&app, &state.db, &state.http, match_id.as_deref(), None).await
+}
+
+/// Lists recorded listing changes, newest first, for one match or all of them.
+#[tauri::command]
+pub fn get_listing_changes(
+    state: State<AppState>,
+    match_id: Option<String>,
+    limit: Option<usize>,
+) -> Result<Vec<ListingChange>, String> {
+    state
+        .db
+        .listing_changes(match_id.as_deref(), limit.unwrap_or(50))
+}
+
+#[tauri::command]
+pub fn get_feed_settings(app: AppHandle) -> Result<FeedSettings, String> {
+    load_feed_settings(&app)
+}
+
+#[tauri::command]
+pub fn update_feed_settings(
+    app: AppHandle,
+    settings: FeedSettings,
+) -> Result<FeedSettings, String> {
+    save_feed_settings(&app, &settings)
+}
+
+/// Subscribes to an RSS or Atom feed; existing items are only analyzed when asked.
+#[tauri::command]
+pub async fn add_feed(
+    state: State<'_, AppState>,
+    url: String,
+    analyze_existing: Option<bool>,
+) -> Result<Feed, String> {
+    feeds::add_feed(
+        &state.db,
+        &state.http,
+        &url,
+        analyze_existing.unwrap_or(false),
+    )
+    .await
+}
+
+/// Unsubscribes from a feed, given its id or URL.
+#[tauri::command]
+pub fn remove_feed(state: State<AppState>, feed: String) -> Result<(), String> {
+    state.db.delete_feed(&feed)
+}
+
+#[tauri::command]
+pub fn list_feeds(state: State<AppState>) -> Result<Vec<Feed>, String> {
+    state.db.list_feeds(None)
+}
+
+/// Polls one feed, or all of them, now and analyzes new items.
+#[tauri::command]
+pub async fn poll_feeds(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    feed: Option<String>,
+) -> Result<PollSummary, String> {
+    feeds::poll(
+        &app,
+        &state.db,
+        &state.http,
+        state.mcp_port,
+        feed.as_deref(),
+        None,
+    )
+    .await
+}
+
+#[tauri::command]
+pub fn get_crawl_settings(app: AppHandle) -> Result<CrawlSettings, String> {
+    load_crawl_settings(&app)
+}
+
+#[tauri::command]
+pub fn update_crawl_settings(
+    app: AppHandle,
+    settings: CrawlSettings,
+) -> Result<CrawlSettings, String> {
+    save_crawl_settings(&app, &settings)
+}
+
+/// Crawls a careers site for job postings and queues new ones for analysis unless `analyze`
+/// is false. Depth and page budget default to the crawl settings.
+#[tauri::command]
+pub async fn crawl_careers_site(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    url: String,
+    max_depth: Option<u32>,
+    max_pages: Option<usize>,
+    analyze: Option<bool>,
+) -> Result<CrawlSummary, String> {
+    let mut settings = load_crawl_settings(&app)?;
+    settings.max_depth = max_depth.unwrap_or(settings.max_depth);
+    settings.max_pages = max_pages.unwrap_or(settings.max_pages).max(1);
+    crawler::crawl(
+        &app,
+        &state.db,
+        &state.http,
+        state.mcp_port,
+        &url,
+        &settings,
+        analyze.unwrap_or(true),
+    )
+    .await
+}
+
+/// Re-runs extraction on an archived page, optionally saving it as the matches' listing.
+#[tauri::command]
+pub fn reextract_snapshot(
+    state: State<AppState>,
+    snapshot_id: Option<String>,
+    match_id: Option<String>,
+    save: Option<bool>,
+) -> Result<Reextraction, String> {
+    snapshots::reextract(
+        &state.db,
+        snapshot_id.as_deref(),
+        match_id.as_deref(),
+        save.unwrap_or(false),
+    )
+}
+
+#[tauri::command]
+pub fn list_profiles(app: AppHandle) -> Result<ProfileStore, String> {
+    load_profiles(&app)
+}
+
+#[tauri::command]
+pub fn create_profile(
+    app: AppHandle,
+    name: String,
+    settings: Option<JobSettings>,
+) -> Result<ProfileStore, String> {
+    update_profiles(&app, |profiles| profiles.create(&name, settings))
+}
+
+#[tauri::command]
+pub fn rename_profile(
+    app: AppHandle,
+    state: State<AppState>,
+    name: String,
+    new_name: String,
+) -> Result<ProfileStore, String> {
+    let profiles = update_profiles(&app, |profiles| profiles.rename(&name, &new_name))?;
+    state.db.rename_profile(&name, new_name.trim())?;
+    Ok(profiles)
+}
+
+#[tauri::command]
+pub fn delete_profile(
+    app: AppHandle,
+    state: State<AppState>,
+    name: String,
+) -> Result<ProfileStore, String> {
+    let profiles = update_profiles(&app, |profiles| profiles.delete(&name))?;
+    state.db.delete_profile(&name)?;
+    Ok(profiles)
+}
+
+#[tauri::command]
+pub fn activate_profile(app: AppHandle, name: String) -> Result<ProfileStore, String> {
+    update_profiles(&app, |profiles| profiles.activate(&name))
+}
+
 #[tauri::command]
 pub fn list_job_matches(
     state: State<AppState>,
     limit: Option<usize>,
+    profile: Option<String>,
+    sort: Option<MatchSort>,
+    include_rejected: Option<bool>,
 ) -> Result<Vec<JobMatch>, String> {
-    let limit = limit.unwrap_or(50);
-    state.db.list_matches(limit)
+    state.db.list_matches(&MatchQuery {
+        limit: limit.unwrap_or(50),
+        profile,
+        sort: sort.unwrap_or_default(),
+        include_rejected: include_rejected.unwrap_or(false),
+    })
+}
+
+/// Rescores every stored match with the current settings; progress is reported via events.
+#[tauri::command]
+pub async fn rescore_matches(
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<RescoreSummary, String> {
+    let db = state.db.clone();
+    tauri::async_runtime::spawn_blocking(move || rescore::rescore_all(&app, &db))
+        .await
+        .map_err(|err| format!("rescore task: {err}"))?
+}
+
+/// Lists every score recorded for a match, oldest first; all profiles unless one is given.
+#[tauri::command]
+pub fn get_score_history(
+    state: State<AppState>,
+    match_id: String,
+    profile: Option<String>,
+) -> Result<Vec<ScoreRecord>, String> {
+    state.db.score_history(&match_id, profile.as_deref())
+}
+
+/// Aggregates required skills across stored matches and flags those missing from the profile.
+#[tauri::command]
+pub fn skill_gap_report(
+    app: AppHandle,
+    state: State<AppState>,
+    options: Option<SkillReportOptions>,
+    format: Option<ReportFormat>,
+) -> Result<ReportOutput, String> {
+    skills::build_report(
+        &app,
+        &state.db,
+        &options.unwrap_or_default(),
+        format.unwrap_or_default(),
+    )
+}
+
+/// Accepts or rejects a match by hand; a `null` decision returns it to the filters' verdict.
+#[tauri::command]
+pub fn set_match_decision(
+    state: State<AppState>,
+    match_id: String,
+    decision: Option<Decision>,
+) -> Result<(), String> {
+    state.db.set_decision(&match_id, decision)
+}
+
+#[tauri::command]
+pub fn find_similar_jobs(
+    state: State<AppState>,
+    match_id: String,
+    limit: Option<usize>,
+) -> Result<Vec<SimilarJob>, String> {
+    let reference = state.db.match_embedding(&match_id)?;
+    state
+        .db
+        .find_similar(&reference, Some(&match_id), limit.unwrap_or(10))
+}
+
+#[tauri::command]
+pub fn rate_job_match(
+    app: AppHandle,
+    state: State<AppState>,
+    match_id: String,
+    rating: Rating,
+    profile: Option<String>,
+) -> Result<WeightsReport, String> {
+    let profile = resolve_profile(&app, profile)?;
+    feedback::rate_match(&state.db, &profile, &match_id, rating)
+}
+
+#[tauri::command]
+pub fn get_scoring_weights(
+    app: AppHandle,
+    state: State<AppState>,
+    profile: Option<String>,
+) -> Result<WeightsReport, String> {
+    let profile = resolve_profile(&app, profile)?;
+    feedback::weights_report(&state.db, &profile)
+}
+
+#[tauri::command]
+pub fn reset_scoring_weights(
+    app: AppHandle,
+    state: State<AppState>,
+    profile: Option<String>,
+) -> Result<WeightsReport, String> {
+    let profile = resolve_profile(&app, profile)?;
+    feedback::reset_weights(&state.db, &profile)
+}
+
+fn resolve_profile(app: &AppHandle, profile: Option<String>) -> Result<String, String> {
+    match profile {
+        Some(profile) => Ok(profile),
+        None => Ok(load_profiles(app)?.active),
+    }
 }
 
 #[tauri::command]
@@ -42,23 +443,7 @@ pub fn start_analysis(
     state: State<AppState>,
     url: String,
 ) -> Result<AnalysisStart, String> {
-    let analysis_id = Uuid::new_v4().to_string();
-    let exe = std::env::current_exe().map_err(|err| format!("locate executable: {err}"))?;
-
-    std::process::Command::new(exe)
-        .arg("--analysis-agent")
-        .env("JOB_HUNTER_MCP_PORT", state.mcp_port.to_string())
-        .env("JOB_HUNTER_TARGET_URL", url)
-        .env("JOB_HUNTER_ANALYSIS_ID", analysis_id.clone())
-        .spawn()
-        .map_err(|err| format!("spawn analysis agent: {err}"))?;
-
-    let analysis_id_for_emit = analysis_id.clone();
-    let _ = app.emit(
-        "analysis:started",
-        serde_json::json!({ "analysisId": analysis_id_for_emit, "mcpPort": state.mcp_port }),
-    );
-
+    let (analysis_id, _) = analysis_agent::launch(&app, state.mcp_port, &url, false)?;
     Ok(AnalysisStart {
         analysis_id,
         mcp_port: state.mcp_port,
@@ -0,0 +1,315 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
+
+/// Legal-form suffixes dropped before company names are compared.
+const LEGAL_SUFFIXES: &[&str] = &[
+    "inc",
+    "incorporated",
+    "llc",
+    "llp",
+    "ltd",
+    "limited",
+    "plc",
+    "corp",
+    "corporation",
+    "co",
+    "company",
+    "gmbh",
+    "ag",
+    "kg",
+    "se",
+    "sa",
+    "sas",
+    "sarl",
+    "srl",
+    "spa",
+    "bv",
+    "nv",
+    "ab",
+    "as",
+    "asa",
+    "oy",
+    "pty",
+    "pte",
+    "kk",
+];
+
+/// Words in a poster's name that mark it as a recruiting agency.
+const AGENCY_MARKERS: &[&str] = &[
+    "recruitment",
+    "recruiting",
+    "recruiters",
+    "staffing",
+    "headhunters",
+    "executive search",
+    "talent solutions",
+    "personnel",
+];
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Tier {
+    Dream,
+    Good,
+    Ok,
+}
+
+impl Tier {
+    pub fn value(self) -> f64 {
+        match self {
+            Tier::Dream => 1.0,
+            Tier::Good => 0.66,
+            Tier::Ok => 0.33,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Tier::Dream => "dream",
+            Tier::Good => "good",
+            Tier::Ok => "ok",
+        }
+    }
+}
+
+/// A parent company and the brands or former names that should be treated as it.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CompanyGroup {
+    pub name: String,
+    pub aliases: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct CompanyTiers {
+    pub dream: Vec<String>,
+    pub good: Vec<String>,
+    pub ok: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default, rename_all = "camelCase")]
+pub struct CompanyPreferences {
+    pub favorites: Vec<String>,
+    pub tiers: CompanyTiers,
+    pub groups: Vec<CompanyGroup>,
+    /// Recruiting agencies recognized by name, in addition to names containing agency words.
+    pub agencies: Vec<String>,
+}
+
+impl Default for CompanyPreferences {
+    fn default() -> Self {
+        let group = |name: &str, aliases: &[&str]| CompanyGroup {
+            name: name.to_string(),
+            aliases: aliases.iter().map(|alias| alias.to_string()).collect(),
+        };
+        Self {
+            favorites: Vec::new(),
+            tiers: CompanyTiers::default(),
+            groups: vec![
+                group(
+                    "Meta",
+                    &[
+                        "Meta Platforms",
+                        "Facebook",
+                        "Instagram",
+                        "WhatsApp",
+                        "Oculus",
+                    ],
+                ),
+                group("Alphabet", &["Google", "YouTube", "DeepMind", "Waymo"]),
+                group(
+                    "Amazon",
+                    &["AWS", "Amazon Web Services", "Twitch", "Audible"],
+                ),
+                group("Microsoft", &["LinkedIn", "GitHub"]),
+            ],
+            agencies: [
+                "Robert Half",
+                "Hays",
+                "Randstad",
+                "Adecco",
+                "Michael Page",
+                "ManpowerGroup",
+                "Kforce",
+                "TEKsystems",
+                "Harvey Nash",
+                "Aerotek",
+            ]
+            .iter()
+            .map(|name| name.to_string())
+            .collect(),
+        }
+    }
+}
+
+/// A posting company after alias resolution, with the client behind an agency posting.
+#[derive(Debug, Clone)]
+pub struct ResolvedCompany {
+    /// The name as posted.
+    pub posted: String,
+    /// Set when the poster is a recruiting agency.
+    pub agency: bool,
+    /// The hiring company named in an agency posting, if it could be found.
+    pub client: Option<String>,
+}
+
+impl ResolvedCompany {
+    /// Names preferences are checked against: the client first, then the poster.
+    fn candidates(&self) -> impl Iterator<Item = &str> {
+        self.client
+            .as_deref()
+            .into_iter()
+            .chain([self.posted.as_str()])
+    }
+}
+
+/// Lowercases, strips punctuation, a leading "the" and trailing legal suffixes, so
+/// `The Acme Co., Inc.` and `ACME GmbH` both become `acme`.
+pub fn normalize(name: &str) -> String {
+    let lowered = name.to_lowercase().replace('&', " and ");
+    let mut words: Vec<&str> = lowered
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect();
+    if words.len() > 1 && words[0] == "the" {
+        words.remove(0);
+    }
+    while words.len() > 1
+        && words
+            .last()
+            .is_some_and(|word| LEGAL_SUFFIXES.contains(word))
+    {
+        words.pop();
+    }
+    words.join(" ")
+}
+
+impl CompanyPreferences {
+    /// Maps a name to its group's normalized name when it is an alias, else to its own.
+    pub fn canonical(&self, name: &str) -> String {
+        let normalized = normalize(name);
+        self.groups
+            .iter()
+            .find(|group| {
+                normalize(&group.name) == normalized
+                    || group
+                        .aliases
+                        .iter()
+                        .any(|alias| normalize(alias) == normalized)
+            })
+            .map(|group| normalize(&group.name))
+            .unwrap_or(normalized)
+    }
+
+    /// Whether `company` is `entry` or in the same group. A shared first word is not enough,
+    /// since `Meta Materials` is not Meta; divisions count only when listed as aliases.
+    pub fn matches(&self, company: &str, entry: &str) -> bool {
+        if normalize(entry).is_empty() {
+            return false;
+        }
+        self.canonical(company) == self.canonical(entry)
+    }
+
+    /// The first entry of `list` the resolved company matches.
+    pub fn find_in<'a>(&self, company: &ResolvedCompany, list: &'a [String]) -> Option<&'a str> {
+        company.candidates().find_map(|name| {
+            list.iter()
+                .find(|entry| self.matches(name, entry))
+                .map(String::as_str)
+        })
+    }
+
+    pub fn tier(&self, company: &ResolvedCompany) -> Option<Tier> {
+        [
+            (Tier::Dream, &self.tiers.dream),
+            (Tier::Good, &self.tiers.good),
+            (Tier::Ok, &self.tiers.ok),
+        ]
+        .into_iter()
+        .find(|(_, list)| self.find_in(company, list).is_some())
+        .map(|(tier, _)| tier)
+    }
+
+    pub fn is_agency(&self, name: &str) -> bool {
+        let lowered = name.to_lowercase();
+        AGENCY_MARKERS.iter().any(|marker| lowered.contains(marker))
+            || self
+                .agencies
+                .iter()
+                .any(|agency| self.matches(name, agency))
+    }
+
+    /// Resolves the posting company, looking for the hiring client when an agency posted it.
+    pub fn resolve(&self, posted: &str, text: &str) -> ResolvedCompany {
+        let agency = self.is_agency(posted);
+        ResolvedCompany {
+            posted: posted.to_string(),
+            agency,
+            client: if agency { agency_client(text) } else { None },
+        }
+    }
+}
+
+/// Finds the client an agency recruits for, e.g. "on behalf of Acme Ltd, a ..." or
+/// "our client, Acme, is ...". Anonymous clients ("our client, a leading fintech") yield `None`.
+fn agency_client(text: &str) -> Option<String> {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    let regex = PATTERN.get_or_init(|| {
+        Regex::new(
+            r"(?:(?i:on behalf of|our client,?|recruiting for|hiring for))\s+([A-Z][\w&'.\-]*(?:\s+[A-Z][\w&'.\-]*){0,4})",
+        )
+        .expect("agency client pattern")
+    });
+    let client = regex.captures_iter(text).find_map(|caps| {
+        let name = caps.get(1)?.as_str().trim_end_matches(['.', ',']).trim();
+        (!name.is_empty()).then(|| name.to_string())
+    });
+    client
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn legal_suffixes_are_stripped() {
+        assert_eq!(normalize("Acme Inc."), "acme");
+        assert_eq!(normalize("ACME GmbH"), "acme");
+        assert_eq!(normalize("The Acme Co., Ltd"), "acme");
+        assert_eq!(normalize("Smith & Jones LLC"), "smith and jones");
+        // A suffix word on its own is the whole name, not a suffix.
+        assert_eq!(normalize("AG"), "ag");
+    }
+
+    #[test]
+    fn groups_cover_their_aliases() {
+        let preferences = CompanyPreferences::default();
+        assert!(preferences.matches("Facebook", "Meta"));
+        assert!(preferences.matches("Instagram, Inc.", "Meta"));
+        assert!(preferences.matches("Meta Platforms, Inc.", "meta"));
+        assert!(preferences.matches("Meta", "Facebook"));
+        assert!(preferences.matches("Acme GmbH", "Acme Inc"));
+    }
+
+    #[test]
+    fn shared_prefix_is_not_a_division() {
+        let preferences = CompanyPreferences::default();
+        assert!(!preferences.matches("Meta Materials", "Meta"));
+        assert!(!preferences.matches("Apple Leisure Group", "Apple"));
+        assert!(!preferences.matches("Acme", ""));
+    }
+
+    #[test]
+    fn agency_postings_name_their_client() {
+        let preferences = CompanyPreferences::default();
+        let resolved = preferences.resolve(
+            "Hays",
+            "We are recruiting on behalf of Acme Robotics, a fast-growing startup.",
+        );
+        assert!(resolved.agency);
+        assert_eq!(resolved.client.as_deref(), Some("Acme Robotics"));
+    }
+}
@@ -0,0 +1,436 @@
+use crate::analysis_agent;
+use crate::db::Db;
+use crate::http::{ErrorCategory, FetchOrigin, HttpClient};
+use crate::robots::Robots;
+use crate::settings::CrawlSettings;
+use chrono::{DateTime, NaiveDate, Utc};
+use regex::Regex;
+use reqwest::Url;
+use scraper::{Html, Selector};
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::collections::{HashSet, VecDeque};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+use tauri::{AppHandle, Emitter};
+
+/// Sitemaps read per crawl, including those listed by sitemap indexes.
+const MAX_SITEMAPS: usize = 20;
+/// Links to these are never fetched as pages.
+const SKIPPED_EXTENSIONS: &[&str] = &[
+    ".pdf", ".doc", ".docx", ".zip", ".gz", ".xml", ".json", ".css", ".js", ".ico", ".png", ".jpg",
+    ".jpeg", ".gif", ".svg", ".webp", ".mp4", ".mp3",
+];
+
+static RUNNING: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CrawledPosting {
+    pub url: String,
+    pub title: Option<String>,
+    /// Set when this crawl queued the posting for analysis.
+    pub analysis_id: Option<String>,
+    /// Already a saved match or queued by an earlier crawl.
+    pub known: bool,
+    /// `validThrough` is in the past, so the posting is not queued.
+    pub expired: bool,
+}
+
+#[derive(Debug, Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct CrawlSummary {
+    pub url: String,
+    pub pages_fetched: usize,
+    /// In-scope page URLs found in sitemaps.
+    pub sitemap_urls: usize,
+    /// Pages skipped because robots.txt disallows them.
+    pub disallowed: usize,
+    pub failed: usize,
+    /// The page budget ran out before every in-scope page was visited.
+    pub budget_exhausted: bool,
+    /// Sitemaps cut off by the download cap, whose later URLs were missed.
+    pub truncated_sitemaps: Vec<String>,
+    pub postings: Vec<CrawledPosting>,
+    pub queued: usize,
+}
+
+/// The host and path prefix a crawl stays within.
+struct Scope {
+    host: String,
+    prefix: String,
+}
+
+impl Scope {
+    /// `https://acme.com/careers` and `https://acme.com/careers/index.html` both cover
+    /// everything under `/careers/`; a bare host covers the whole site.
+    fn new(start: &Url) -> Self {
+        let path = start.path();
+        let last = path.rsplit('/').next().unwrap_or_default();
+        let prefix = if last.contains('.') {
+            &path[..path.len() - last.len()]
+        } else {
+            path
+        };
+        Self {
+            host: bare_host(start),
+            prefix: prefix.trim_end_matches('/').to_string(),
+        }
+    }
+
+    fn contains(&self, url: &Url) -> bool {
+        let path = url.path();
+        matches!(url.scheme(), "http" | "https")
+            && bare_host(url) == self.host
+            && (path == self.prefix || path.starts_with(&format!("{}/", self.prefix)))
+            && !SKIPPED_EXTENSIONS
+                .iter()
+                .any(|extension| path.to_lowercase().ends_with(extension))
+    }
+}
+
+/// Host without a leading `www.`, so `www.acme.com` and `acme.com` count as one site.
+fn bare_host(url: &Url) -> String {
+    let host = url.host_str().unwrap_or_default().to_lowercase();
+    host.strip_prefix("www.")
+        .map(str::to_string)
+        .unwrap_or(host)
+}
+
+/// What a crawled page contributes: whether it is a posting and where it links.
+struct PageInfo {
+    posting: Option<PostingData>,
+    links: Vec<Url>,
+}
+
+struct PostingData {
+    title: Option<String>,
+    expired: bool,
+}
+
+/// Crawls a careers site from `url`, staying on its host and under its path, and queues new
+/// job postings for analysis when `analyze` is set. Seeds come from the start page plus the
+/// site's sitemaps. Pages are fetched as automated requests, so robots.txt applies under the
+/// `automated` mode. Emits
+/// `crawl:progress` after each page and `crawl:completed` at the end.
+pub async fn crawl(
+    app: &AppHandle,
+    db: &Db,
+    http: &HttpClient,
+    mcp_port: u16,
+    url: &str,
+    settings: &CrawlSettings,
+    analyze: bool,
+) -> Result<CrawlSummary, String> {
+    if RUNNING.swap(true, Ordering::SeqCst) {
+        return Err("a crawl is already in progress".to_string());
+    }
+    let result = crawl_site(app, db, http, mcp_port, url, settings, analyze).await;
+    RUNNING.store(false, Ordering::SeqCst);
+    let summary = result?;
+    let _ = app.emit("crawl:completed", json!(summary));
+    Ok(summary)
+}
+
+async fn crawl_site(
+    app: &AppHandle,
+    db: &Db,
+    http: &HttpClient,
+    mcp_port: u16,
+    url: &str,
+    settings: &CrawlSettings,
+    analyze: bool,
+) -> Result<CrawlSummary, String> {
+    let mut start = Url::parse(url.trim()).map_err(|err| format!("invalid url: {err}"))?;
+    start.set_fragment(None);
+    if !matches!(start.scheme(), "http" | "https") {
+        return Err(format!("unsupported url scheme: {}", start.scheme()));
+    }
+    let host = start
+        .host_str()
+        .ok_or_else(|| format!("url has no host: {start}"))?
+        .to_lowercase();
+    let scope = Scope::new(&start);
+    let robots = http.robots(&start).await.unwrap_or_default();
+    let mut summary = CrawlSummary {
+        url: start.to_string(),
+        ..CrawlSummary::default()
+    };
+
+    let mut seen = HashSet::from([start.to_string()]);
+    let mut frontier = VecDeque::from([(start.clone(), 0)]);
+    let (sitemap_urls, truncated_sitemaps) = sitemap_pages(http, &start, &robots).await;
+    summary.truncated_sitemaps = truncated_sitemaps;
+    for mut page in sitemap_urls {
+        page.set_fragment(None);
+        if scope.contains(&page) && seen.insert(page.to_string()) {
+            summary.sitemap_urls += 1;
+            frontier.push_back((page, 1));
+        }
+    }
+
+    let mut found = Vec::new();
+    while let Some((page_url, depth)) = frontier.pop_front() {
+        if summary.pages_fetched >= settings.max_pages {
+            summary.budget_exhausted = true;
+            break;
+        }
+        let page = match http
+            .fetch(page_url.as_str(), false, FetchOrigin::Automated)
+            .await
+        {
+            Ok(page) => page,
+            Err(err) if err.category == Some(ErrorCategory::DisallowedByRobots) => {
+                summary.disallowed += 1;
+                continue;
+            }
+            Err(err) => {
+                summary.pages_fetched += 1;
+                summary.failed += 1;
+                log::warn!("crawl {page_url}: {}", err.message);
+                continue;
+            }
+        };
+        summary.pages_fetched += 1;
+        // Links resolve against where a redirect landed, but only if it stayed in scope.
+        let base = Url::parse(&page.final_url)
+            .ok()
+            .filter(|base| scope.contains(base));
+        let info = inspect(&page.body, base.as_ref());
+        if let Some(posting) = info.posting {
+            found.push((page_url.clone(), posting));
+        }
+        if depth < settings.max_depth {
+            for link in info.links {
+                if scope.contains(&link) && seen.insert(link.to_string()) {
+                    frontier.push_back((link, depth + 1));
+                }
+            }
+        }
+        let _ = app.emit(
+            "crawl:progress",
+            json!({
+                "url": summary.url,
+                "pagesFetched": summary.pages_fetched,
+                "postings": found.len(),
+            }),
+        );
+    }
+
+    for (posting_url, posting) in found {
+        let url = posting_url.to_string();
+        let known = db.has_match_for_url(&url)? || db.crawl_posting_queued(&url)?;
+        let mut analysis_id = None;
+        if analyze && !known && !posting.expired {
+            let (id, mut child) = analysis_agent::launch(app, mcp_port, &url, true)?;
+            db.insert_crawl_posting(&url, &host, posting.title.as_deref(), &id)?;
+            summary.queued += 1;
+            analysis_id = Some(id);
+            // Waiting keeps a large site from starting dozens of agents at once.
+            let _ = tauri::async_runtime::spawn_blocking(move || child.wait()).await;
+        }
+        summary.postings.push(CrawledPosting {
+            url,
+            title: posting.title,
+            analysis_id,
+            known,
+            expired: posting.expired,
+        });
+    }
+    Ok(summary)
+}
+
+/// Page URLs listed in the sitemaps robots.txt names, or in `/sitemap.xml` when it names
+/// none. Sitemap indexes are followed, with job and career sitemaps first, up to
+/// `MAX_SITEMAPS`. Also returns the sitemaps that hit the download cap.
+async fn sitemap_pages(http: &HttpClient, start: &Url, robots: &Robots) -> (Vec<Url>, Vec<String>) {
+    let mut pending: VecDeque<String> = robots.sitemaps().iter().cloned().collect();
+    if pending.is_empty() {
+        pending.extend(start.join("/sitemap.xml").ok().map(String::from));
+    }
+    let mut visited = HashSet::new();
+    let mut pages = Vec::new();
+    let mut truncated = Vec::new();
+    while let Some(sitemap) = pending.pop_front() {
+        if visited.len() >= MAX_SITEMAPS {
+            break;
+        }
+        if !visited.insert(sitemap.clone()) || sitemap.to_lowercase().ends_with(".gz") {
+            continue;
+        }
+        let body = match http.fetch(&sitemap, false, FetchOrigin::Automated).await {
+            Ok(page) if page.truncated => {
+                // Entries before the cut are still usable; a partial `<loc>` never matches.
+                log::warn!("sitemap {sitemap} exceeds the download cap; later URLs are missed");
+                truncated.push(sitemap.clone());
+                page.body
+            }
+            Ok(page) => page.body,
+            Err(err) => {
+                log::info!("sitemap {sitemap}: {}", err.message);
+                continue;
+            }
+        };
+        let (is_index, locations) = sitemap_locations(&body);
+        if is_index {
+            let (relevant, other): (Vec<_>, Vec<_>) = locations.into_iter().partition(|loc| {
+                let loc = loc.to_lowercase();
+                loc.contains("job") || loc.contains("career")
+            });
+            for loc in relevant.into_iter().rev() {
+                pending.push_front(loc);
+            }
+            pending.extend(other);
+        } else {
+            pages.extend(locations.iter().filter_map(|loc| Url::parse(loc).ok()));
+        }
+    }
+    (pages, truncated)
+}
+
+/// The `<loc>` entries of a sitemap, and whether it is a sitemap index.
+fn sitemap_locations(xml: &str) -> (bool, Vec<String>) {
+    static LOC: OnceLock<Regex> = OnceLock::new();
+    let loc = LOC.get_or_init(|| {
+        Regex::new(r"(?is)<loc>\s*(?:<!\[CDATA\[)?(.*?)(?:\]\]>)?\s*</loc>")
+            .expect("sitemap loc pattern")
+    });
+    let locations = loc
+        .captures_iter(xml)
+        .map(|caps| {
+            caps[1]
+                .replace("&lt;", "<")
+                .replace("&gt;", ">")
+                .replace("&quot;", "\"")
+                .replace("&apos;", "'")
+                .replace("&amp;", "&")
+        })
+        .collect();
+    (xml.contains("<sitemapindex"), locations)
+}
+
+/// CSS selectors used on every crawled page, parsed once.
+struct Selectors {
+    anchors: Selector,
+    json_ld: Selector,
+    microdata_scope: Selector,
+    microdata_title: Selector,
+    microdata_valid_through: Selector,
+}
+
+fn selectors() -> &'static Selectors {
+    static SELECTORS: OnceLock<Selectors> = OnceLock::new();
+    SELECTORS.get_or_init(|| Selectors {
+        anchors: Selector::parse("a[href]").expect("anchor selector"),
+        json_ld: Selector::parse(r#"script[type="application/ld+json"]"#)
+            .expect("json-ld selector"),
+        microdata_scope: Selector::parse(r#"[itemtype$="schema.org/JobPosting"]"#)
+            .expect("scope selector"),
+        microdata_title: Selector::parse(r#"[itemprop="title"]"#).expect("title selector"),
+        microdata_valid_through: Selector::parse(r#"[itemprop="validThrough"]"#)
+            .expect("valid selector"),
+    })
+}
+
+/// Parses a page once for posting markup and, when `base` is given, its links.
+fn inspect(html: &str, base: Option<&Url>) -> PageInfo {
+    let document = Html::parse_document(html);
+    let links = match base {
+        Some(base) => document
+            .select(&selectors().anchors)
+            .filter_map(|anchor| anchor.value().attr("href"))
+            .filter_map(|href| base.join(href.trim()).ok())
+            .map(|mut link| {
+                link.set_fragment(None);
+                link
+            })
+            .collect(),
+        None => Vec::new(),
+    };
+    PageInfo {
+        posting: json_ld_posting(&document).or_else(|| microdata_posting(&document)),
+        links,
+    }
+}
+
+/// A schema.org `JobPosting` in the page's JSON-LD, including inside `@graph` and arrays.
+fn json_ld_posting(document: &Html) -> Option<PostingData> {
+    document.select(&selectors().json_ld).find_map(|script| {
+        let value: Value = serde_json::from_str(&script.text().collect::<String>()).ok()?;
+        let posting = find_job_posting(&value)?;
+        Some(PostingData {
+            title: posting
+                .get("title")
+                .and_then(Value::as_str)
+                .map(|title| title.trim().to_string())
+                .filter(|title| !title.is_empty()),
+            expired: posting
+                .get("validThrough")
+                .and_then(Value::as_str)
+                .is_some_and(is_past),
+        })
+    })
+}
+
+fn find_job_posting(value: &Value) -> Option<&Value> {
+    match value {
+        Value::Array(items) => items.iter().find_map(find_job_posting),
+        Value::Object(object) => {
+            let is_posting = match object.get("@type") {
+                Some(Value::String(kind)) => is_job_posting_type(kind),
+                Some(Value::Array(kinds)) => kinds
+                    .iter()
+                    .filter_map(Value::as_str)
+                    .any(is_job_posting_type),
+                _ => false,
+            };
+            if is_posting {
+                return Some(value);
+            }
+            ["@graph", "mainEntity"]
+                .iter()
+                .filter_map(|key| object.get(*key))
+                .find_map(find_job_posting)
+        }
+        _ => None,
+    }
+}
+
+fn is_job_posting_type(kind: &str) -> bool {
+    kind == "JobPosting" || kind.ends_with("/JobPosting")
+}
+
+/// A schema.org `JobPosting` marked up with microdata.
+fn microdata_posting(document: &Html) -> Option<PostingData> {
+    let selectors = selectors();
+    let posting = document.select(&selectors.microdata_scope).next()?;
+    Some(PostingData {
+        title: posting
+            .select(&selectors.microdata_title)
+            .next()
+            .map(|element| element.text().collect::<String>().trim().to_string())
+            .filter(|title| !title.is_empty()),
+        expired: posting
+            .select(&selectors.microdata_valid_through)
+            .next()
+            .and_then(|element| {
+                element
+                    .value()
+                    .attr("content")
+                    .or_else(|| element.value().attr("datetime"))
+            })
+            .is_some_and(is_past),
+    })
+}
+
+/// Whether an ISO 8601 date or date-time is before now. Unparseable values count as current.
+fn is_past(value: &str) -> bool {
+    let value = value.trim();
+    if let Ok(moment) = DateTime::parse_from_rfc3339(value) {
+        return moment < Utc::now();
+    }
+    value
+        .get(..10)
+        .and_then(|date| NaiveDate::parse_from_str(date, "%Y-%m-%d").ok())
+        .is_some_and(|date| date < Utc::now().date_naive())
+}
@@ -0,0 +1,570 @@
+use chrono::{DateTime, NaiveDateTime, Utc};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, SET_COOKIE};
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct StoredCookie {
+    /// Lowercase domain without a leading dot.
+    pub domain: String,
+    /// Sent only to `domain` itself, not its subdomains.
+    pub host_only: bool,
+    pub path: String,
+    pub secure: bool,
+    /// Unix timestamp; `None` for session cookies, which are kept until replaced.
+    pub expires: Option<i64>,
+    pub name: String,
+    pub value: String,
+}
+
+impl StoredCookie {
+    fn matches(&self, url: &Url, now: i64) -> bool {
+        let host = url.host_str().unwrap_or_default().to_lowercase();
+        let domain_matches = host == self.domain
+            || (!self.host_only && host.ends_with(&format!(".{}", self.domain)));
+        domain_matches
+            && path_matches(url.path(), &self.path)
+            && (!self.secure || url.scheme() == "https")
+            && self.expires.map_or(true, |expires| expires > now)
+    }
+
+    fn same_slot(&self, other: &StoredCookie) -> bool {
+        self.domain == other.domain && self.path == other.path && self.name == other.name
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default, rename_all = "camelCase")]
+struct CredentialFile {
+    cookies: Vec<StoredCookie>,
+    /// Extra request headers keyed by domain; they apply to subdomains too.
+    headers: BTreeMap<String, BTreeMap<String, String>>,
+}
+
+impl CredentialFile {
+    fn domains(&self) -> BTreeSet<String> {
+        self.cookies
+            .iter()
+            .map(|cookie| cookie.domain.clone())
+            .chain(self.headers.keys().cloned())
+            .collect()
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum CookieFormat {
+    /// The tab-separated `cookies.txt` format written by curl and browser extensions.
+    Netscape,
+    /// A JSON array of cookie objects, as exported by Cookie-Editor or EditThisCookie.
+    Json,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportSummary {
+    pub imported: usize,
+    /// Lines or entries that were malformed or already expired.
+    pub skipped: usize,
+    pub domains: Vec<String>,
+}
+
+/// What is configured for a domain, without any cookie or header values.
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DomainCredentials {
+    pub domain: String,
+    pub cookie_names: Vec<String>,
+    pub header_names: Vec<String>,
+    /// Earliest expiry among the domain's persistent cookies.
+    pub expires: Option<DateTime<Utc>>,
+}
+
+/// Cookies and headers to add to one request.
+pub struct RequestCredentials {
+    pub cookie: Option<String>,
+    pub headers: Vec<(HeaderName, HeaderValue)>,
+}
+
+/// Cookie jar and per-domain headers for logged-in fetching, persisted to a file readable only
+/// by the current user.
+#[derive(Clone)]
+pub struct CredentialStore {
+    path: Option<PathBuf>,
+    data: Arc<Mutex<CredentialFile>>,
+}
+
+fn normalize_domain(domain: &str) -> String {
+    domain.trim().trim_start_matches('.').to_lowercase()
+}
+
+fn domain_matches(host: &str, domain: &str) -> bool {
+    host == domain || host.ends_with(&format!(".{domain}"))
+}
+
+/// RFC 6265 §5.1.4: the cookie path must equal the request path or be a prefix of it that
+/// ends at a `/`, so `/app` covers `/app/jobs` but not `/application`.
+fn path_matches(request_path: &str, cookie_path: &str) -> bool {
+    request_path == cookie_path
+        || (request_path.starts_with(cookie_path)
+            && (cookie_path.ends_with('/') || request_path[cookie_path.len()..].starts_with('/')))
+}
+
+/// Parses an `Expires` date in the RFC 1123 form or the older `Wed, 21-Oct-2015 07:28:00 GMT`
+/// and asctime forms servers still send.
+fn parse_cookie_date(value: &str) -> Option<i64> {
+    if let Ok(date) = DateTime::parse_from_rfc2822(value) {
+        return Some(date.timestamp());
+    }
+    let value = value
+        .trim()
+        .trim_end_matches("GMT")
+        .trim_end_matches("UTC")
+        .trim();
+    [
+        "%a, %d-%b-%Y %H:%M:%S",
+        "%A, %d-%b-%y %H:%M:%S",
+        "%a, %d-%b-%y %H:%M:%S",
+        "%a %b %e %H:%M:%S %Y",
+    ]
+    .iter()
+    .find_map(|format| NaiveDateTime::parse_from_str(value, format).ok())
+    .map(|date| date.and_utc().timestamp())
+}
+
+impl CredentialStore {
+    /// Loads the store from `path`; without a path the store lives in memory only.
+    pub fn load(path: Option<PathBuf>) -> Self {
+        let data = path
+            .as_ref()
+            .and_then(|path| std::fs::read(path).ok())
+            .and_then(|bytes| {
+                serde_json::from_slice(&bytes)
+                    .map_err(|err| log::warn!("ignoring unreadable credentials file: {err}"))
+                    .ok()
+            })
+            .unwrap_or_default();
+        Self {
+            path,
+            data: Arc::new(Mutex::new(data)),
+        }
+    }
+
+    fn update<T>(&self, change: impl FnOnce(&mut CredentialFile) -> T) -> Result<T, String> {
+        let mut data = self
+            .data
+            .lock()
+            .map_err(|_| "credentials lock poisoned".to_string())?;
+        let result = change(&mut data);
+        if let Some(path) = &self.path {
+            write_private(path, &data)?;
+        }
+        Ok(result)
+    }
+
+    pub fn import_cookies(
+        &self,
+        content: &str,
+        format: Option<CookieFormat>,
+    ) -> Result<ImportSummary, String> {
+        let format = format.unwrap_or_else(|| {
+            if content.trim_start().starts_with(['[', '{']) {
+                CookieFormat::Json
+            } else {
+                CookieFormat::Netscape
+            }
+        });
+        let (cookies, mut skipped) = match format {
+            CookieFormat::Netscape => parse_netscape(content),
+            CookieFormat::Json => parse_json(content)?,
+        };
+        let now = Utc::now().timestamp();
+        let live: Vec<StoredCookie> = cookies
+            .into_iter()
+            .filter(|cookie| {
+                let alive = cookie.expires.map_or(true, |expires| expires > now);
+                if !alive {
+                    skipped += 1;
+                }
+                alive
+            })
+            .collect();
+        let domains: BTreeSet<String> = live.iter().map(|cookie| cookie.domain.clone()).collect();
+        let imported = live.len();
+        self.update(|data| {
+            for cookie in live {
+                data.cookies.retain(|existing| !existing.same_slot(&cookie));
+                data.cookies.push(cookie);
+            }
+        })?;
+        Ok(ImportSummary {
+            imported,
+            skipped,
+            domains: domains.into_iter().collect(),
+        })
+    }
+
+    /// Replaces the extra headers sent to `domain`; an empty map removes them.
+    pub fn set_headers(
+        &self,
+        domain: &str,
+        headers: BTreeMap<String, String>,
+    ) -> Result<(), String> {
+        let domain = normalize_domain(domain);
+        if domain.is_empty() {
+            return Err("domain is required".to_string());
+        }
+        for (name, value) in &headers {
+            HeaderName::from_bytes(name.as_bytes())
+                .map_err(|_| format!("invalid header name: {name}"))?;
+            HeaderValue::from_str(value).map_err(|_| format!("invalid value for header {name}"))?;
+        }
+        self.update(|data| {
+            if headers.is_empty() {
+                data.headers.remove(&domain);
+            } else {
+                data.headers.insert(domain, headers);
+            }
+        })
+    }
+
+    /// Forgets the cookies and headers of one domain, or of every domain.
+    pub fn clear(&self, domain: Option<&str>) -> Result<(), String> {
+        let domain = domain.map(normalize_domain);
+        self.update(|data| match &domain {
+            Some(domain) => {
+                data.cookies.retain(|cookie| &cookie.domain != domain);
+                data.headers.remove(domain);
+            }
+            None => *data = CredentialFile::default(),
+        })
+    }
+
+    pub fn domains(&self) -> Result<Vec<DomainCredentials>, String> {
+        let data = self
+            .data
+            .lock()
+            .map_err(|_| "credentials lock poisoned".to_string())?;
+        Ok(data
+            .domains()
+            .into_iter()
+            .map(|domain| {
+                let cookies: Vec<&StoredCookie> = data
+                    .cookies
+                    .iter()
+                    .filter(|cookie| cookie.domain == domain)
+                    .collect();
+                DomainCredentials {
+                    cookie_names: cookies.iter().map(|cookie| cookie.name.clone()).collect(),
+                    header_names: data
+                        .headers
+                        .get(&domain)
+                        .map(|headers| headers.keys().cloned().collect())
+                        .unwrap_or_default(),
+                    expires: cookies
+                        .iter()
+                        .filter_map(|cookie| cookie.expires)
+                        .min()
+                        .and_then(|expires| DateTime::from_timestamp(expires, 0)),
+                    domain,
+                }
+            })
+            .collect())
+    }
+
+    /// Cookies and headers configured for `url`, or `None` when the request goes anonymous.
+    pub fn for_url(&self, url: &Url) -> Result<Option<RequestCredentials>, String> {
+        let data = self
+            .data
+            .lock()
+            .map_err(|_| "credentials lock poisoned".to_string())?;
+        let host = url.host_str().unwrap_or_default().to_lowercase();
+        let now = Utc::now().timestamp();
+        let mut cookies: Vec<&StoredCookie> = data
+            .cookies
+            .iter()
+            .filter(|cookie| cookie.matches(url, now))
+            .collect();
+        // Longer paths first, as browsers send them.
+        cookies.sort_by_key(|cookie| std::cmp::Reverse(cookie.path.len()));
+        let headers: Vec<(HeaderName, HeaderValue)> = data
+            .headers
+            .iter()
+            .filter(|(domain, _)| domain_matches(&host, domain))
+            .flat_map(|(_, headers)| headers.iter())
+            .filter_map(|(name, value)| {
+                Some((
+                    HeaderName::from_bytes(name.as_bytes()).ok()?,
+                    HeaderValue::from_str(value).ok()?,
+                ))
+            })
+            .collect();
+        if cookies.is_empty() && headers.is_empty() {
+            return Ok(None);
+        }
+        let cookie = (!cookies.is_empty()).then(|| {
+            cookies
+                .iter()
+                .map(|cookie| format!("{}={}", cookie.name, cookie.value))
+                .collect::<Vec<_>>()
+                .join("; ")
+        });
+        Ok(Some(RequestCredentials { cookie, headers }))
+    }
+
+    /// Keeps sessions alive by storing `Set-Cookie` responses from domains that already have
+    /// credentials; other sites' cookies are ignored so anonymous fetches stay anonymous.
+    pub fn store_response_cookies(&self, url: &Url, headers: &HeaderMap) -> Result<(), String> {
+        let host = url.host_str().unwrap_or_default().to_lowercase();
+        let set_cookies: Vec<&str> = headers
+            .get_all(SET_COOKIE)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .collect();
+        if set_cookies.is_empty() {
+            return Ok(());
+        }
+        {
+            let data = self
+                .data
+                .lock()
+                .map_err(|_| "credentials lock poisoned".to_string())?;
+            if !data
+                .domains()
+                .iter()
+                .any(|domain| domain_matches(&host, domain))
+            {
+                return Ok(());
+            }
+        }
+        let now = Utc::now().timestamp();
+        let cookies: Vec<StoredCookie> = set_cookies
+            .into_iter()
+            .filter_map(|header| parse_set_cookie(header, &host, url.path(), now))
+            .collect();
+        if cookies.is_empty() {
+            return Ok(());
+        }
+        self.update(|data| {
+            for cookie in cookies {
+                data.cookies.retain(|existing| !existing.same_slot(&cookie));
+                if cookie.expires.map_or(true, |expires| expires > now) {
+                    data.cookies.push(cookie);
+                }
+            }
+        })
+    }
+}
+
+/// Writes the credentials with owner-only permissions, replacing the file atomically.
+fn write_private(path: &PathBuf, data: &CredentialFile) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|err| format!("create credentials dir: {err}"))?;
+    }
+    let bytes = serde_json::to_vec(data).map_err(|err| format!("serialize credentials: {err}"))?;
+    let temp = path.with_extension("tmp");
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options
+        .open(&temp)
+        .map_err(|err| format!("write credentials: {err}"))?;
+    file.write_all(&bytes)
+        .map_err(|err| format!("write credentials: {err}"))?;
+    std::fs::rename(&temp, path).map_err(|err| format!("save credentials: {err}"))
+}
+
+/// Parses `cookies.txt`: domain, subdomain flag, path, secure, expiry, name and value per line.
+fn parse_netscape(content: &str) -> (Vec<StoredCookie>, usize) {
+    let mut cookies = Vec::new();
+    let mut skipped = 0;
+    for line in content.lines() {
+        // curl marks HttpOnly cookies with a prefix that otherwise looks like a comment.
+        let line = line.strip_prefix("#HttpOnly_").unwrap_or(line).trim_end();
+        if line.trim().is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let fields: Vec<&str> = line.split('\t').collect();
+        if fields.len() < 7 {
+            skipped += 1;
+            continue;
+        }
+        let Ok(expires) = fields[4].trim().parse::<i64>() else {
+            skipped += 1;
+            continue;
+        };
+        cookies.push(StoredCookie {
+            domain: normalize_domain(fields[0]),
+            host_only: !fields[1].eq_ignore_ascii_case("TRUE"),
+            path: fields[2].to_string(),
+            secure: fields[3].eq_ignore_ascii_case("TRUE"),
+            expires: (expires > 0).then_some(expires),
+            name: fields[5].to_string(),
+            value: fields[6..].join("\t"),
+        });
+    }
+    (cookies, skipped)
+}
+
+/// Parses a JSON cookie export: an array of objects, or an object with a `cookies` array.
+fn parse_json(content: &str) -> Result<(Vec<StoredCookie>, usize), String> {
+    let value: Value =
+        serde_json::from_str(content).map_err(|err| format!("cookie json parse: {err}"))?;
+    let entries = match &value {
+        Value::Array(entries) => entries.as_slice(),
+        Value::Object(object) => object
+            .get("cookies")
+            .and_then(Value::as_array)
+            .map(Vec::as_slice)
+            .ok_or("cookie json needs an array or a `cookies` array")?,
+        _ => return Err("cookie json needs an array or a `cookies` array".to_string()),
+    };
+    let mut cookies = Vec::new();
+    let mut skipped = 0;
+    for entry in entries {
+        let text = |key: &str| entry.get(key).and_then(Value::as_str);
+        let (Some(raw_domain), Some(name), Some(value)) =
+            (text("domain"), text("name"), text("value"))
+        else {
+            skipped += 1;
+            continue;
+        };
+        let expires = ["expirationDate", "expires", "expiry"]
+            .iter()
+            .find_map(|key| entry.get(*key).and_then(Value::as_f64))
+            .filter(|expires| *expires > 0.0)
+            .map(|expires| expires as i64);
+        let session = entry
+            .get("session")
+            .and_then(Value::as_bool)
+            .unwrap_or(false);
+        cookies.push(StoredCookie {
+            domain: normalize_domain(raw_domain),
+            host_only: entry
+                .get("hostOnly")
+                .and_then(Value::as_bool)
+                .unwrap_or(!raw_domain.starts_with('.')),
+            path: text("path").unwrap_or("/").to_string(),
+            secure: entry
+                .get("secure")
+                .and_then(Value::as_bool)
+                .unwrap_or(false),
+            expires: if session { None } else { expires },
+            name: name.to_string(),
+            value: value.to_string(),
+        });
+    }
+    Ok((cookies, skipped))
+}
+
+/// Parses one `Set-Cookie` header received from `host`. Cookies for a domain the host does not
+/// belong to are rejected.
+fn parse_set_cookie(
+    header: &str,
+    host: &str,
+    request_path: &str,
+    now: i64,
+) -> Option<StoredCookie> {
+    let mut parts = header.split(';');
+    let (name, value) = parts.next()?.split_once('=')?;
+    let name = name.trim();
+    if name.is_empty() {
+        return None;
+    }
+    let mut cookie = StoredCookie {
+        domain: host.to_string(),
+        host_only: true,
+        path: match request_path.rfind('/') {
+            Some(0) | None => "/".to_string(),
+            Some(index) => request_path[..index].to_string(),
+        },
+        secure: false,
+        expires: None,
+        name: name.to_string(),
+        value: value.trim().trim_matches('"').to_string(),
+    };
+    let mut max_age = None;
+    for attribute in parts {
+        let (key, value) = attribute
+            .split_once('=')
+            .map_or((attribute.trim(), ""), |(key, value)| {
+                (key.trim(), value.trim())
+            });
+        match key.to_ascii_lowercase().as_str() {
+            "domain" if !value.is_empty() => {
+                let domain = normalize_domain(value);
+                if !domain_matches(host, &domain) {
+                    return None;
+                }
+                cookie.domain = domain;
+                cookie.host_only = false;
+            }
+            "path" if value.starts_with('/') => cookie.path = value.to_string(),
+            "secure" => cookie.secure = true,
+            "max-age" => max_age = value.parse::<i64>().ok(),
+            "expires" => {
+                cookie.expires = parse_cookie_date(value);
+            }
+            _ => {}
+        }
+    }
+    // Max-Age wins over Expires; zero or negative deletes the cookie.
+    if let Some(max_age) = max_age {
+        cookie.expires = Some(now + max_age);
+    }
+    Some(cookie)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cookie_path_matches_whole_segments() {
+        assert!(path_matches("/app", "/app"));
+        assert!(path_matches("/app/jobs", "/app"));
+        assert!(path_matches("/app/jobs", "/app/"));
+        assert!(path_matches("/anything", "/"));
+        assert!(!path_matches("/application", "/app"));
+        assert!(!path_matches("/ap", "/app"));
+    }
+
+    #[test]
+    fn expires_accepts_common_date_forms() {
+        let expected = Some(1_445_412_480);
+        assert_eq!(parse_cookie_date("Wed, 21 Oct 2015 07:28:00 GMT"), expected);
+        assert_eq!(parse_cookie_date("Wed, 21-Oct-2015 07:28:00 GMT"), expected);
+        assert_eq!(
+            parse_cookie_date("Wednesday, 21-Oct-15 07:28:00 GMT"),
+            expected
+        );
+        assert_eq!(parse_cookie_date("Wed Oct 21 07:28:00 2015"), expected);
+        assert_eq!(parse_cookie_date("soon"), None);
+    }
+
+    #[test]
+    fn set_cookie_with_dashed_expires_is_persistent() {
+        let cookie = parse_set_cookie(
+            "session=abc; Path=/app; Expires=Wed, 21-Oct-2015 07:28:00 GMT",
+            "jobs.example.com",
+            "/app/login",
+            0,
+        )
+        .unwrap();
+        assert_eq!(cookie.expires, Some(1_445_412_480));
+        let url = Url::parse("https://jobs.example.com/application").unwrap();
+        assert!(!cookie.matches(&url, 0));
+        let url = Url::parse("https://jobs.example.com/app/jobs").unwrap();
+        assert!(cookie.matches(&url, 0));
+    }
+}
@@ -1,11 +1,24 @@
+use crate::embedding;
+use crate::scoring::{ExtractedListing, FeedbackSample, Rejection, ScoreBreakdown, ScoreWeights};
+use crate::watch::ChangeKind;
 use chrono::{DateTime, Utc};
-use rusqlite::{params, Connection};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use rusqlite::{params, Connection, OptionalExtension, Row};
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::io::{Read, Write};
 use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
 use tauri::{AppHandle, Manager};
 use uuid::Uuid;
 
+const MATCH_COLUMNS: &str = "id, analysis_id, url, title, company, location, match_score, summary, created_at, raw_excerpt, insights, content_hash, score_breakdown, decision, rejection, decision_overridden, previous_score, role_family, settings_hash, scorer_version, scored_at, closed_at";
+/// Number of entries in `MATCH_COLUMNS`; extra selected columns start at this index.
+const MATCH_COLUMN_COUNT: usize = 22;
+
 #[derive(Clone)]
 pub struct Db {
     conn: Arc<Mutex<Connection>>,
@@ -23,6 +36,114 @@ pub struct JobMatch {
     pub summary: String,
     pub created_at: String,
     pub raw_excerpt: Option<String>,
+    pub insights: Option<Value>,
+    pub content_hash: Option<String>,
+    pub score_breakdown: Option<ScoreBreakdown>,
+    #[serde(default)]
+    pub profile_scores: Vec<ProfileScore>,
+    #[serde(default)]
+    pub decision: Decision,
+    #[serde(default)]
+    pub rejection: Option<Rejection>,
+    /// Set when `decision` was chosen by hand rather than by the filters.
+    #[serde(default)]
+    pub decision_overridden: bool,
+    /// Score before the most recent rescore.
+    #[serde(default)]
+    pub previous_score: Option<f64>,
+    /// Normalized role family of the title, such as `backend` or `em`.
+    #[serde(default)]
+    pub role_family: Option<String>,
+    /// Fingerprint of the settings and weights behind `match_score`.
+    #[serde(default)]
+    pub settings_hash: Option<String>,
+    /// `SCORER_VERSION` that produced `match_score`.
+    #[serde(default)]
+    pub scorer_version: Option<u32>,
+    #[serde(default)]
+    pub scored_at: Option<String>,
+    /// When the watcher found the posting gone; cleared if it comes back.
+    #[serde(default)]
+    pub closed_at: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ProfileScore {
+    pub profile: String,
+    pub match_score: f64,
+    pub score_breakdown: Option<ScoreBreakdown>,
+    #[serde(default)]
+    pub rejection: Option<Rejection>,
+    #[serde(default)]
+    pub previous_score: Option<f64>,
+    #[serde(default)]
+    pub settings_hash: Option<String>,
+    #[serde(default)]
+    pub scorer_version: Option<u32>,
+}
+
+/// One entry in a match's score history.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ScoreRecord {
+    pub profile: String,
+    pub match_score: f64,
+    pub score_breakdown: Option<ScoreBreakdown>,
+    pub rejection: Option<Rejection>,
+    pub settings_hash: Option<String>,
+    pub scorer_version: Option<u32>,
+    pub created_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Decision {
+    #[default]
+    Accepted,
+    Rejected,
+}
+
+impl Decision {
+    fn as_str(self) -> &'static str {
+        match self {
+            Decision::Accepted => "accepted",
+            Decision::Rejected => "rejected",
+        }
+    }
+
+    fn parse(value: &str) -> Self {
+        if value == "rejected" {
+            Decision::Rejected
+        } else {
+            Decision::Accepted
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum MatchSort {
+    #[default]
+    Recent,
+    Score,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct MatchQuery {
+    pub limit: usize,
+    /// Restricts the list to matches scored against this profile and reports that profile's score
+    /// as `match_score`.
+    pub profile: Option<String>,
+    pub sort: MatchSort,
+    /// Includes listings rejected by hard filters, which are hidden by default.
+    pub include_rejected: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SimilarJob {
+    #[serde(rename = "match")]
+    pub job_match: JobMatch,
+    pub similarity: f64,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -35,6 +156,139 @@ pub struct JobMatchInput {
     pub match_score: f64,
     pub summary: String,
     pub raw_excerpt: Option<String>,
+    #[serde(default)]
+    pub insights: Option<Value>,
+    #[serde(default)]
+    pub content_hash: Option<String>,
+    #[serde(default)]
+    pub score_breakdown: Option<ScoreBreakdown>,
+    #[serde(default)]
+    pub profile_scores: Vec<ProfileScore>,
+    #[serde(default)]
+    pub rejection: Option<Rejection>,
+    /// Extracted fields kept so the match can be rescored without refetching.
+    #[serde(default)]
+    pub listing: Option<ExtractedListing>,
+    #[serde(default)]
+    pub role_family: Option<String>,
+    #[serde(default)]
+    pub settings_hash: Option<String>,
+    #[serde(default)]
+    pub scorer_version: Option<u32>,
+}
+
+/// A stored match's extracted listing, as needed to rescore it.
+#[derive(Debug, Clone)]
+pub struct StoredListing {
+    pub match_id: String,
+    pub listing: ExtractedListing,
+    pub embedding: Option<Vec<f32>>,
+}
+
+/// Text and scores of an accepted match, as needed for the skill report.
+#[derive(Debug, Clone)]
+pub struct SkillSource {
+    pub match_id: String,
+    pub url: String,
+    pub title: Option<String>,
+    pub company: Option<String>,
+    pub match_score: f64,
+    /// Stored listing text, or the excerpt for matches saved before listings were stored.
+    pub text: String,
+    /// Skills the LLM stage extracted, if it ran.
+    pub llm_skills: Vec<String>,
+}
+
+/// New scores for a stored match; the current scores become the previous ones.
+#[derive(Debug, Clone)]
+pub struct Rescore {
+    pub match_score: f64,
+    pub score_breakdown: ScoreBreakdown,
+    pub rejection: Option<Rejection>,
+    pub settings_hash: Option<String>,
+    pub scorer_version: Option<u32>,
+    pub profile_scores: Vec<ProfileScore>,
+}
+
+/// A fetched page to archive.
+#[derive(Debug, Clone)]
+pub struct SnapshotInput<'a> {
+    pub analysis_id: Option<&'a str>,
+    pub url: &'a str,
+    pub final_url: &'a str,
+    pub status: u16,
+    pub headers: &'a [(String, String)],
+    pub encoding: &'a str,
+    /// The body hit the download cap.
+    pub truncated: bool,
+    pub html: &'a str,
+}
+
+/// An archived page. `html` is left empty when only the metadata was requested.
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Snapshot {
+    pub id: String,
+    pub analysis_id: Option<String>,
+    pub url: String,
+    pub final_url: String,
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub encoding: String,
+    pub truncated: bool,
+    pub fetched_at: String,
+    /// SHA-256 of the HTML, shared by snapshots with identical bodies.
+    pub content_hash: String,
+    pub size: i64,
+    pub compressed_size: i64,
+    pub html: String,
+}
+
+/// A subscribed RSS or Atom feed.
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Feed {
+    pub id: String,
+    pub url: String,
+    pub title: Option<String>,
+    pub added_at: String,
+    pub polled_at: Option<String>,
+    /// Why the last poll failed; cleared by the next successful one.
+    pub last_error: Option<String>,
+    pub item_count: usize,
+    /// Items that started an analysis.
+    pub analyzed_count: usize,
+}
+
+/// A saved match the watcher refetches.
+#[derive(Debug, Clone)]
+pub struct WatchTarget {
+    pub match_id: String,
+    pub analysis_id: String,
+    pub url: String,
+    pub closed: bool,
+}
+
+/// A detected change to a watched listing.
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ListingChange {
+    pub id: i64,
+    pub match_id: String,
+    pub detected_at: String,
+    pub kinds: Vec<ChangeKind>,
+    pub summary: String,
+    /// Unified diff of the normalized page text; empty when the page could not be fetched.
+    pub diff: String,
+    pub previous_snapshot_id: Option<String>,
+    pub snapshot_id: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct LearnedWeights {
+    pub weights: ScoreWeights,
+    pub samples: usize,
+    pub updated_at: String,
 }
 
 impl Db {
@@ -63,11 +317,150 @@ impl Db {
           match_score REAL NOT NULL,
           summary TEXT NOT NULL,
           created_at TEXT NOT NULL,
-          raw_excerpt TEXT
+          raw_excerpt TEXT,
+          insights TEXT,
+          content_hash TEXT,
+          score_breakdown TEXT,
+          decision TEXT NOT NULL DEFAULT 'accepted',
+          rejection TEXT,
+          decision_overridden INTEGER NOT NULL DEFAULT 0,
+          previous_score REAL,
+          listing TEXT,
+          role_family TEXT,
+          settings_hash TEXT,
+          scorer_version INTEGER,
+          scored_at TEXT,
+          closed_at TEXT,
+          watched_at TEXT
+        );
+        CREATE TABLE IF NOT EXISTS embeddings (
+          content_hash TEXT PRIMARY KEY,
+          model TEXT NOT NULL,
+          vector BLOB NOT NULL,
+          created_at TEXT NOT NULL
+        );
+        CREATE TABLE IF NOT EXISTS match_profile_scores (
+          match_id TEXT NOT NULL,
+          profile TEXT NOT NULL,
+          match_score REAL NOT NULL,
+          score_breakdown TEXT,
+          rejection TEXT,
+          previous_score REAL,
+          settings_hash TEXT,
+          scorer_version INTEGER,
+          scored_at TEXT,
+          PRIMARY KEY (match_id, profile)
+        );
+        CREATE TABLE IF NOT EXISTS match_feedback (
+          match_id TEXT NOT NULL,
+          profile TEXT NOT NULL,
+          good INTEGER NOT NULL,
+          created_at TEXT NOT NULL,
+          PRIMARY KEY (match_id, profile)
+        );
+        CREATE TABLE IF NOT EXISTS learned_weights (
+          profile TEXT PRIMARY KEY,
+          weights TEXT NOT NULL,
+          samples INTEGER NOT NULL,
+          updated_at TEXT NOT NULL
+        );
+        CREATE TABLE IF NOT EXISTS score_history (
+          id INTEGER PRIMARY KEY AUTOINCREMENT,
+          match_id TEXT NOT NULL,
+          profile TEXT NOT NULL,
+          match_score REAL NOT NULL,
+          score_breakdown TEXT,
+          rejection TEXT,
+          settings_hash TEXT,
+          scorer_version INTEGER,
+          created_at TEXT NOT NULL
+        );
+        CREATE INDEX IF NOT EXISTS score_history_match ON score_history (match_id, profile);
+        CREATE TABLE IF NOT EXISTS snapshot_bodies (
+          content_hash TEXT PRIMARY KEY,
+          size INTEGER NOT NULL,
+          compressed_size INTEGER NOT NULL,
+          body BLOB NOT NULL
+        );
+        CREATE TABLE IF NOT EXISTS page_snapshots (
+          id TEXT PRIMARY KEY,
+          analysis_id TEXT,
+          url TEXT NOT NULL,
+          final_url TEXT NOT NULL,
+          status INTEGER NOT NULL,
+          headers TEXT NOT NULL,
+          encoding TEXT NOT NULL,
+          truncated INTEGER NOT NULL DEFAULT 0,
+          content_hash TEXT NOT NULL,
+          fetched_at TEXT NOT NULL
+        );
+        CREATE INDEX IF NOT EXISTS page_snapshots_analysis ON page_snapshots (analysis_id);
+        CREATE TABLE IF NOT EXISTS feeds (
+          id TEXT PRIMARY KEY,
+          url TEXT NOT NULL UNIQUE,
+          title TEXT,
+          added_at TEXT NOT NULL,
+          polled_at TEXT,
+          last_error TEXT
+        );
+        CREATE TABLE IF NOT EXISTS feed_items (
+          feed_id TEXT NOT NULL,
+          guid TEXT NOT NULL,
+          link TEXT NOT NULL,
+          title TEXT,
+          seen_at TEXT NOT NULL,
+          analysis_id TEXT,
+          PRIMARY KEY (feed_id, guid)
+        );
+        CREATE TABLE IF NOT EXISTS crawl_postings (
+          url TEXT PRIMARY KEY,
+          site TEXT NOT NULL,
+          title TEXT,
+          queued_at TEXT NOT NULL,
+          analysis_id TEXT NOT NULL
         );
+        CREATE TABLE IF NOT EXISTS listing_changes (
+          id INTEGER PRIMARY KEY AUTOINCREMENT,
+          match_id TEXT NOT NULL,
+          detected_at TEXT NOT NULL,
+          kinds TEXT NOT NULL,
+          summary TEXT NOT NULL,
+          diff TEXT NOT NULL,
+          previous_snapshot_id TEXT,
+          snapshot_id TEXT
+        );
+        CREATE INDEX IF NOT EXISTS listing_changes_match ON listing_changes (match_id);
         "#,
         )
         .map_err(|err| format!("create table: {err}"))?;
+        ensure_column(&conn, "job_matches", "insights", "TEXT")?;
+        ensure_column(&conn, "job_matches", "content_hash", "TEXT")?;
+        ensure_column(&conn, "job_matches", "score_breakdown", "TEXT")?;
+        ensure_column(
+            &conn,
+            "job_matches",
+            "decision",
+            "TEXT NOT NULL DEFAULT 'accepted'",
+        )?;
+        ensure_column(&conn, "job_matches", "rejection", "TEXT")?;
+        ensure_column(
+            &conn,
+            "job_matches",
+            "decision_overridden",
+            "INTEGER NOT NULL DEFAULT 0",
+        )?;
+        ensure_column(&conn, "job_matches", "previous_score", "REAL")?;
+        ensure_column(&conn, "job_matches", "listing", "TEXT")?;
+        ensure_column(&conn, "job_matches", "role_family", "TEXT")?;
+        ensure_column(&conn, "job_matches", "closed_at", "TEXT")?;
+        ensure_column(&conn, "job_matches", "watched_at", "TEXT")?;
+        ensure_column(&conn, "match_profile_scores", "rejection", "TEXT")?;
+        ensure_column(&conn, "match_profile_scores", "previous_score", "REAL")?;
+        for table in ["job_matches", "match_profile_scores"] {
+            ensure_column(&conn, table, "settings_hash", "TEXT")?;
+            ensure_column(&conn, table, "scorer_version", "INTEGER")?;
+            ensure_column(&conn, table, "scored_at", "TEXT")?;
+        }
         Ok(Self {
             conn: Arc::new(Mutex::new(conn)),
         })
@@ -79,17 +472,39 @@ impl Db {
         let created_at = created_at.to_rfc3339();
 
         let match_score = input.match_score;
-        let conn = self
+        let insights = input.insights.as_ref().map(|value| value.to_string());
+        let score_breakdown = input
+            .score_breakdown
+            .as_ref()
+            .and_then(|value| serde_json::to_string(value).ok());
+        let decision = if input.rejection.is_some() {
+            Decision::Rejected
+        } else {
+            Decision::Accepted
+        };
+        let rejection = input
+            .rejection
+            .as_ref()
+            .and_then(|value| serde_json::to_string(value).ok());
+        let listing = input
+            .listing
+            .as_ref()
+            .and_then(|value| serde_json::to_string(value).ok());
+        let mut conn = self
             .conn
             .lock()
             .map_err(|_| "db lock poisoned".to_string())?;
-        conn
+        // The match, its profile scores and their history are written together or not at all.
+        let tx = conn
+            .transaction()
+            .map_err(|err| format!("begin insert match: {err}"))?;
+        tx
       .execute(
         r#"
         INSERT INTO job_matches
-          (id, analysis_id, url, title, company, location, match_score, summary, created_at, raw_excerpt)
+          (id, analysis_id, url, title, company, location, match_score, summary, created_at, raw_excerpt, insights, content_hash, score_breakdown, decision, rejection, listing, role_family, settings_hash, scorer_version, scored_at)
         VALUES
-          (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
+          (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?9)
         "#,
         params![
           id,
@@ -101,10 +516,48 @@ impl Db {
           match_score,
           input.summary,
           created_at,
-          input.raw_excerpt
+          input.raw_excerpt,
+          insights,
+          input.content_hash,
+          score_breakdown,
+          decision.as_str(),
+          rejection,
+          listing,
+          input.role_family,
+          input.settings_hash,
+          input.scorer_version
         ],
       )
       .map_err(|err| format!("insert job match: {err}"))?;
+        for profile_score in &input.profile_scores {
+            let breakdown = profile_score
+                .score_breakdown
+                .as_ref()
+                .and_then(|value| serde_json::to_string(value).ok());
+            let rejection = profile_score
+                .rejection
+                .as_ref()
+                .and_then(|value| serde_json::to_string(value).ok());
+            tx.execute(
+                "INSERT OR REPLACE INTO match_profile_scores \
+                 (match_id, profile, match_score, score_breakdown, rejection, settings_hash, scorer_version, scored_at) \
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                params![
+                    id,
+                    profile_score.profile,
+                    profile_score.match_score,
+                    breakdown,
+                    rejection,
+                    profile_score.settings_hash,
+                    profile_score.scorer_version,
+                    created_at
+                ],
+            )
+            .map_err(|err| format!("insert profile score: {err}"))?;
+            record_score(&tx, &id, profile_score, &created_at)?;
+        }
+        tx.commit()
+            .map_err(|err| format!("commit insert match: {err}"))?;
 
         Ok(JobMatch {
             id,
@@ -115,48 +568,1128 @@ impl Db {
             location: input.location,
             match_score,
             summary: input.summary,
-            created_at,
+            created_at: created_at.clone(),
             raw_excerpt: input.raw_excerpt,
+            insights: input.insights,
+            content_hash: input.content_hash,
+            score_breakdown: input.score_breakdown,
+            profile_scores: input.profile_scores,
+            decision,
+            rejection: input.rejection,
+            decision_overridden: false,
+            previous_score: None,
+            role_family: input.role_family,
+            settings_hash: input.settings_hash,
+            scorer_version: input.scorer_version,
+            scored_at: Some(created_at),
+            closed_at: None,
         })
     }
 
-    pub fn list_matches(&self, limit: usize) -> Result<Vec<JobMatch>, String> {
+    pub fn list_matches(&self, query: &MatchQuery) -> Result<Vec<JobMatch>, String> {
         let conn = self
             .conn
             .lock()
             .map_err(|_| "db lock poisoned".to_string())?;
+        let columns = match_columns("m");
+        let mut matches = match &query.profile {
+            Some(profile) => {
+                let order = match query.sort {
+                    MatchSort::Recent => "datetime(m.created_at) DESC",
+                    MatchSort::Score => "s.match_score DESC",
+                };
+                // A manual decision applies to every profile; otherwise the profile's own filters decide.
+                let filter = if query.include_rejected {
+                    ""
+                } else {
+                    "WHERE (m.decision_overridden = 1 AND m.decision = 'accepted') \
+                     OR (m.decision_overridden = 0 AND s.rejection IS NULL)"
+                };
+                let mut stmt = conn
+                    .prepare(&format!(
+                        "SELECT {columns}, s.match_score, s.rejection, s.previous_score, s.settings_hash, \
+                         s.scorer_version, s.scored_at FROM job_matches m \
+                         JOIN match_profile_scores s ON s.match_id = m.id AND s.profile = ?2 \
+                         {filter} ORDER BY {order} LIMIT ?1"
+                    ))
+                    .map_err(|err| format!("prepare query: {err}"))?;
+                let rows = stmt
+                    .query_map(params![query.limit as i64, profile], |row| {
+                        let mut job_match = row_to_match(row)?;
+                        job_match.match_score = row.get(MATCH_COLUMN_COUNT)?;
+                        job_match.previous_score = row.get(MATCH_COLUMN_COUNT + 2)?;
+                        job_match.settings_hash = row.get(MATCH_COLUMN_COUNT + 3)?;
+                        job_match.scorer_version = row.get(MATCH_COLUMN_COUNT + 4)?;
+                        job_match.scored_at = row.get(MATCH_COLUMN_COUNT + 5)?;
+                        if !job_match.decision_overridden {
+                            job_match.rejection = row
+                                .get::<_, Option<String>>(MATCH_COLUMN_COUNT + 1)?
+                                .and_then(|value| serde_json::from_str(&value).ok());
+                            job_match.decision = if job_match.rejection.is_some() {
+                                Decision::Rejected
+                            } else {
+                                Decision::Accepted
+                            };
+                        }
+                        Ok(job_match)
+                    })
+                    .map_err(|err| format!("query job matches: {err}"))?;
+                rows.collect::<Result<Vec<_>, _>>()
+                    .map_err(|err| format!("row parse: {err}"))?
+            }
+            None => {
+                let order = match query.sort {
+                    MatchSort::Recent => "datetime(m.created_at) DESC",
+                    MatchSort::Score => "m.match_score DESC",
+                };
+                let filter = if query.include_rejected {
+                    ""
+                } else {
+                    "WHERE m.decision = 'accepted'"
+                };
+                let mut stmt = conn
+                    .prepare(&format!(
+                        "SELECT {columns} FROM job_matches m {filter} ORDER BY {order} LIMIT ?1"
+                    ))
+                    .map_err(|err| format!("prepare query: {err}"))?;
+                let rows = stmt
+                    .query_map([query.limit as i64], row_to_match)
+                    .map_err(|err| format!("query job matches: {err}"))?;
+                rows.collect::<Result<Vec<_>, _>>()
+                    .map_err(|err| format!("row parse: {err}"))?
+            }
+        };
+
         let mut stmt = conn
-      .prepare(
-        r#"
-        SELECT id, analysis_id, url, title, company, location, match_score, summary, created_at, raw_excerpt
-        FROM job_matches
-        ORDER BY datetime(created_at) DESC
-        LIMIT ?1
-        "#,
-      )
-      .map_err(|err| format!("prepare query: {err}"))?;
-        let rows = stmt
-            .query_map([limit as i64], |row| {
-                Ok(JobMatch {
-                    id: row.get(0)?,
-                    analysis_id: row.get(1)?,
-                    url: row.get(2)?,
-                    title: row.get(3)?,
-                    company: row.get(4)?,
-                    location: row.get(5)?,
-                    match_score: row.get::<_, f64>(6)?,
-                    summary: row.get(7)?,
-                    created_at: row.get(8)?,
-                    raw_excerpt: row.get(9)?,
+            .prepare(
+                "SELECT profile, match_score, score_breakdown, rejection, previous_score, settings_hash, \
+                 scorer_version FROM match_profile_scores \
+                 WHERE match_id = ?1 ORDER BY profile",
+            )
+            .map_err(|err| format!("prepare query: {err}"))?;
+        for job_match in matches.iter_mut() {
+            let rows = stmt
+                .query_map([&job_match.id], |row| {
+                    Ok(ProfileScore {
+                        profile: row.get(0)?,
+                        match_score: row.get(1)?,
+                        score_breakdown: row
+                            .get::<_, Option<String>>(2)?
+                            .and_then(|value| serde_json::from_str(&value).ok()),
+                        rejection: row
+                            .get::<_, Option<String>>(3)?
+                            .and_then(|value| serde_json::from_str(&value).ok()),
+                        previous_score: row.get(4)?,
+                        settings_hash: row.get(5)?,
+                        scorer_version: row.get(6)?,
+                    })
                 })
+                .map_err(|err| format!("query profile scores: {err}"))?;
+            job_match.profile_scores = rows
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|err| format!("row parse: {err}"))?;
+        }
+        Ok(matches)
+    }
+
+    /// Returns the cached embedding for `text`, computing and storing it on a miss.
+    pub fn embedding_for(&self, text: &str) -> Result<(String, Vec<f32>), String> {
+        let content_hash = embedding::content_hash(text);
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|_| "db lock poisoned".to_string())?;
+        let cached: Option<Vec<u8>> = conn
+            .query_row(
+                "SELECT vector FROM embeddings WHERE content_hash = ?1 AND model = ?2",
+                params![content_hash, embedding::MODEL_ID],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(|err| format!("load embedding: {err}"))?;
+        if let Some(bytes) = cached {
+            return Ok((content_hash, embedding::from_bytes(&bytes)));
+        }
+
+        let vector = embedding::embed(text);
+        conn.execute(
+            "INSERT OR REPLACE INTO embeddings (content_hash, model, vector, created_at) VALUES (?1, ?2, ?3, ?4)",
+            params![
+                content_hash,
+                embedding::MODEL_ID,
+                embedding::to_bytes(&vector),
+                Utc::now().to_rfc3339()
+            ],
+        )
+        .map_err(|err| format!("store embedding: {err}"))?;
+        Ok((content_hash, vector))
+    }
+
+    /// Ranks stored matches by cosine similarity to `reference`, skipping `exclude_id`.
+    pub fn find_similar(
+        &self,
+        reference: &[f32],
+        exclude_id: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<SimilarJob>, String> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|_| "db lock poisoned".to_string())?;
+        let columns = match_columns("m");
+        let mut stmt = conn
+            .prepare(&format!(
+                "SELECT {columns}, e.vector FROM job_matches m \
+                 JOIN embeddings e ON e.content_hash = m.content_hash AND e.model = ?1"
+            ))
+            .map_err(|err| format!("prepare query: {err}"))?;
+        let rows = stmt
+            .query_map([embedding::MODEL_ID], |row| {
+                let vector: Vec<u8> = row.get(MATCH_COLUMN_COUNT)?;
+                Ok((row_to_match(row)?, embedding::from_bytes(&vector)))
             })
             .map_err(|err| format!("query job matches: {err}"))?;
 
-        let mut matches = Vec::new();
+        let mut similar = Vec::new();
         for row in rows {
-            matches.push(row.map_err(|err| format!("row parse: {err}"))?);
+            let (job_match, vector) = row.map_err(|err| format!("row parse: {err}"))?;
+            if exclude_id == Some(job_match.id.as_str()) {
+                continue;
+            }
+            similar.push(SimilarJob {
+                similarity: embedding::cosine(reference, &vector),
+                job_match,
+            });
         }
-        Ok(matches)
+        similar.sort_by(|a, b| b.similarity.total_cmp(&a.similarity));
+        similar.truncate(limit);
+        Ok(similar)
+    }
+
+    /// Loads the cached embedding of a stored match's content.
+    pub fn match_embedding(&self, match_id: &str) -> Result<Vec<f32>, String> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|_| "db lock poisoned".to_string())?;
+        let bytes: Option<Vec<u8>> = conn
+            .query_row(
+                "SELECT e.vector FROM job_matches m \
+                 JOIN embeddings e ON e.content_hash = m.content_hash AND e.model = ?2 \
+                 WHERE m.id = ?1",
+                params![match_id, embedding::MODEL_ID],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(|err| format!("load embedding: {err}"))?;
+        bytes
+            .map(|bytes| embedding::from_bytes(&bytes))
+            .ok_or_else(|| format!("no embedding stored for match {match_id}"))
+    }
+
+    pub fn record_feedback(&self, match_id: &str, profile: &str, good: bool) -> Result<(), String> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|_| "db lock poisoned".to_string())?;
+        let exists: Option<String> = conn
+            .query_row(
+                "SELECT id FROM job_matches WHERE id = ?1",
+                [match_id],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(|err| format!("load job match: {err}"))?;
+        if exists.is_none() {
+            return Err(format!("job match not found: {match_id}"));
+        }
+        conn.execute(
+            "INSERT OR REPLACE INTO match_feedback (match_id, profile, good, created_at) VALUES (?1, ?2, ?3, ?4)",
+            params![match_id, profile, good, Utc::now().to_rfc3339()],
+        )
+        .map_err(|err| format!("record feedback: {err}"))?;
+        Ok(())
+    }
+
+    /// Returns the scoring features and rating of every rated match that has a stored breakdown.
+    pub fn feedback_samples(&self, profile: &str) -> Result<Vec<FeedbackSample>, String> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|_| "db lock poisoned".to_string())?;
+        let mut stmt = conn
+            .prepare(
+                "SELECT COALESCE(s.score_breakdown, m.score_breakdown), f.good FROM match_feedback f \
+                 JOIN job_matches m ON m.id = f.match_id \
+                 LEFT JOIN match_profile_scores s ON s.match_id = f.match_id AND s.profile = f.profile \
+                 WHERE f.profile = ?1 AND COALESCE(s.score_breakdown, m.score_breakdown) IS NOT NULL",
+            )
+            .map_err(|err| format!("prepare query: {err}"))?;
+        let rows = stmt
+            .query_map([profile], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, bool>(1)?))
+            })
+            .map_err(|err| format!("query feedback: {err}"))?;
+
+        let mut samples = Vec::new();
+        for row in rows {
+            let (breakdown, good) = row.map_err(|err| format!("row parse: {err}"))?;
+            if let Ok(breakdown) = serde_json::from_str::<ScoreBreakdown>(&breakdown) {
+                samples.push((breakdown.features(), good));
+            }
+        }
+        Ok(samples)
+    }
+
+    pub fn load_learned_weights(&self, profile: &str) -> Result<Option<LearnedWeights>, String> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|_| "db lock poisoned".to_string())?;
+        let row: Option<(String, i64, String)> = conn
+            .query_row(
+                "SELECT weights, samples, updated_at FROM learned_weights WHERE profile = ?1",
+                [profile],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .optional()
+            .map_err(|err| format!("load learned weights: {err}"))?;
+        match row {
+            Some((weights, samples, updated_at)) => Ok(Some(LearnedWeights {
+                weights: serde_json::from_str(&weights)
+                    .map_err(|err| format!("learned weights parse: {err}"))?,
+                samples: samples as usize,
+                updated_at,
+            })),
+            None => Ok(None),
+        }
+    }
+
+    pub fn save_learned_weights(
+        &self,
+        profile: &str,
+        weights: &ScoreWeights,
+        samples: usize,
+    ) -> Result<(), String> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|_| "db lock poisoned".to_string())?;
+        let weights =
+            serde_json::to_string(weights).map_err(|err| format!("learned weights: {err}"))?;
+        conn.execute(
+            "INSERT OR REPLACE INTO learned_weights (profile, weights, samples, updated_at) VALUES (?1, ?2, ?3, ?4)",
+            params![profile, weights, samples as i64, Utc::now().to_rfc3339()],
+        )
+        .map_err(|err| format!("save learned weights: {err}"))?;
+        Ok(())
+    }
+
+    /// Drops the learned weights and the ratings they were trained on.
+    pub fn reset_learned_weights(&self, profile: &str) -> Result<(), String> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|_| "db lock poisoned".to_string())?;
+        conn.execute("DELETE FROM learned_weights WHERE profile = ?1", [profile])
+            .map_err(|err| format!("reset learned weights: {err}"))?;
+        conn.execute("DELETE FROM match_feedback WHERE profile = ?1", [profile])
+            .map_err(|err| format!("reset feedback: {err}"))?;
+        Ok(())
+    }
+
+    /// Loads the extracted listings of all matches that stored one, with their cached embeddings.
+    pub fn stored_listings(&self) -> Result<Vec<StoredListing>, String> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|_| "db lock poisoned".to_string())?;
+        let mut stmt = conn
+            .prepare(
+                "SELECT m.id, m.listing, e.vector FROM job_matches m \
+                 LEFT JOIN embeddings e ON e.content_hash = m.content_hash AND e.model = ?1 \
+                 WHERE m.listing IS NOT NULL ORDER BY datetime(m.created_at) DESC",
+            )
+            .map_err(|err| format!("prepare query: {err}"))?;
+        let rows = stmt
+            .query_map([embedding::MODEL_ID], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, Option<Vec<u8>>>(2)?,
+                ))
+            })
+            .map_err(|err| format!("query listings: {err}"))?;
+        let mut listings = Vec::new();
+        for row in rows {
+            let (match_id, listing, vector) = row.map_err(|err| format!("row parse: {err}"))?;
+            match serde_json::from_str(&listing) {
+                Ok(listing) => listings.push(StoredListing {
+                    match_id,
+                    listing,
+                    embedding: vector.map(|bytes| embedding::from_bytes(&bytes)),
+                }),
+                Err(err) => log::warn!("skipping unreadable listing for match {match_id}: {err}"),
+            }
+        }
+        Ok(listings)
+    }
+
+    /// Loads accepted matches with the given profile's score, falling back to the match score.
+    pub fn skill_sources(&self, profile: &str) -> Result<Vec<SkillSource>, String> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|_| "db lock poisoned".to_string())?;
+        let mut stmt = conn
+            .prepare(
+                "SELECT m.id, m.url, m.title, m.company, COALESCE(s.match_score, m.match_score), \
+                 m.listing, m.raw_excerpt, m.insights FROM job_matches m \
+                 LEFT JOIN match_profile_scores s ON s.match_id = m.id AND s.profile = ?1 \
+                 WHERE m.decision = 'accepted' ORDER BY datetime(m.created_at) DESC",
+            )
+            .map_err(|err| format!("prepare query: {err}"))?;
+        let rows = stmt
+            .query_map([profile], |row| {
+                let listing: Option<ExtractedListing> = row
+                    .get::<_, Option<String>>(5)?
+                    .and_then(|value| serde_json::from_str(&value).ok());
+                let insights: Option<Value> = row
+                    .get::<_, Option<String>>(7)?
+                    .and_then(|value| serde_json::from_str(&value).ok());
+                Ok(SkillSource {
+                    match_id: row.get(0)?,
+                    url: row.get(1)?,
+                    title: row.get(2)?,
+                    company: row.get(3)?,
+                    match_score: row.get(4)?,
+                    text: match listing {
+                        Some(listing) => listing.text,
+                        None => row.get::<_, Option<String>>(6)?.unwrap_or_default(),
+                    },
+                    llm_skills: insights
+                        .as_ref()
+                        .and_then(|value| value.get("skills"))
+                        .and_then(|value| serde_json::from_value(value.clone()).ok())
+                        .unwrap_or_default(),
+                })
+            })
+            .map_err(|err| format!("query job matches: {err}"))?;
+        rows.collect::<Result<Vec<_>, _>>()
+            .map_err(|err| format!("row parse: {err}"))
+    }
+
+    pub fn count_matches(&self) -> Result<usize, String> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|_| "db lock poisoned".to_string())?;
+        conn.query_row("SELECT COUNT(*) FROM job_matches", [], |row| {
+            row.get::<_, i64>(0)
+        })
+        .map(|count| count as usize)
+        .map_err(|err| format!("count job matches: {err}"))
+    }
+
+    pub fn match_score(&self, match_id: &str) -> Result<Option<f64>, String> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|_| "db lock poisoned".to_string())?;
+        conn.query_row(
+            "SELECT match_score FROM job_matches WHERE id = ?1",
+            [match_id],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|err| format!("load match score: {err}"))
+    }
+
+    /// Stores new scores for a match, keeping the current ones as `previous_score`. A manual
+    /// decision is kept; otherwise the decision follows the new rejection.
+    pub fn update_scores(&self, match_id: &str, rescore: &Rescore) -> Result<(), String> {
+        let mut conn = self
+            .conn
+            .lock()
+            .map_err(|_| "db lock poisoned".to_string())?;
+        let tx = conn
+            .transaction()
+            .map_err(|err| format!("begin update scores: {err}"))?;
+        let scored_at = Utc::now().to_rfc3339();
+        let breakdown = serde_json::to_string(&rescore.score_breakdown).ok();
+        let rejection = rescore
+            .rejection
+            .as_ref()
+            .and_then(|value| serde_json::to_string(value).ok());
+        let decision = if rescore.rejection.is_some() {
+            Decision::Rejected
+        } else {
+            Decision::Accepted
+        };
+        tx.execute(
+            "UPDATE job_matches SET previous_score = match_score, match_score = ?2, \
+             score_breakdown = ?3, rejection = ?4, \
+             decision = CASE WHEN decision_overridden = 1 THEN decision ELSE ?5 END, \
+             settings_hash = ?6, scorer_version = ?7, scored_at = ?8 \
+             WHERE id = ?1",
+            params![
+                match_id,
+                rescore.match_score,
+                breakdown,
+                rejection,
+                decision.as_str(),
+                rescore.settings_hash,
+                rescore.scorer_version,
+                scored_at
+            ],
+        )
+        .map_err(|err| format!("update scores: {err}"))?;
+        for profile_score in &rescore.profile_scores {
+            let breakdown = profile_score
+                .score_breakdown
+                .as_ref()
+                .and_then(|value| serde_json::to_string(value).ok());
+            let rejection = profile_score
+                .rejection
+                .as_ref()
+                .and_then(|value| serde_json::to_string(value).ok());
+            tx.execute(
+                "INSERT INTO match_profile_scores \
+                 (match_id, profile, match_score, score_breakdown, rejection, settings_hash, scorer_version, scored_at) \
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8) \
+                 ON CONFLICT(match_id, profile) DO UPDATE SET previous_score = match_score, \
+                 match_score = excluded.match_score, score_breakdown = excluded.score_breakdown, \
+                 rejection = excluded.rejection, settings_hash = excluded.settings_hash, \
+                 scorer_version = excluded.scorer_version, scored_at = excluded.scored_at",
+                params![
+                    match_id,
+                    profile_score.profile,
+                    profile_score.match_score,
+                    breakdown,
+                    rejection,
+                    profile_score.settings_hash,
+                    profile_score.scorer_version,
+                    scored_at
+                ],
+            )
+            .map_err(|err| format!("update profile score: {err}"))?;
+            record_score(&tx, match_id, profile_score, &scored_at)?;
+        }
+        tx.commit()
+            .map_err(|err| format!("commit update scores: {err}"))
+    }
+
+    /// Every score recorded for a match, oldest first, optionally for one profile only.
+    pub fn score_history(
+        &self,
+        match_id: &str,
+        profile: Option<&str>,
+    ) -> Result<Vec<ScoreRecord>, String> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|_| "db lock poisoned".to_string())?;
+        let mut stmt = conn
+            .prepare(
+                "SELECT profile, match_score, score_breakdown, rejection, settings_hash, scorer_version, \
+                 created_at FROM score_history WHERE match_id = ?1 AND (?2 IS NULL OR profile = ?2) \
+                 ORDER BY id",
+            )
+            .map_err(|err| format!("prepare query: {err}"))?;
+        let rows = stmt
+            .query_map(params![match_id, profile], |row| {
+                Ok(ScoreRecord {
+                    profile: row.get(0)?,
+                    match_score: row.get(1)?,
+                    score_breakdown: row
+                        .get::<_, Option<String>>(2)?
+                        .and_then(|value| serde_json::from_str(&value).ok()),
+                    rejection: row
+                        .get::<_, Option<String>>(3)?
+                        .and_then(|value| serde_json::from_str(&value).ok()),
+                    settings_hash: row.get(4)?,
+                    scorer_version: row.get(5)?,
+                    created_at: row.get(6)?,
+                })
+            })
+            .map_err(|err| format!("query score history: {err}"))?;
+        rows.collect::<Result<Vec<_>, _>>()
+            .map_err(|err| format!("row parse: {err}"))
+    }
+
+    /// Overrides the filter decision for a match; `None` restores the decision the filters made.
+    pub fn set_decision(&self, match_id: &str, decision: Option<Decision>) -> Result<(), String> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|_| "db lock poisoned".to_string())?;
+        let updated = match decision {
+            Some(decision) => conn.execute(
+                "UPDATE job_matches SET decision = ?2, decision_overridden = 1 WHERE id = ?1",
+                params![match_id, decision.as_str()],
+            ),
+            None => conn.execute(
+                "UPDATE job_matches SET decision = CASE WHEN rejection IS NULL THEN 'accepted' ELSE 'rejected' END, \
+                 decision_overridden = 0 WHERE id = ?1",
+                [match_id],
+            ),
+        }
+        .map_err(|err| format!("update decision: {err}"))?;
+        if updated == 0 {
+            return Err(format!("job match not found: {match_id}"));
+        }
+        Ok(())
+    }
+
+    /// Moves per-profile scores, ratings and learned weights to a renamed profile.
+    pub fn rename_profile(&self, from: &str, to: &str) -> Result<(), String> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|_| "db lock poisoned".to_string())?;
+        for table in [
+            "match_profile_scores",
+            "match_feedback",
+            "learned_weights",
+            "score_history",
+        ] {
+            conn.execute(
+                &format!("UPDATE {table} SET profile = ?2 WHERE profile = ?1"),
+                params![from, to],
+            )
+            .map_err(|err| format!("rename profile in {table}: {err}"))?;
+        }
+        Ok(())
+    }
+
+    pub fn delete_profile(&self, profile: &str) -> Result<(), String> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|_| "db lock poisoned".to_string())?;
+        for table in [
+            "match_profile_scores",
+            "match_feedback",
+            "learned_weights",
+            "score_history",
+        ] {
+            conn.execute(
+                &format!("DELETE FROM {table} WHERE profile = ?1"),
+                [profile],
+            )
+            .map_err(|err| format!("delete profile from {table}: {err}"))?;
+        }
+        Ok(())
+    }
+
+    /// Archives a fetched page, storing its body once per distinct content and then pruning
+    /// the archive back under `max_total_bytes` of compressed bodies.
+    pub fn insert_snapshot(
+        &self,
+        input: &SnapshotInput,
+        max_total_bytes: u64,
+    ) -> Result<String, String> {
+        let content_hash = format!("{:x}", Sha256::digest(input.html.as_bytes()));
+        let id = Uuid::new_v4().to_string();
+        let headers = serde_json::to_string(input.headers)
+            .map_err(|err| format!("serialize snapshot headers: {err}"))?;
+        let mut conn = self
+            .conn
+            .lock()
+            .map_err(|_| "db lock poisoned".to_string())?;
+        // The body, its snapshot row and the pruning land together or not at all, so a failure
+        // never leaves an orphaned body or a snapshot over the size budget.
+        let tx = conn
+            .transaction()
+            .map_err(|err| format!("begin snapshot: {err}"))?;
+        let stored: bool = tx
+            .query_row(
+                "SELECT EXISTS(SELECT 1 FROM snapshot_bodies WHERE content_hash = ?1)",
+                [&content_hash],
+                |row| row.get(0),
+            )
+            .map_err(|err| format!("check snapshot body: {err}"))?;
+        if !stored {
+            let body = compress(input.html.as_bytes())?;
+            tx.execute(
+                "INSERT INTO snapshot_bodies (content_hash, size, compressed_size, body) \
+                 VALUES (?1, ?2, ?3, ?4)",
+                params![
+                    content_hash,
+                    input.html.len() as i64,
+                    body.len() as i64,
+                    body
+                ],
+            )
+            .map_err(|err| format!("insert snapshot body: {err}"))?;
+        }
+        tx.execute(
+            "INSERT INTO page_snapshots \
+             (id, analysis_id, url, final_url, status, headers, encoding, truncated, content_hash, fetched_at) \
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+            params![
+                id,
+                input.analysis_id,
+                input.url,
+                input.final_url,
+                input.status,
+                headers,
+                input.encoding,
+                input.truncated,
+                content_hash,
+                Utc::now().to_rfc3339()
+            ],
+        )
+        .map_err(|err| format!("insert snapshot: {err}"))?;
+        prune_snapshots(&tx, max_total_bytes, &id)?;
+        tx.commit()
+            .map_err(|err| format!("commit snapshot: {err}"))?;
+        Ok(id)
+    }
+
+    /// Loads a snapshot by id, or the latest one from the analysis that saved `match_id`.
+    pub fn snapshot(
+        &self,
+        snapshot_id: Option<&str>,
+        match_id: Option<&str>,
+    ) -> Result<Option<Snapshot>, String> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|_| "db lock poisoned".to_string())?;
+        let columns = "p.id, p.analysis_id, p.url, p.final_url, p.status, p.headers, p.encoding, \
+                       p.truncated, p.fetched_at, p.content_hash, b.size, b.compressed_size, b.body";
+        let (sql, key) = match (snapshot_id, match_id) {
+            (Some(snapshot_id), _) => (
+                format!(
+                    "SELECT {columns} FROM page_snapshots p \
+                     JOIN snapshot_bodies b ON b.content_hash = p.content_hash WHERE p.id = ?1"
+                ),
+                snapshot_id,
+            ),
+            (None, Some(match_id)) => (
+                format!(
+                    "SELECT {columns} FROM page_snapshots p \
+                     JOIN snapshot_bodies b ON b.content_hash = p.content_hash \
+                     JOIN job_matches m ON m.analysis_id = p.analysis_id WHERE m.id = ?1 \
+                     ORDER BY datetime(p.fetched_at) DESC LIMIT 1"
+                ),
+                match_id,
+            ),
+            (None, None) => return Err("snapshotId or matchId is required".to_string()),
+        };
+        let row = conn
+            .query_row(&sql, [key], |row| {
+                Ok((
+                    Snapshot {
+                        id: row.get(0)?,
+                        analysis_id: row.get(1)?,
+                        url: row.get(2)?,
+                        final_url: row.get(3)?,
+                        status: row.get(4)?,
+                        headers: serde_json::from_str(&row.get::<_, String>(5)?)
+                            .unwrap_or_default(),
+                        encoding: row.get(6)?,
+                        truncated: row.get(7)?,
+                        fetched_at: row.get(8)?,
+                        content_hash: row.get(9)?,
+                        size: row.get(10)?,
+                        compressed_size: row.get(11)?,
+                        html: String::new(),
+                    },
+                    row.get::<_, Vec<u8>>(12)?,
+                ))
+            })
+            .optional()
+            .map_err(|err| format!("load snapshot: {err}"))?;
+        row.map(|(mut snapshot, body)| {
+            snapshot.html = decompress(&body)?;
+            Ok(snapshot)
+        })
+        .transpose()
+    }
+
+    /// Matches saved by the analysis a snapshot belongs to.
+    pub fn snapshot_matches(&self, snapshot_id: &str) -> Result<Vec<String>, String> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|_| "db lock poisoned".to_string())?;
+        let mut stmt = conn
+            .prepare(
+                "SELECT m.id FROM job_matches m \
+                 JOIN page_snapshots p ON p.analysis_id = m.analysis_id WHERE p.id = ?1",
+            )
+            .map_err(|err| format!("prepare query: {err}"))?;
+        let rows = stmt
+            .query_map([snapshot_id], |row| row.get(0))
+            .map_err(|err| format!("query snapshot matches: {err}"))?;
+        rows.collect::<Result<Vec<_>, _>>()
+            .map_err(|err| format!("row parse: {err}"))
+    }
+
+    /// Replaces a match's stored listing, as used for rescoring.
+    pub fn update_listing(&self, match_id: &str, listing: &ExtractedListing) -> Result<(), String> {
+        let listing =
+            serde_json::to_string(listing).map_err(|err| format!("serialize listing: {err}"))?;
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|_| "db lock poisoned".to_string())?;
+        conn.execute(
+            "UPDATE job_matches SET listing = ?2 WHERE id = ?1",
+            params![match_id, listing],
+        )
+        .map_err(|err| format!("update listing: {err}"))?;
+        Ok(())
+    }
+
+    /// Matches to refetch: one by id, or those matching the watch filters that were last
+    /// checked before `checked_before`. Matches without an analysis id have no snapshots to
+    /// compare against and are left out.
+    pub fn watch_targets(
+        &self,
+        match_id: Option<&str>,
+        include_rejected: bool,
+        max_age_days: u32,
+        checked_before: Option<&str>,
+    ) -> Result<Vec<WatchTarget>, String> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|_| "db lock poisoned".to_string())?;
+        let mut stmt = conn
+            .prepare(
+                "SELECT id, analysis_id, url, closed_at IS NOT NULL FROM job_matches \
+                 WHERE analysis_id IS NOT NULL AND (?1 IS NOT NULL AND id = ?1 OR ?1 IS NULL \
+                 AND (?2 OR decision = 'accepted') \
+                 AND datetime(created_at) >= datetime('now', ?3) \
+                 AND (?4 IS NULL OR watched_at IS NULL OR datetime(watched_at) < datetime(?4))) \
+                 ORDER BY watched_at IS NOT NULL, datetime(watched_at)",
+            )
+            .map_err(|err| format!("prepare query: {err}"))?;
+        let rows = stmt
+            .query_map(
+                params![
+                    match_id,
+                    include_rejected,
+                    format!("-{max_age_days} days"),
+                    checked_before
+                ],
+                |row| {
+                    Ok(WatchTarget {
+                        match_id: row.get(0)?,
+                        analysis_id: row.get(1)?,
+                        url: row.get(2)?,
+                        closed: row.get(3)?,
+                    })
+                },
+            )
+            .map_err(|err| format!("query watch targets: {err}"))?;
+        rows.collect::<Result<Vec<_>, _>>()
+            .map_err(|err| format!("row parse: {err}"))
+    }
+
+    /// Records a watch check; `closed` marks the posting gone or back when given.
+    pub fn mark_watched(&self, match_id: &str, closed: Option<bool>) -> Result<(), String> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|_| "db lock poisoned".to_string())?;
+        let now = Utc::now().to_rfc3339();
+        conn.execute(
+            "UPDATE job_matches SET watched_at = ?2, closed_at = CASE \
+             WHEN ?3 IS NULL THEN closed_at WHEN ?3 THEN COALESCE(closed_at, ?2) ELSE NULL END \
+             WHERE id = ?1",
+            params![match_id, now, closed],
+        )
+        .map_err(|err| format!("update watch state: {err}"))?;
+        Ok(())
+    }
+
+    pub fn insert_change(
+        &self,
+        match_id: &str,
+        kinds: &[ChangeKind],
+        summary: &str,
+        diff: &str,
+        previous_snapshot_id: Option<&str>,
+        snapshot_id: Option<&str>,
+    ) -> Result<ListingChange, String> {
+        let detected_at = Utc::now().to_rfc3339();
+        let kinds_json =
+            serde_json::to_string(kinds).map_err(|err| format!("serialize change: {err}"))?;
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|_| "db lock poisoned".to_string())?;
+        conn.execute(
+            "INSERT INTO listing_changes \
+             (match_id, detected_at, kinds, summary, diff, previous_snapshot_id, snapshot_id) \
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                match_id,
+                detected_at,
+                kinds_json,
+                summary,
+                diff,
+                previous_snapshot_id,
+                snapshot_id
+            ],
+        )
+        .map_err(|err| format!("insert listing change: {err}"))?;
+        Ok(ListingChange {
+            id: conn.last_insert_rowid(),
+            match_id: match_id.to_string(),
+            detected_at,
+            kinds: kinds.to_vec(),
+            summary: summary.to_string(),
+            diff: diff.to_string(),
+            previous_snapshot_id: previous_snapshot_id.map(str::to_string),
+            snapshot_id: snapshot_id.map(str::to_string),
+        })
+    }
+
+    /// Recorded changes, newest first, for one match or across all of them.
+    pub fn listing_changes(
+        &self,
+        match_id: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<ListingChange>, String> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|_| "db lock poisoned".to_string())?;
+        let mut stmt = conn
+            .prepare(
+                "SELECT id, match_id, detected_at, kinds, summary, diff, previous_snapshot_id, \
+                 snapshot_id FROM listing_changes WHERE ?1 IS NULL OR match_id = ?1 \
+                 ORDER BY id DESC LIMIT ?2",
+            )
+            .map_err(|err| format!("prepare query: {err}"))?;
+        let rows = stmt
+            .query_map(params![match_id, limit as i64], |row| {
+                Ok(ListingChange {
+                    id: row.get(0)?,
+                    match_id: row.get(1)?,
+                    detected_at: row.get(2)?,
+                    kinds: serde_json::from_str(&row.get::<_, String>(3)?).unwrap_or_default(),
+                    summary: row.get(4)?,
+                    diff: row.get(5)?,
+                    previous_snapshot_id: row.get(6)?,
+                    snapshot_id: row.get(7)?,
+                })
+            })
+            .map_err(|err| format!("query listing changes: {err}"))?;
+        rows.collect::<Result<Vec<_>, _>>()
+            .map_err(|err| format!("row parse: {err}"))
+    }
+
+    pub fn insert_feed(&self, url: &str, title: Option<&str>) -> Result<Feed, String> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|_| "db lock poisoned".to_string())?;
+        let exists: bool = conn
+            .query_row(
+                "SELECT EXISTS(SELECT 1 FROM feeds WHERE url = ?1)",
+                [url],
+                |row| row.get(0),
+            )
+            .map_err(|err| format!("check feed: {err}"))?;
+        if exists {
+            return Err(format!("already subscribed to {url}"));
+        }
+        let feed = Feed {
+            id: Uuid::new_v4().to_string(),
+            url: url.to_string(),
+            title: title.map(str::to_string),
+            added_at: Utc::now().to_rfc3339(),
+            polled_at: None,
+            last_error: None,
+            item_count: 0,
+            analyzed_count: 0,
+        };
+        conn.execute(
+            "INSERT INTO feeds (id, url, title, added_at) VALUES (?1, ?2, ?3, ?4)",
+            params![feed.id, feed.url, feed.title, feed.added_at],
+        )
+        .map_err(|err| format!("insert feed: {err}"))?;
+        Ok(feed)
+    }
+
+    /// Removes a feed, by id or URL, with its seen items.
+    pub fn delete_feed(&self, feed: &str) -> Result<(), String> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|_| "db lock poisoned".to_string())?;
+        let id: Option<String> = conn
+            .query_row(
+                "SELECT id FROM feeds WHERE id = ?1 OR url = ?1",
+                [feed],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(|err| format!("find feed: {err}"))?;
+        let id = id.ok_or_else(|| format!("feed not found: {feed}"))?;
+        conn.execute("DELETE FROM feed_items WHERE feed_id = ?1", [&id])
+            .map_err(|err| format!("delete feed items: {err}"))?;
+        conn.execute("DELETE FROM feeds WHERE id = ?1", [&id])
+            .map_err(|err| format!("delete feed: {err}"))?;
+        Ok(())
+    }
+
+    /// Feeds in the order they were added; with `polled_before`, only those last polled
+    /// before then or never.
+    pub fn list_feeds(&self, polled_before: Option<&str>) -> Result<Vec<Feed>, String> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|_| "db lock poisoned".to_string())?;
+        let mut stmt = conn
+            .prepare(
+                "SELECT f.id, f.url, f.title, f.added_at, f.polled_at, f.last_error, \
+                 (SELECT COUNT(*) FROM feed_items i WHERE i.feed_id = f.id), \
+                 (SELECT COUNT(*) FROM feed_items i WHERE i.feed_id = f.id AND i.analysis_id IS NOT NULL) \
+                 FROM feeds f WHERE ?1 IS NULL OR f.polled_at IS NULL \
+                 OR datetime(f.polled_at) < datetime(?1) ORDER BY datetime(f.added_at)",
+            )
+            .map_err(|err| format!("prepare query: {err}"))?;
+        let rows = stmt
+            .query_map([polled_before], |row| {
+                Ok(Feed {
+                    id: row.get(0)?,
+                    url: row.get(1)?,
+                    title: row.get(2)?,
+                    added_at: row.get(3)?,
+                    polled_at: row.get(4)?,
+                    last_error: row.get(5)?,
+                    item_count: row.get::<_, i64>(6)? as usize,
+                    analyzed_count: row.get::<_, i64>(7)? as usize,
+                })
+            })
+            .map_err(|err| format!("query feeds: {err}"))?;
+        rows.collect::<Result<Vec<_>, _>>()
+            .map_err(|err| format!("row parse: {err}"))
+    }
+
+    /// Records the outcome of a poll, keeping a newly found title.
+    pub fn mark_feed_polled(
+        &self,
+        feed_id: &str,
+        title: Option<&str>,
+        error: Option<&str>,
+    ) -> Result<(), String> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|_| "db lock poisoned".to_string())?;
+        conn.execute(
+            "UPDATE feeds SET polled_at = ?2, title = COALESCE(?3, title), last_error = ?4 \
+             WHERE id = ?1",
+            params![feed_id, Utc::now().to_rfc3339(), title, error],
+        )
+        .map_err(|err| format!("update feed: {err}"))?;
+        Ok(())
+    }
+
+    pub fn feed_item_seen(&self, feed_id: &str, guid: &str) -> Result<bool, String> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|_| "db lock poisoned".to_string())?;
+        conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM feed_items WHERE feed_id = ?1 AND guid = ?2)",
+            params![feed_id, guid],
+            |row| row.get(0),
+        )
+        .map_err(|err| format!("check feed item: {err}"))
+    }
+
+    /// Marks a feed item as seen, with the analysis it started if any.
+    pub fn insert_feed_item(
+        &self,
+        feed_id: &str,
+        guid: &str,
+        link: &str,
+        title: Option<&str>,
+        analysis_id: Option<&str>,
+    ) -> Result<(), String> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|_| "db lock poisoned".to_string())?;
+        conn.execute(
+            "INSERT OR IGNORE INTO feed_items (feed_id, guid, link, title, seen_at, analysis_id) \
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                feed_id,
+                guid,
+                link,
+                title,
+                Utc::now().to_rfc3339(),
+                analysis_id
+            ],
+        )
+        .map_err(|err| format!("insert feed item: {err}"))?;
+        Ok(())
+    }
+
+    pub fn has_match_for_url(&self, url: &str) -> Result<bool, String> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|_| "db lock poisoned".to_string())?;
+        conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM job_matches WHERE url = ?1)",
+            [url],
+            |row| row.get(0),
+        )
+        .map_err(|err| format!("check job match: {err}"))
+    }
+
+    /// Whether a crawl already queued `url` for analysis.
+    pub fn crawl_posting_queued(&self, url: &str) -> Result<bool, String> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|_| "db lock poisoned".to_string())?;
+        conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM crawl_postings WHERE url = ?1)",
+            [url],
+            |row| row.get(0),
+        )
+        .map_err(|err| format!("check crawl posting: {err}"))
+    }
+
+    pub fn insert_crawl_posting(
+        &self,
+        url: &str,
+        site: &str,
+        title: Option<&str>,
+        analysis_id: &str,
+    ) -> Result<(), String> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|_| "db lock poisoned".to_string())?;
+        conn.execute(
+            "INSERT OR REPLACE INTO crawl_postings (url, site, title, queued_at, analysis_id) \
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![url, site, title, Utc::now().to_rfc3339(), analysis_id],
+        )
+        .map_err(|err| format!("insert crawl posting: {err}"))?;
+        Ok(())
+    }
+
+    /// Applies a new size limit to the snapshot archive.
+    pub fn prune_snapshots(&self, max_total_bytes: u64) -> Result<(), String> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|_| "db lock poisoned".to_string())?;
+        prune_snapshots(&conn, max_total_bytes, "")
     }
 
     pub fn clear(&self) -> Result<(), String> {
@@ -166,6 +1699,170 @@ impl Db {
             .map_err(|_| "db lock poisoned".to_string())?;
         conn.execute("DELETE FROM job_matches", [])
             .map_err(|err| format!("clear job matches: {err}"))?;
+        conn.execute("DELETE FROM match_feedback", [])
+            .map_err(|err| format!("clear feedback: {err}"))?;
+        conn.execute("DELETE FROM match_profile_scores", [])
+            .map_err(|err| format!("clear profile scores: {err}"))?;
+        conn.execute("DELETE FROM score_history", [])
+            .map_err(|err| format!("clear score history: {err}"))?;
+        conn.execute("DELETE FROM listing_changes", [])
+            .map_err(|err| format!("clear listing changes: {err}"))?;
         Ok(())
     }
 }
+
+fn match_columns(alias: &str) -> String {
+    MATCH_COLUMNS
+        .split(", ")
+        .map(|column| format!("{alias}.{column}"))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn row_to_match(row: &Row) -> rusqlite::Result<JobMatch> {
+    Ok(JobMatch {
+        id: row.get(0)?,
+        analysis_id: row.get(1)?,
+        url: row.get(2)?,
+        title: row.get(3)?,
+        company: row.get(4)?,
+        location: row.get(5)?,
+        match_score: row.get::<_, f64>(6)?,
+        summary: row.get(7)?,
+        created_at: row.get(8)?,
+        raw_excerpt: row.get(9)?,
+        insights: row
+            .get::<_, Option<String>>(10)?
+            .and_then(|value| serde_json::from_str(&value).ok()),
+        content_hash: row.get(11)?,
+        score_breakdown: row
+            .get::<_, Option<String>>(12)?
+            .and_then(|value| serde_json::from_str(&value).ok()),
+        profile_scores: Vec::new(),
+        decision: Decision::parse(&row.get::<_, String>(13)?),
+        rejection: row
+            .get::<_, Option<String>>(14)?
+            .and_then(|value| serde_json::from_str(&value).ok()),
+        decision_overridden: row.get::<_, i64>(15)? != 0,
+        previous_score: row.get(16)?,
+        role_family: row.get(17)?,
+        settings_hash: row.get(18)?,
+        scorer_version: row.get(19)?,
+        scored_at: row.get(20)?,
+        closed_at: row.get(21)?,
+    })
+}
+
+/// Appends a profile score to the match's score history.
+fn record_score(
+    conn: &Connection,
+    match_id: &str,
+    score: &ProfileScore,
+    created_at: &str,
+) -> Result<(), String> {
+    let breakdown = score
+        .score_breakdown
+        .as_ref()
+        .and_then(|value| serde_json::to_string(value).ok());
+    let rejection = score
+        .rejection
+        .as_ref()
+        .and_then(|value| serde_json::to_string(value).ok());
+    conn.execute(
+        "INSERT INTO score_history \
+         (match_id, profile, match_score, score_breakdown, rejection, settings_hash, scorer_version, created_at) \
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        params![
+            match_id,
+            score.profile,
+            score.match_score,
+            breakdown,
+            rejection,
+            score.settings_hash,
+            score.scorer_version,
+            created_at
+        ],
+    )
+    .map_err(|err| format!("record score history: {err}"))?;
+    Ok(())
+}
+
+fn compress(bytes: &[u8]) -> Result<Vec<u8>, String> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder
+        .write_all(bytes)
+        .and_then(|_| encoder.finish())
+        .map_err(|err| format!("compress snapshot: {err}"))
+}
+
+fn decompress(bytes: &[u8]) -> Result<String, String> {
+    let mut html = String::new();
+    GzDecoder::new(bytes)
+        .read_to_string(&mut html)
+        .map_err(|err| format!("decompress snapshot: {err}"))?;
+    Ok(html)
+}
+
+/// Deletes snapshots until the stored bodies fit in `max_total_bytes`, starting with those no
+/// saved match refers to, oldest first. `keep` is never deleted, so the newest page survives
+/// even when it alone exceeds the limit.
+fn prune_snapshots(conn: &Connection, max_total_bytes: u64, keep: &str) -> Result<(), String> {
+    let total = |conn: &Connection| {
+        conn.query_row(
+            "SELECT COALESCE(SUM(compressed_size), 0) FROM snapshot_bodies",
+            [],
+            |row| row.get::<_, i64>(0),
+        )
+        .map(|total| total.max(0) as u64)
+        .map_err(|err| format!("measure snapshot archive: {err}"))
+    };
+    while total(conn)? > max_total_bytes {
+        let oldest: Option<String> = conn
+            .query_row(
+                "SELECT p.id FROM page_snapshots p WHERE p.id != ?1 \
+                 ORDER BY EXISTS(SELECT 1 FROM job_matches m WHERE m.analysis_id = p.analysis_id), \
+                 datetime(p.fetched_at) LIMIT 1",
+                [keep],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(|err| format!("find oldest snapshot: {err}"))?;
+        let Some(oldest) = oldest else {
+            break;
+        };
+        conn.execute("DELETE FROM page_snapshots WHERE id = ?1", [oldest])
+            .map_err(|err| format!("prune snapshot: {err}"))?;
+        conn.execute(
+            "DELETE FROM snapshot_bodies WHERE content_hash NOT IN \
+             (SELECT content_hash FROM page_snapshots)",
+            [],
+        )
+        .map_err(|err| format!("prune snapshot bodies: {err}"))?;
+    }
+    Ok(())
+}
+
+/// Adds a column to an existing table when an older database predates it.
+fn ensure_column(
+    conn: &Connection,
+    table: &str,
+    column: &str,
+    definition: &str,
+) -> Result<(), String> {
+    let mut stmt = conn
+        .prepare(&format!("PRAGMA table_info({table})"))
+        .map_err(|err| format!("inspect {table}: {err}"))?;
+    let columns = stmt
+        .query_map([], |row| row.get::<_, String>(1))
+        .map_err(|err| format!("inspect {table}: {err}"))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|err| format!("inspect {table}: {err}"))?;
+    if !columns.iter().any(|name| name == column) {
+        conn.execute(
+            &format!("ALTER TABLE {table} ADD COLUMN {column} {definition}"),
+            [],
+        )
+        .map_err(|err| format!("migrate {table}.{column}: {err}"))?;
+    }
+    Ok(())
+}
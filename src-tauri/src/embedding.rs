@@ -0,0 +1,108 @@
+use crate::settings::JobSettings;
+
+/// Identifies the vector space so cached vectors are recomputed if the model changes.
+pub const MODEL_ID: &str = "hashed-ngram-v1";
+pub const DIMENSIONS: usize = 512;
+
+const WORD_WEIGHT: f32 = 1.0;
+const BIGRAM_WEIGHT: f32 = 0.7;
+const TRIGRAM_WEIGHT: f32 = 0.4;
+
+const STOPWORDS: &[&str] = &[
+    "a", "an", "and", "are", "as", "at", "be", "by", "for", "from", "in", "is", "it", "of", "on",
+    "or", "our", "that", "the", "this", "to", "we", "will", "with", "you", "your",
+];
+
+/// Embeds text into a fixed-size, L2-normalized vector using signed feature hashing over
+/// words, word bigrams and character trigrams. Runs on CPU with no model files.
+pub fn embed(text: &str) -> Vec<f32> {
+    let mut vector = vec![0.0f32; DIMENSIONS];
+    let lowered = text.to_lowercase();
+    let words: Vec<&str> = lowered
+        .split(|c: char| !(c.is_alphanumeric() || c == '+' || c == '#'))
+        .filter(|word| !word.is_empty() && !STOPWORDS.contains(word))
+        .collect();
+
+    for word in &words {
+        add_feature(&mut vector, word.as_bytes(), WORD_WEIGHT);
+        let padded: Vec<char> = format!("<{word}>").chars().collect();
+        for window in padded.windows(3) {
+            let trigram: String = window.iter().collect();
+            add_feature(&mut vector, trigram.as_bytes(), TRIGRAM_WEIGHT);
+        }
+    }
+    for pair in words.windows(2) {
+        let bigram = format!("{} {}", pair[0], pair[1]);
+        add_feature(&mut vector, bigram.as_bytes(), BIGRAM_WEIGHT);
+    }
+
+    // Dampen repeated terms so long listings are not dominated by boilerplate.
+    for value in vector.iter_mut() {
+        *value = value.signum() * value.abs().ln_1p();
+    }
+    let norm = vector.iter().map(|value| value * value).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        for value in vector.iter_mut() {
+            *value /= norm;
+        }
+    }
+    vector
+}
+
+pub fn cosine(a: &[f32], b: &[f32]) -> f64 {
+    if a.len() != b.len() {
+        return 0.0;
+    }
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm_a = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norm_b = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return 0.0;
+    }
+    (dot / (norm_a * norm_b)) as f64
+}
+
+/// Text describing what the user is looking for, embedded as the reference side of comparisons.
+pub fn profile_text(settings: &JobSettings) -> String {
+    let mut parts = Vec::new();
+    parts.extend(settings.preferred_titles.iter().cloned());
+    parts.extend(settings.keywords.iter().cloned());
+    if let Some(resume) = settings.resume.as_deref() {
+        parts.push(resume.to_string());
+    }
+    parts.join("\n")
+}
+
+pub fn content_hash(text: &str) -> String {
+    format!("{:016x}", fnv1a(text.as_bytes()))
+}
+
+pub fn to_bytes(vector: &[f32]) -> Vec<u8> {
+    vector
+        .iter()
+        .flat_map(|value| value.to_le_bytes())
+        .collect()
+}
+
+pub fn from_bytes(bytes: &[u8]) -> Vec<f32> {
+    bytes
+        .chunks_exact(4)
+        .map(|chunk| f32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+        .collect()
+}
+
+fn add_feature(vector: &mut [f32], feature: &[u8], weight: f32) {
+    let hash = fnv1a(feature);
+    let index = (hash % DIMENSIONS as u64) as usize;
+    let sign = if hash >> 63 == 0 { 1.0 } else { -1.0 };
+    vector[index] += sign * weight;
+}
+
+fn fnv1a(bytes: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in bytes {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+    }
+    hash
+}
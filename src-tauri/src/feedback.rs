@@ -0,0 +1,71 @@
+use crate::db::Db;
+use crate::scoring::{default_weights, train_weights, ScoreWeights};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Rating {
+    Good,
+    Bad,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct WeightsReport {
+    pub profile: String,
+    pub defaults: ScoreWeights,
+    pub learned: Option<ScoreWeights>,
+    pub effective: ScoreWeights,
+    /// Learned minus default weight for every factor; empty until ratings exist.
+    pub deltas: ScoreWeights,
+    pub samples: usize,
+    pub updated_at: Option<String>,
+}
+
+/// Records a rating and retrains the profile's weights on every rating collected so far.
+pub fn rate_match(
+    db: &Db,
+    profile: &str,
+    match_id: &str,
+    rating: Rating,
+) -> Result<WeightsReport, String> {
+    db.record_feedback(match_id, profile, rating == Rating::Good)?;
+    let samples = db.feedback_samples(profile)?;
+    let weights = train_weights(&default_weights(), &samples);
+    db.save_learned_weights(profile, &weights, samples.len())?;
+    weights_report(db, profile)
+}
+
+pub fn weights_report(db: &Db, profile: &str) -> Result<WeightsReport, String> {
+    let defaults = default_weights();
+    let learned = db.load_learned_weights(profile)?;
+    let effective = learned
+        .as_ref()
+        .map(|value| value.weights.clone())
+        .unwrap_or_else(|| defaults.clone());
+    let deltas = match &learned {
+        Some(value) => value
+            .weights
+            .iter()
+            .map(|(name, weight)| {
+                let base = defaults.get(name).copied().unwrap_or(0.0);
+                (name.clone(), weight - base)
+            })
+            .collect(),
+        None => ScoreWeights::new(),
+    };
+    Ok(WeightsReport {
+        profile: profile.to_string(),
+        defaults,
+        effective,
+        deltas,
+        samples: learned.as_ref().map(|value| value.samples).unwrap_or(0),
+        updated_at: learned.as_ref().map(|value| value.updated_at.clone()),
+        learned: learned.map(|value| value.weights),
+    })
+}
+
+pub fn reset_weights(db: &Db, profile: &str) -> Result<WeightsReport, String> {
+    db.reset_learned_weights(profile)?;
+    weights_report(db, profile)
+}
@@ -0,0 +1,225 @@
+use crate::analysis_agent;
+use crate::db::{Db, Feed};
+use crate::http::{FetchOrigin, HttpClient};
+use crate::settings::load_feed_settings;
+use chrono::{DateTime, Duration as ChronoDuration, Utc};
+use reqwest::Url;
+use serde::Serialize;
+use serde_json::json;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+
+/// How often the scheduler looks for feeds that are due.
+const POLL_INTERVAL: Duration = Duration::from_secs(60);
+
+static RUNNING: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct PollSummary {
+    pub polled: usize,
+    /// Unseen items found, including those left for the next poll.
+    pub new_items: usize,
+    pub analyzed: usize,
+    pub failed: usize,
+}
+
+/// One posting from a feed.
+struct FeedEntry {
+    guid: String,
+    link: String,
+    title: Option<String>,
+    published: Option<DateTime<Utc>>,
+}
+
+/// Fetches and parses a feed, returning its title and entries oldest first. Entries without
+/// a link are skipped; entries without an id are keyed by their link.
+async fn fetch_entries(
+    http: &HttpClient,
+    url: &str,
+) -> Result<(Option<String>, Vec<FeedEntry>), String> {
+    let page = http
+        .fetch(url, false, FetchOrigin::Automated)
+        .await
+        .map_err(|err| err.message)?;
+    let feed =
+        feed_rs::parser::parse(page.body.as_bytes()).map_err(|err| format!("feed parse: {err}"))?;
+    let mut entries: Vec<FeedEntry> = feed
+        .entries
+        .into_iter()
+        .filter_map(|entry| {
+            let link = entry.links.first()?.href.trim().to_string();
+            if link.is_empty() {
+                return None;
+            }
+            Some(FeedEntry {
+                guid: if entry.id.trim().is_empty() {
+                    link.clone()
+                } else {
+                    entry.id
+                },
+                title: entry.title.map(|title| title.content),
+                published: entry.published.or(entry.updated),
+                link,
+            })
+        })
+        .collect();
+    entries.sort_by_key(|entry| entry.published);
+    Ok((feed.title.map(|title| title.content), entries))
+}
+
+/// Subscribes to a feed after checking that it parses. Items already in the feed are marked
+/// as seen unless `analyze_existing` is set, in which case the next poll analyzes them.
+pub async fn add_feed(
+    db: &Db,
+    http: &HttpClient,
+    url: &str,
+    analyze_existing: bool,
+) -> Result<Feed, String> {
+    let url = Url::parse(url.trim())
+        .map_err(|err| format!("invalid feed url: {err}"))?
+        .to_string();
+    let (title, entries) = fetch_entries(http, &url).await?;
+    let feed = db.insert_feed(&url, title.as_deref())?;
+    if analyze_existing {
+        return Ok(feed);
+    }
+    for entry in &entries {
+        db.insert_feed_item(
+            &feed.id,
+            &entry.guid,
+            &entry.link,
+            entry.title.as_deref(),
+            None,
+        )?;
+    }
+    db.mark_feed_polled(&feed.id, None, None)?;
+    db.list_feeds(None)?
+        .into_iter()
+        .find(|stored| stored.id == feed.id)
+        .ok_or_else(|| "feed disappeared while being added".to_string())
+}
+
+/// Starts the background loop that polls feeds once they are due.
+pub fn start(app: AppHandle, db: Db, http: HttpClient, mcp_port: u16) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(POLL_INTERVAL).await;
+            let settings = match load_feed_settings(&app) {
+                Ok(settings) => settings,
+                Err(err) => {
+                    log::warn!("feed poll: {err}");
+                    continue;
+                }
+            };
+            if !settings.enabled {
+                continue;
+            }
+            let due =
+                Utc::now() - ChronoDuration::minutes(i64::from(settings.poll_interval_minutes));
+            let result = poll(&app, &db, &http, mcp_port, None, Some(&due.to_rfc3339())).await;
+            if let Err(err) = result {
+                log::warn!("feed poll: {err}");
+            }
+        }
+    });
+}
+
+/// Polls one feed (by id or URL), or every feed last polled before `polled_before`, and runs
+/// the analysis agent on new items. Ends with a `feeds:polled` event.
+pub async fn poll(
+    app: &AppHandle,
+    db: &Db,
+    http: &HttpClient,
+    mcp_port: u16,
+    feed: Option<&str>,
+    polled_before: Option<&str>,
+) -> Result<PollSummary, String> {
+    if RUNNING.swap(true, Ordering::SeqCst) {
+        return Err("a feed poll is already in progress".to_string());
+    }
+    let result = poll_feeds(app, db, http, mcp_port, feed, polled_before).await;
+    RUNNING.store(false, Ordering::SeqCst);
+    let summary = result?;
+    let _ = app.emit("feeds:polled", json!(summary));
+    Ok(summary)
+}
+
+async fn poll_feeds(
+    app: &AppHandle,
+    db: &Db,
+    http: &HttpClient,
+    mcp_port: u16,
+    feed: Option<&str>,
+    polled_before: Option<&str>,
+) -> Result<PollSummary, String> {
+    let max_items = load_feed_settings(app)?.max_items_per_poll;
+    let mut feeds = db.list_feeds(polled_before)?;
+    if let Some(feed) = feed {
+        feeds.retain(|stored| stored.id == feed || stored.url == feed);
+        if feeds.is_empty() {
+            return Err(format!("feed not found: {feed}"));
+        }
+    }
+    let mut summary = PollSummary::default();
+    for feed in &feeds {
+        summary.polled += 1;
+        match poll_one(app, db, http, mcp_port, feed, max_items).await {
+            Ok((new_items, analyzed)) => {
+                summary.new_items += new_items;
+                summary.analyzed += analyzed;
+            }
+            Err(err) => {
+                summary.failed += 1;
+                log::warn!("feed {}: {err}", feed.url);
+                db.mark_feed_polled(&feed.id, None, Some(&err))?;
+            }
+        }
+    }
+    Ok(summary)
+}
+
+/// Analyzes up to `max_items` unseen entries, one agent at a time, and returns how many
+/// entries were new and how many were analyzed. Entries whose link is already a saved match
+/// are marked as seen without a new analysis.
+async fn poll_one(
+    app: &AppHandle,
+    db: &Db,
+    http: &HttpClient,
+    mcp_port: u16,
+    feed: &Feed,
+    max_items: usize,
+) -> Result<(usize, usize), String> {
+    let (title, entries) = fetch_entries(http, &feed.url).await?;
+    let mut new_items = 0;
+    let mut analyzed = 0;
+    for entry in &entries {
+        if db.feed_item_seen(&feed.id, &entry.guid)? {
+            continue;
+        }
+        new_items += 1;
+        // Left unseen so the next poll picks it up.
+        if analyzed >= max_items {
+            continue;
+        }
+        let title = entry.title.as_deref();
+        if db.has_match_for_url(&entry.link)? {
+            db.insert_feed_item(&feed.id, &entry.guid, &entry.link, title, None)?;
+            continue;
+        }
+        let (analysis_id, mut child) = analysis_agent::launch(app, mcp_port, &entry.link, true)?;
+        db.insert_feed_item(
+            &feed.id,
+            &entry.guid,
+            &entry.link,
+            title,
+            Some(&analysis_id),
+        )?;
+        analyzed += 1;
+        // Waiting keeps a large feed from starting dozens of agents at once.
+        let _ = tauri::async_runtime::spawn_blocking(move || child.wait()).await;
+    }
+    db.mark_feed_polled(&feed.id, title.as_deref(), None)?;
+    Ok((new_items, analyzed))
+}
@@ -0,0 +1,153 @@
+use std::sync::OnceLock;
+
+/// Offline gazetteer bundled into the binary: `name,country,latitude,longitude,population`.
+const CITIES_CSV: &str = include_str!("../data/cities.csv");
+const EARTH_RADIUS_KM: f64 = 6371.0;
+
+/// Country names and US state abbreviations that disambiguate cities sharing a name.
+const COUNTRY_HINTS: &[(&str, &[&str])] = &[
+    (
+        "US",
+        &[
+            "united states",
+            "usa",
+            "us",
+            "ca",
+            "ny",
+            "ma",
+            "wa",
+            "tx",
+            "il",
+            "ga",
+            "nc",
+            "va",
+            "dc",
+            "fl",
+            "pa",
+            "oh",
+            "mi",
+            "mn",
+        ],
+    ),
+    (
+        "GB",
+        &["united kingdom", "uk", "england", "scotland", "wales"],
+    ),
+    ("CA", &["canada", "ontario", "bc", "quebec", "alberta"]),
+    ("DE", &["germany", "deutschland"]),
+    ("IE", &["ireland"]),
+    ("AU", &["australia", "nsw", "vic"]),
+    ("IN", &["india"]),
+];
+
+#[derive(Debug, Clone)]
+pub struct Place {
+    pub name: String,
+    pub country: String,
+    pub latitude: f64,
+    pub longitude: f64,
+    population: u64,
+}
+
+impl Place {
+    pub fn label(&self) -> String {
+        if self.country.is_empty() {
+            self.name.clone()
+        } else {
+            format!("{}, {}", self.name, self.country)
+        }
+    }
+}
+
+fn places() -> &'static [Place] {
+    static PLACES: OnceLock<Vec<Place>> = OnceLock::new();
+    PLACES.get_or_init(|| {
+        CITIES_CSV
+            .lines()
+            .filter(|line| !line.trim().is_empty() && !line.starts_with('#'))
+            .filter_map(|line| {
+                let mut fields = line.split(',').map(str::trim);
+                Some(Place {
+                    name: fields.next()?.to_string(),
+                    country: fields.next()?.to_string(),
+                    latitude: fields.next()?.parse().ok()?,
+                    longitude: fields.next()?.parse().ok()?,
+                    population: fields
+                        .next()
+                        .and_then(|value| value.parse().ok())
+                        .unwrap_or(0),
+                })
+            })
+            .collect()
+    })
+}
+
+/// Resolves free-form location text such as `Berlin, Germany`, `Hybrid - London` or
+/// `52.52, 13.40` to coordinates. Prefers the longest city name found in the text, then a
+/// city in a country the text mentions, then the larger city.
+pub fn resolve(location: &str) -> Option<Place> {
+    if let Some(place) = parse_coordinates(location) {
+        return Some(place);
+    }
+    let words = normalize(location);
+    let padded = format!(" {} ", words.join(" "));
+    let hinted: Vec<&str> = COUNTRY_HINTS
+        .iter()
+        .filter(|(_, hints)| {
+            hints
+                .iter()
+                .any(|hint| padded.contains(&format!(" {hint} ")))
+        })
+        .map(|(country, _)| *country)
+        .collect();
+
+    places()
+        .iter()
+        .filter(|place| {
+            let name = normalize(&place.name).join(" ");
+            !name.is_empty() && padded.contains(&format!(" {name} "))
+        })
+        .max_by_key(|place| {
+            (
+                place.name.len(),
+                hinted.contains(&place.country.as_str()),
+                place.population,
+            )
+        })
+        .cloned()
+}
+
+/// Great-circle distance in kilometres.
+pub fn distance_km(from: &Place, to: &Place) -> f64 {
+    let (lat1, lat2) = (from.latitude.to_radians(), to.latitude.to_radians());
+    let delta_lat = lat2 - lat1;
+    let delta_lon = (to.longitude - from.longitude).to_radians();
+    let a =
+        (delta_lat / 2.0).sin().powi(2) + lat1.cos() * lat2.cos() * (delta_lon / 2.0).sin().powi(2);
+    2.0 * EARTH_RADIUS_KM * a.sqrt().asin()
+}
+
+fn parse_coordinates(text: &str) -> Option<Place> {
+    let (latitude, longitude) = text.split_once(',')?;
+    let latitude: f64 = latitude.trim().parse().ok()?;
+    let longitude: f64 = longitude.trim().parse().ok()?;
+    if !(-90.0..=90.0).contains(&latitude) || !(-180.0..=180.0).contains(&longitude) {
+        return None;
+    }
+    Some(Place {
+        name: format!("{latitude:.4}, {longitude:.4}"),
+        country: String::new(),
+        latitude,
+        longitude,
+        population: 0,
+    })
+}
+
+fn normalize(text: &str) -> Vec<String> {
+    text.to_lowercase()
+        .split(|c: char| !(c.is_alphanumeric() || c == '.'))
+        .map(|word| word.trim_matches('.'))
+        .filter(|word| !word.is_empty())
+        .map(str::to_string)
+        .collect()
+}
@@ -0,0 +1,870 @@
+use crate::charset;
+use crate::credentials::{CredentialStore, RequestCredentials};
+use crate::http_cache::{CacheEntry, CacheStatus, HttpCache};
+use crate::robots::Robots;
+use crate::settings::{DomainOverride, HostLimit, NetworkSettings, RetrySettings, RobotsMode};
+use chrono::{DateTime, Utc};
+use rand::Rng;
+use reqwest::header::{
+    HeaderMap, CACHE_CONTROL, CONTENT_TYPE, COOKIE, IF_MODIFIED_SINCE, IF_NONE_MATCH, RETRY_AFTER,
+};
+use reqwest::{StatusCode, Url};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::error::Error as _;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+/// How long a fetched robots.txt is trusted.
+const ROBOTS_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+/// How long a robots.txt that could not be fetched disallows its site before another try.
+const ROBOTS_RETRY: Duration = Duration::from_secs(10 * 60);
+/// Upper bound on a robots.txt `Crawl-delay`, so a hostile value cannot stall fetches.
+const MAX_CRAWL_DELAY: Duration = Duration::from_secs(60);
+
+/// A fetched page with its body read in full.
+#[derive(Debug, Clone)]
+pub struct FetchedPage {
+    pub status: u16,
+    /// URL after redirects.
+    pub final_url: String,
+    /// Response headers without `Set-Cookie`; for cached pages, those of the stored response.
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+    pub cache: CacheStatus,
+    /// Set when the body hit the download cap and was cut short.
+    pub truncated: bool,
+    /// Encoding the body was decoded from.
+    pub encoding: String,
+    /// Set when robots.txt disallows the page but the `warn` mode fetched it anyway.
+    pub robots_warning: Option<String>,
+}
+
+/// Who asked for a page, which picks the robots.txt mode that applies.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FetchOrigin {
+    /// A URL the user pasted.
+    User,
+    /// Crawls, feed polls, listing watch and the analyses they start.
+    Automated,
+}
+
+/// Why a fetch failed, for the agent and UI to react to.
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCategory {
+    Dns,
+    Tls,
+    Timeout,
+    /// The connection was refused, reset or dropped mid-response.
+    Connection,
+    /// `429 Too Many Requests`.
+    RateLimited,
+    /// `401`, `403`, or LinkedIn's `999`.
+    Blocked,
+    /// `404` or `410`.
+    NotFound,
+    /// Any `5xx`.
+    ServerError,
+    /// robots.txt disallows the URL, or could not be read, under the `respect` mode.
+    DisallowedByRobots,
+}
+
+impl ErrorCategory {
+    fn is_transient(self) -> bool {
+        matches!(
+            self,
+            ErrorCategory::Timeout
+                | ErrorCategory::Connection
+                | ErrorCategory::RateLimited
+                | ErrorCategory::ServerError
+        )
+    }
+
+    fn from_status(status: StatusCode) -> Option<Self> {
+        match status.as_u16() {
+            429 => Some(ErrorCategory::RateLimited),
+            401 | 403 | 999 => Some(ErrorCategory::Blocked),
+            404 | 410 => Some(ErrorCategory::NotFound),
+            500..=599 => Some(ErrorCategory::ServerError),
+            _ => None,
+        }
+    }
+
+    /// Classifies from reqwest's own flags and the typed I/O errors in the source chain first;
+    /// DNS and TLS failures only surface as messages, so those fall back to matching text.
+    fn from_error(err: &reqwest::Error) -> Self {
+        if err.is_timeout() {
+            return ErrorCategory::Timeout;
+        }
+        let mut source = err.source();
+        while let Some(inner) = source {
+            if let Some(io) = inner.downcast_ref::<std::io::Error>() {
+                match io.kind() {
+                    std::io::ErrorKind::TimedOut => return ErrorCategory::Timeout,
+                    std::io::ErrorKind::ConnectionRefused
+                    | std::io::ErrorKind::ConnectionReset
+                    | std::io::ErrorKind::ConnectionAborted
+                    | std::io::ErrorKind::NotConnected
+                    | std::io::ErrorKind::AddrNotAvailable => return ErrorCategory::Connection,
+                    // rustls reports handshake failures as invalid data on the stream.
+                    std::io::ErrorKind::InvalidData if err.is_connect() => {
+                        return ErrorCategory::Tls
+                    }
+                    _ => {}
+                }
+            }
+            source = inner.source();
+        }
+        let mut chain = err.to_string();
+        let mut source = err.source();
+        while let Some(inner) = source {
+            chain.push_str(&format!(": {inner}"));
+            source = inner.source();
+        }
+        let chain = chain.to_lowercase();
+        if [
+            "dns",
+            "failed to lookup",
+            "name or service not known",
+            "no such host",
+        ]
+        .iter()
+        .any(|marker| chain.contains(marker))
+        {
+            ErrorCategory::Dns
+        } else if ["certificate", "tls", "ssl", "handshake"]
+            .iter()
+            .any(|marker| chain.contains(marker))
+        {
+            ErrorCategory::Tls
+        } else if chain.contains("timed out") {
+            ErrorCategory::Timeout
+        } else {
+            ErrorCategory::Connection
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct FetchError {
+    pub message: String,
+    /// `None` for failures that are not about the network, such as an invalid URL.
+    pub category: Option<ErrorCategory>,
+    pub status: Option<u16>,
+    /// Seconds the server asked us to wait, from `Retry-After`.
+    pub retry_after: Option<u64>,
+}
+
+impl FetchError {
+    fn new(category: ErrorCategory, message: String) -> Self {
+        Self {
+            message,
+            category: Some(category),
+            status: None,
+            retry_after: None,
+        }
+    }
+
+    fn is_transient(&self) -> bool {
+        self.category.is_some_and(ErrorCategory::is_transient)
+    }
+}
+
+impl From<String> for FetchError {
+    fn from(message: String) -> Self {
+        Self {
+            message,
+            category: None,
+            status: None,
+            retry_after: None,
+        }
+    }
+}
+
+/// A response read in full while the host's limits were held.
+struct RawResponse {
+    status: StatusCode,
+    final_url: Url,
+    headers: HeaderMap,
+    body: String,
+    truncated: bool,
+    encoding: &'static str,
+}
+
+/// Token bucket refilled at `rate` tokens per second up to `capacity`.
+struct Bucket {
+    tokens: f64,
+    capacity: f64,
+    rate: f64,
+    updated: Instant,
+}
+
+impl Bucket {
+    fn new(limit: &HostLimit) -> Self {
+        let capacity = f64::from(limit.burst.max(1));
+        Self {
+            tokens: capacity,
+            capacity,
+            rate: limit.requests_per_second,
+            updated: Instant::now(),
+        }
+    }
+
+    /// Takes a token, or returns how long to wait before one is available.
+    fn take(&mut self) -> Option<Duration> {
+        if self.rate <= 0.0 {
+            return None;
+        }
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.updated).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.rate).min(self.capacity);
+        self.updated = now;
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            None
+        } else {
+            Some(Duration::from_secs_f64((1.0 - self.tokens) / self.rate))
+        }
+    }
+
+    /// Slows the bucket to one request per `delay` without bursts, for a robots.txt
+    /// `Crawl-delay`. A configured limit that is already slower is kept.
+    fn apply_crawl_delay(&mut self, delay: Duration) {
+        if delay.is_zero() {
+            return;
+        }
+        let rate = 1.0 / delay.as_secs_f64();
+        if self.rate <= 0.0 || rate < self.rate {
+            self.rate = rate;
+        }
+        self.capacity = 1.0;
+        self.tokens = self.tokens.min(1.0);
+    }
+}
+
+struct HostState {
+    bucket: Mutex<Bucket>,
+    permits: Arc<Semaphore>,
+}
+
+impl HostState {
+    fn new(limit: &HostLimit) -> Self {
+        Self {
+            bucket: Mutex::new(Bucket::new(limit)),
+            permits: Arc::new(Semaphore::new(limit.max_concurrent.max(1))),
+        }
+    }
+
+    /// Waits for a concurrency slot, then for a rate-limit token.
+    async fn acquire(&self) -> Result<OwnedSemaphorePermit, String> {
+        let permit = self
+            .permits
+            .clone()
+            .acquire_owned()
+            .await
+            .map_err(|err| format!("host limiter: {err}"))?;
+        loop {
+            let wait = self
+                .bucket
+                .lock()
+                .map_err(|_| "host limiter poisoned".to_string())?
+                .take();
+            match wait {
+                Some(wait) => tokio::time::sleep(wait).await,
+                None => return Ok(permit),
+            }
+        }
+    }
+}
+
+/// reqwest clients for the current settings: one for most hosts and one per domain override.
+struct Clients {
+    default: reqwest::Client,
+    /// Parallel to `NetworkSettings::domain_overrides`.
+    overrides: Vec<reqwest::Client>,
+}
+
+impl Clients {
+    fn build(settings: &NetworkSettings) -> Result<Self, String> {
+        // Read once so every client trusts the same extra roots.
+        let certificates = match &settings.ca_bundle {
+            Some(path) => {
+                let pem =
+                    std::fs::read(path).map_err(|err| format!("read ca bundle {path}: {err}"))?;
+                reqwest::Certificate::from_pem_bundle(&pem)
+                    .map_err(|err| format!("parse ca bundle {path}: {err}"))?
+            }
+            None => Vec::new(),
+        };
+        Ok(Self {
+            default: build_client(settings, None, &certificates)?,
+            overrides: settings
+                .domain_overrides
+                .iter()
+                .map(|entry| build_client(settings, Some(entry), &certificates))
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+fn build_client(
+    settings: &NetworkSettings,
+    entry: Option<&DomainOverride>,
+    certificates: &[reqwest::Certificate],
+) -> Result<reqwest::Client, String> {
+    let name = entry.map_or("default", |entry| entry.domain.as_str());
+    let user_agent = entry
+        .and_then(|entry| entry.user_agent.as_deref())
+        .unwrap_or(&settings.user_agent);
+    let connect_timeout = entry
+        .and_then(|entry| entry.connect_timeout_secs)
+        .unwrap_or(settings.connect_timeout_secs);
+    let read_timeout = entry
+        .and_then(|entry| entry.read_timeout_secs)
+        .unwrap_or(settings.read_timeout_secs);
+    let mut builder = reqwest::Client::builder()
+        .user_agent(user_agent)
+        .connect_timeout(Duration::from_secs(connect_timeout))
+        .read_timeout(Duration::from_secs(read_timeout));
+    for certificate in certificates {
+        builder = builder.add_root_certificate(certificate.clone());
+    }
+    let proxy = entry
+        .and_then(|entry| entry.proxy.as_ref())
+        .or(settings.proxy.as_ref());
+    if let Some(proxy) = proxy {
+        let proxy = reqwest::Proxy::all(proxy)
+            .map_err(|err| format!("proxy for {name}: {err}"))?
+            .no_proxy(reqwest::NoProxy::from_string(&settings.no_proxy.join(",")));
+        builder = builder.proxy(proxy);
+    }
+    builder
+        .build()
+        .map_err(|err| format!("http client for {name}: {err}"))
+}
+
+/// A site's robots.txt as last fetched.
+struct RobotsEntry {
+    robots: Arc<Robots>,
+    /// Why robots.txt could not be read; the site counts as disallowed until `expires`.
+    unavailable: Option<String>,
+    expires: Instant,
+}
+
+struct Limiter {
+    settings: NetworkSettings,
+    /// Keyed by the matching limit's host, or the request host under the default limit.
+    hosts: HashMap<String, Arc<HostState>>,
+    clients: Clients,
+    /// Keyed by origin (`https://host:port`).
+    robots: HashMap<String, RobotsEntry>,
+}
+
+/// One HTTP client shared by every fetch, so connections are reused and each host is throttled
+/// across concurrent analyses.
+#[derive(Clone)]
+pub struct HttpClient {
+    limiter: Arc<Mutex<Limiter>>,
+    cache: Option<HttpCache>,
+    credentials: CredentialStore,
+}
+
+impl HttpClient {
+    /// Responses are cached under `cache_dir` when it is given. If the saved proxy or CA bundle
+    /// no longer works, the default client options are used so the app still starts.
+    pub fn new(
+        settings: &NetworkSettings,
+        cache_dir: Option<PathBuf>,
+        credentials: CredentialStore,
+    ) -> Result<Self, String> {
+        let clients = Clients::build(settings).or_else(|err| {
+            log::error!("network settings: {err}; using default client options");
+            Clients::build(&NetworkSettings::default())
+        })?;
+        Ok(Self {
+            limiter: Arc::new(Mutex::new(Limiter {
+                settings: settings.clone(),
+                hosts: HashMap::new(),
+                clients,
+                robots: HashMap::new(),
+            })),
+            cache: cache_dir.map(HttpCache::new),
+            credentials,
+        })
+    }
+
+    pub fn credentials(&self) -> &CredentialStore {
+        &self.credentials
+    }
+
+    /// Applies new limits and client options, failing without changes if a proxy or the CA
+    /// bundle is unusable. Requests already waiting finish under the old settings.
+    pub fn configure(&self, settings: &NetworkSettings) -> Result<(), String> {
+        settings.validate()?;
+        let clients = Clients::build(settings)?;
+        let mut limiter = self
+            .limiter
+            .lock()
+            .map_err(|_| "http limiter poisoned".to_string())?;
+        limiter.settings = settings.clone();
+        limiter.hosts.clear();
+        limiter.clients = clients;
+        // Crawl delays lived in the cleared host buckets, and the user agent may have changed.
+        limiter.robots.clear();
+        Ok(())
+    }
+
+    fn client_for(&self, host: &str) -> Result<reqwest::Client, String> {
+        let limiter = self
+            .limiter
+            .lock()
+            .map_err(|_| "http limiter poisoned".to_string())?;
+        Ok(match limiter.settings.override_for(host) {
+            Some(index) => limiter.clients.overrides[index].clone(),
+            None => limiter.clients.default.clone(),
+        })
+    }
+
+    fn settings(&self) -> Result<NetworkSettings, String> {
+        self.limiter
+            .lock()
+            .map(|limiter| limiter.settings.clone())
+            .map_err(|_| "http limiter poisoned".to_string())
+    }
+
+    fn host_state(&self, host: &str) -> Result<Arc<HostState>, String> {
+        let mut limiter = self
+            .limiter
+            .lock()
+            .map_err(|_| "http limiter poisoned".to_string())?;
+        let (key, limit) = match limiter.settings.limit_for(host) {
+            Some(limit) => (limit.host.to_lowercase(), limit.clone()),
+            None => (host.to_string(), limiter.settings.default_limit.clone()),
+        };
+        Ok(limiter
+            .hosts
+            .entry(key)
+            .or_insert_with(|| Arc::new(HostState::new(&limit)))
+            .clone())
+    }
+
+    /// Fetches `url` after checking the site's robots.txt under the mode for `origin`, serving
+    /// fresh cache entries directly and revalidating stale ones with `If-None-Match` /
+    /// `If-Modified-Since`. Requests wait for the host's concurrency and rate limits, and
+    /// transient failures are retried with jittered exponential backoff that honors
+    /// `Retry-After`. `bypass_cache` always fetches in full but still stores the response.
+    /// Requests that carry configured cookies or headers skip the cache entirely, so logged-in
+    /// and anonymous versions of a page never mix.
+    pub async fn fetch(
+        &self,
+        url: &str,
+        bypass_cache: bool,
+        origin: FetchOrigin,
+    ) -> Result<FetchedPage, FetchError> {
+        let parsed = Url::parse(url).map_err(|err| format!("invalid url: {err}"))?;
+        let robots_warning = self.check_robots(&parsed, origin).await?;
+        let mut page = self.fetch_parsed(url, &parsed, bypass_cache).await?;
+        page.robots_warning = robots_warning;
+        Ok(page)
+    }
+
+    /// Applies robots.txt under the mode for `origin`: `Ok(None)` to go ahead, a warning to go
+    /// ahead under `warn`, or a `disallowed_by_robots` error under `respect`.
+    async fn check_robots(
+        &self,
+        url: &Url,
+        origin: FetchOrigin,
+    ) -> Result<Option<String>, FetchError> {
+        let settings = self.settings()?;
+        let mode = match origin {
+            FetchOrigin::User => settings.robots.user,
+            FetchOrigin::Automated => settings.robots.automated,
+        };
+        if mode == RobotsMode::Ignore || url.path() == "/robots.txt" {
+            return Ok(None);
+        }
+        let host = url.host_str().unwrap_or_default().to_lowercase();
+        let (robots, unavailable) = self.robots_entry(url).await?;
+        let path = match url.query() {
+            Some(query) => format!("{}?{query}", url.path()),
+            None => url.path().to_string(),
+        };
+        if robots.is_allowed(settings.user_agent_for(&host), &path) {
+            return Ok(None);
+        }
+        let message = match unavailable {
+            Some(reason) => {
+                format!("disallowed by robots: robots.txt for {host} is unavailable ({reason})")
+            }
+            None => format!("disallowed by robots: robots.txt for {host} disallows {path}"),
+        };
+        if mode == RobotsMode::Respect {
+            return Err(FetchError::new(ErrorCategory::DisallowedByRobots, message));
+        }
+        log::warn!("{message}; fetching {url} anyway");
+        Ok(Some(message))
+    }
+
+    /// The robots.txt for `url`'s site, for its `Sitemap` lines. Fetched like any check would.
+    pub async fn robots(&self, url: &Url) -> Result<Arc<Robots>, FetchError> {
+        Ok(self.robots_entry(url).await?.0)
+    }
+
+    /// The cached robots.txt for `url`'s origin, fetched when missing or expired. A missing or
+    /// forbidden file allows everything; one that cannot be fetched disallows everything for
+    /// `ROBOTS_RETRY`. A `Crawl-delay` slows the host's rate limit.
+    async fn robots_entry(&self, url: &Url) -> Result<(Arc<Robots>, Option<String>), FetchError> {
+        let origin = url.origin().ascii_serialization();
+        {
+            let limiter = self
+                .limiter
+                .lock()
+                .map_err(|_| "http limiter poisoned".to_string())?;
+            if let Some(entry) = limiter
+                .robots
+                .get(&origin)
+                .filter(|entry| entry.expires > Instant::now())
+            {
+                return Ok((entry.robots.clone(), entry.unavailable.clone()));
+            }
+        }
+        let robots_url = format!("{origin}/robots.txt");
+        let parsed = Url::parse(&robots_url).map_err(|err| format!("invalid url: {err}"))?;
+        let (robots, unavailable) = match self.fetch_parsed(&robots_url, &parsed, false).await {
+            Ok(page) if page.status < 400 => (Robots::parse(&page.body), None),
+            Ok(_) => (Robots::default(), None),
+            Err(err)
+                if matches!(
+                    err.category,
+                    Some(ErrorCategory::NotFound | ErrorCategory::Blocked)
+                ) =>
+            {
+                (Robots::default(), None)
+            }
+            Err(err) => (Robots::disallow_all(), Some(err.message)),
+        };
+        let host = url.host_str().unwrap_or_default().to_lowercase();
+        let settings = self.settings()?;
+        if let Some(delay) = robots.crawl_delay(settings.user_agent_for(&host)) {
+            let delay = Duration::from_secs_f64(delay).min(MAX_CRAWL_DELAY);
+            self.host_state(&host)?
+                .bucket
+                .lock()
+                .map_err(|_| "host limiter poisoned".to_string())?
+                .apply_crawl_delay(delay);
+        }
+        let robots = Arc::new(robots);
+        let ttl = if unavailable.is_some() {
+            ROBOTS_RETRY
+        } else {
+            ROBOTS_TTL
+        };
+        self.limiter
+            .lock()
+            .map_err(|_| "http limiter poisoned".to_string())?
+            .robots
+            .insert(
+                origin,
+                RobotsEntry {
+                    robots: robots.clone(),
+                    unavailable: unavailable.clone(),
+                    expires: Instant::now() + ttl,
+                },
+            );
+        Ok((robots, unavailable))
+    }
+
+    /// `fetch` without the robots.txt check.
+    async fn fetch_parsed(
+        &self,
+        url: &str,
+        parsed: &Url,
+        bypass_cache: bool,
+    ) -> Result<FetchedPage, FetchError> {
+        let host = parsed
+            .host_str()
+            .ok_or_else(|| format!("url has no host: {url}"))?
+            .to_lowercase();
+        let credentials = self.credentials.for_url(parsed)?;
+        let cache = self.cache.as_ref().filter(|_| credentials.is_none());
+        let cached = match (cache, bypass_cache) {
+            (Some(cache), false) => cache.load(url).await,
+            _ => None,
+        };
+        if let Some(entry) = cached.as_ref().filter(|entry| entry.is_fresh(Utc::now())) {
+            return Ok(FetchedPage {
+                status: entry.status,
+                final_url: entry.final_url.clone().unwrap_or_else(|| url.to_string()),
+                headers: entry.headers.clone(),
+                body: entry.body.clone(),
+                cache: CacheStatus::Hit,
+                truncated: false,
+                encoding: entry.encoding.clone(),
+                robots_warning: None,
+            });
+        }
+
+        let settings = self.settings()?;
+        let retry = &settings.retry;
+        let mut attempt = 0;
+        let response = loop {
+            let err = match self
+                .send_once(
+                    parsed,
+                    &host,
+                    cached.as_ref(),
+                    credentials.as_ref(),
+                    bypass_cache,
+                    settings.max_body_bytes,
+                )
+                .await
+            {
+                Ok(response) => break response,
+                Err(err) => err,
+            };
+            if !err.is_transient() || attempt >= retry.max_retries {
+                return Err(err);
+            }
+            let Some(delay) = backoff(retry, attempt, err.retry_after) else {
+                return Err(err);
+            };
+            log::info!(
+                "retrying {url} in {}ms after: {}",
+                delay.as_millis(),
+                err.message
+            );
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        };
+
+        if response.status == StatusCode::NOT_MODIFIED {
+            if let Some(mut entry) = cached {
+                entry.refresh(&response.headers);
+                if let Some(cache) = cache {
+                    if let Err(err) = cache.store(&entry).await {
+                        log::warn!("http cache: {err}");
+                    }
+                }
+                return Ok(FetchedPage {
+                    status: entry.status,
+                    final_url: entry.final_url.unwrap_or_else(|| url.to_string()),
+                    headers: entry.headers,
+                    body: entry.body,
+                    cache: CacheStatus::Revalidated,
+                    truncated: false,
+                    encoding: entry.encoding,
+                    robots_warning: None,
+                });
+            }
+        }
+
+        let entry = CacheEntry::new(
+            url,
+            response.final_url.as_str(),
+            response.status.as_u16(),
+            &response.headers,
+            response.body,
+            response.encoding,
+        );
+        if let Some(cache) = cache {
+            let storable = entry.is_storable() && !response.truncated;
+            if storable && (entry.has_validators() || entry.is_fresh(Utc::now())) {
+                if let Err(err) = cache.store(&entry).await {
+                    log::warn!("http cache: {err}");
+                }
+            } else if response.status.is_success() {
+                cache.remove(url).await;
+            }
+        }
+        Ok(FetchedPage {
+            status: entry.status,
+            final_url: response.final_url.to_string(),
+            headers: entry.headers,
+            body: entry.body,
+            cache: if bypass_cache || cache.is_none() {
+                CacheStatus::Bypass
+            } else {
+                CacheStatus::Miss
+            },
+            truncated: response.truncated,
+            encoding: entry.encoding,
+            robots_warning: None,
+        })
+    }
+
+    /// Makes one request once the host's limits allow it, turning failure statuses into errors.
+    /// The body is streamed and cut off after `max_bytes`.
+    async fn send_once(
+        &self,
+        url: &Url,
+        host: &str,
+        cached: Option<&CacheEntry>,
+        credentials: Option<&RequestCredentials>,
+        bypass_cache: bool,
+        max_bytes: usize,
+    ) -> Result<RawResponse, FetchError> {
+        let state = self.host_state(host)?;
+        let _permit = state.acquire().await?;
+        let mut request = self.client_for(host)?.get(url.clone());
+        if let Some(entry) = cached {
+            if let Some(etag) = &entry.etag {
+                request = request.header(IF_NONE_MATCH, etag);
+            }
+            if let Some(last_modified) = &entry.last_modified {
+                request = request.header(IF_MODIFIED_SINCE, last_modified);
+            }
+        }
+        if bypass_cache {
+            request = request.header(CACHE_CONTROL, "no-cache");
+        }
+        if let Some(credentials) = credentials {
+            for (name, value) in &credentials.headers {
+                request = request.header(name.clone(), value.clone());
+            }
+            if let Some(cookie) = &credentials.cookie {
+                request = request.header(COOKIE, cookie);
+            }
+        }
+        let mut response = request.send().await.map_err(|err| {
+            FetchError::new(
+                ErrorCategory::from_error(&err),
+                format!("http fetch: {err}"),
+            )
+        })?;
+        let status = response.status();
+        let final_url = response.url().clone();
+        let headers = response.headers().clone();
+        if let Err(err) = self.credentials.store_response_cookies(url, &headers) {
+            log::warn!("cookie jar: {err}");
+        }
+        if let Some(category) = ErrorCategory::from_status(status) {
+            return Err(FetchError {
+                message: format!("http status {status}"),
+                category: Some(category),
+                status: Some(status.as_u16()),
+                retry_after: retry_after(&headers),
+            });
+        }
+        let mut body = Vec::new();
+        let mut truncated = false;
+        while let Some(chunk) = response.chunk().await.map_err(|err| {
+            FetchError::new(ErrorCategory::from_error(&err), format!("http body: {err}"))
+        })? {
+            let room = max_bytes.saturating_sub(body.len());
+            if chunk.len() > room {
+                body.extend_from_slice(&chunk[..room]);
+                truncated = true;
+                break;
+            }
+            body.extend_from_slice(&chunk);
+        }
+        let content_type = headers
+            .get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok());
+        let decoded = charset::decode(&body, content_type, Some(host), truncated);
+        Ok(RawResponse {
+            status,
+            final_url,
+            headers,
+            body: decoded.text,
+            truncated,
+            encoding: decoded.encoding,
+        })
+    }
+}
+
+/// Parses `Retry-After` as seconds or an HTTP date.
+fn retry_after(headers: &HeaderMap) -> Option<u64> {
+    let value = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(seconds);
+    }
+    let date = DateTime::parse_from_rfc2822(value).ok()?;
+    Some((date.with_timezone(&Utc) - Utc::now()).num_seconds().max(0) as u64)
+}
+
+/// Delay before retry `attempt` (from 0): exponential with jitter in its upper half, raised to
+/// `Retry-After` when the server asked for longer. `None` when the server asks for more than
+/// the configured maximum, so the caller gives up instead of stalling.
+fn backoff(retry: &RetrySettings, attempt: u32, retry_after: Option<u64>) -> Option<Duration> {
+    let max = Duration::from_millis(retry.max_delay_ms);
+    let exponential = Duration::from_millis(retry.base_delay_ms)
+        .saturating_mul(2u32.saturating_pow(attempt))
+        .min(max);
+    let jittered = exponential.mul_f64(rand::thread_rng().gen_range(0.5..=1.0));
+    match retry_after.map(Duration::from_secs) {
+        Some(wait) if wait > max => None,
+        Some(wait) => Some(wait.max(jittered)),
+        None => Some(jittered),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::settings::{RobotsMode, RobotsSettings};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    /// Serves one `200` response with `body` as UTF-8 HTML, returning its URL.
+    async fn mock_page(body: &'static str) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = Vec::new();
+            let mut buf = [0u8; 4096];
+            while !request.windows(4).any(|window| window == b"\r\n\r\n") {
+                let read = socket.read(&mut buf).await.unwrap();
+                if read == 0 {
+                    break;
+                }
+                request.extend_from_slice(&buf[..read]);
+            }
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            );
+            // The client stops reading at the byte cap, so the write may fail.
+            let _ = socket.write_all(response.as_bytes()).await;
+            let _ = socket.shutdown().await;
+        });
+        format!("http://{addr}/posting")
+    }
+
+    #[tokio::test]
+    async fn body_cut_mid_character_decodes_without_replacement() {
+        let url = mock_page("求人情報：シニアエンジニア").await;
+        let settings = NetworkSettings {
+            // Each character is three bytes, so the cap lands inside the third one.
+            max_body_bytes: 7,
+            robots: RobotsSettings {
+                automated: RobotsMode::Ignore,
+                user: RobotsMode::Ignore,
+            },
+            ..NetworkSettings::default()
+        };
+        let client = HttpClient::new(&settings, None, CredentialStore::load(None)).unwrap();
+        let page = client.fetch(&url, false, FetchOrigin::User).await.unwrap();
+        assert!(page.truncated);
+        assert_eq!(page.encoding, "UTF-8");
+        assert_eq!(page.body, "求人");
+    }
+
+    #[tokio::test]
+    async fn refused_connection_is_classified_from_the_io_error() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        drop(listener);
+        let err = reqwest::Client::new()
+            .get(format!("http://{addr}/"))
+            .send()
+            .await
+            .unwrap_err();
+        assert!(err.is_connect());
+        assert_eq!(ErrorCategory::from_error(&err), ErrorCategory::Connection);
+    }
+}
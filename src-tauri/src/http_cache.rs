@@ -0,0 +1,187 @@
+use crate::embedding;
+use chrono::{DateTime, Duration, Utc};
+use reqwest::header::{HeaderMap, CACHE_CONTROL, ETAG, EXPIRES, LAST_MODIFIED, SET_COOKIE};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// How a fetch was served, reported in the `fetch_content` result.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum CacheStatus {
+    /// Served from a fresh cache entry without a request.
+    Hit,
+    /// The server confirmed the cached body with `304 Not Modified`.
+    Revalidated,
+    /// Fetched in full; stored when the response allows it.
+    Miss,
+    /// The cache was skipped on request, in which case the response still refreshes it, or
+    /// because the request carried credentials.
+    Bypass,
+}
+
+/// A cached response body with the headers needed to judge freshness and revalidate it.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheEntry {
+    pub url: String,
+    /// URL after redirects; absent in entries stored before it was recorded.
+    #[serde(default)]
+    pub final_url: Option<String>,
+    pub status: u16,
+    /// Response headers as received, without `Set-Cookie`.
+    #[serde(default)]
+    pub headers: Vec<(String, String)>,
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+    pub cache_control: Option<String>,
+    pub expires: Option<String>,
+    pub stored_at: DateTime<Utc>,
+    pub body: String,
+    /// Encoding the body was decoded from; it is stored as UTF-8.
+    #[serde(default = "default_encoding")]
+    pub encoding: String,
+}
+
+fn default_encoding() -> String {
+    "UTF-8".to_string()
+}
+
+impl CacheEntry {
+    pub fn new(
+        url: &str,
+        final_url: &str,
+        status: u16,
+        headers: &HeaderMap,
+        body: String,
+        encoding: &str,
+    ) -> Self {
+        let mut entry = Self {
+            url: url.to_string(),
+            final_url: Some(final_url.to_string()),
+            status,
+            headers: header_pairs(headers),
+            etag: None,
+            last_modified: None,
+            cache_control: None,
+            expires: None,
+            stored_at: Utc::now(),
+            body,
+            encoding: encoding.to_string(),
+        };
+        entry.refresh(headers);
+        entry
+    }
+
+    /// Takes the caching headers of a new response, as sent with a body or a `304`.
+    pub fn refresh(&mut self, headers: &HeaderMap) {
+        let header = |name| {
+            headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string)
+        };
+        if let Some(etag) = header(ETAG) {
+            self.etag = Some(etag);
+        }
+        if let Some(last_modified) = header(LAST_MODIFIED) {
+            self.last_modified = Some(last_modified);
+        }
+        self.cache_control = header(CACHE_CONTROL);
+        self.expires = header(EXPIRES);
+        self.stored_at = Utc::now();
+    }
+
+    /// Whether the entry may be served without asking the server, per `max-age` or `Expires`.
+    /// Responses with neither are always revalidated.
+    pub fn is_fresh(&self, now: DateTime<Utc>) -> bool {
+        let directives = directives(self.cache_control.as_deref());
+        if directives
+            .iter()
+            .any(|directive| directive == "no-cache" || directive == "no-store")
+        {
+            return false;
+        }
+        let max_age = directives.iter().find_map(|directive| {
+            directive
+                .strip_prefix("max-age=")
+                .and_then(|value| value.trim_matches('"').parse::<i64>().ok())
+        });
+        if let Some(max_age) = max_age {
+            return now < self.stored_at + Duration::seconds(max_age);
+        }
+        self.expires
+            .as_deref()
+            .and_then(|value| DateTime::parse_from_rfc2822(value).ok())
+            .is_some_and(|expires| now < expires)
+    }
+
+    pub fn has_validators(&self) -> bool {
+        self.etag.is_some() || self.last_modified.is_some()
+    }
+
+    /// Whether the response may be stored at all.
+    pub fn is_storable(&self) -> bool {
+        self.status == 200
+            && !directives(self.cache_control.as_deref())
+                .iter()
+                .any(|directive| directive == "no-store")
+    }
+}
+
+/// Headers as name/value pairs, leaving out `Set-Cookie` so session cookies are never persisted
+/// outside the credential store.
+pub fn header_pairs(headers: &HeaderMap) -> Vec<(String, String)> {
+    headers
+        .iter()
+        .filter(|(name, _)| *name != SET_COOKIE)
+        .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_string())))
+        .collect()
+}
+
+fn directives(cache_control: Option<&str>) -> Vec<String> {
+    cache_control
+        .unwrap_or_default()
+        .split(',')
+        .map(|directive| directive.trim().to_lowercase())
+        .filter(|directive| !directive.is_empty())
+        .collect()
+}
+
+/// Response cache kept as one JSON file per URL.
+#[derive(Debug, Clone)]
+pub struct HttpCache {
+    dir: PathBuf,
+}
+
+impl HttpCache {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    fn path(&self, url: &str) -> PathBuf {
+        self.dir
+            .join(format!("{}.json", embedding::content_hash(url)))
+    }
+
+    pub async fn load(&self, url: &str) -> Option<CacheEntry> {
+        let bytes = tokio::fs::read(self.path(url)).await.ok()?;
+        let entry: CacheEntry = serde_json::from_slice(&bytes).ok()?;
+        // Guards against hash collisions between URLs.
+        (entry.url == url).then_some(entry)
+    }
+
+    pub async fn store(&self, entry: &CacheEntry) -> Result<(), String> {
+        tokio::fs::create_dir_all(&self.dir)
+            .await
+            .map_err(|err| format!("create http cache dir: {err}"))?;
+        let bytes =
+            serde_json::to_vec(entry).map_err(|err| format!("serialize cache entry: {err}"))?;
+        tokio::fs::write(self.path(&entry.url), bytes)
+            .await
+            .map_err(|err| format!("write cache entry: {err}"))
+    }
+
+    pub async fn remove(&self, url: &str) {
+        let _ = tokio::fs::remove_file(self.path(url)).await;
+    }
+}
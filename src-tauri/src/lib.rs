@@ -1,9 +1,31 @@
 pub mod analysis_agent;
+mod charset;
 mod commands;
+mod company;
+mod crawler;
+mod credentials;
 mod db;
+mod embedding;
+mod feedback;
+mod feeds;
+mod geo;
+mod http;
+mod http_cache;
+mod llm;
 mod mcp;
+mod rescore;
+mod robots;
+mod rules;
+mod scoring;
 mod settings;
+mod skills;
+mod snapshots;
 mod state;
+mod text;
+mod timezone;
+mod titles;
+mod truncate;
+mod watch;
 
 use tauri::Manager;
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
@@ -21,15 +43,63 @@ pub fn run() {
 
             settings::ensure_defaults(app.handle())?;
             let db = db::Db::new(app.handle())?;
-            let mcp_port = mcp::start(app.handle().clone(), db.clone())?;
-            app.manage(state::AppState { mcp_port, db });
+            let data_dir = app.path().app_data_dir().ok();
+            let http = http::HttpClient::new(
+                &settings::load_network_settings(app.handle())?,
+                data_dir.as_ref().map(|dir| dir.join("http_cache")),
+                credentials::CredentialStore::load(
+                    data_dir.as_ref().map(|dir| dir.join("credentials.json")),
+                ),
+            )?;
+            let mcp_port = mcp::start(app.handle().clone(), db.clone(), http.clone())?;
+            watch::start(app.handle().clone(), db.clone(), http.clone());
+            feeds::start(app.handle().clone(), db.clone(), http.clone(), mcp_port);
+            app.manage(state::AppState { mcp_port, db, http });
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
             commands::get_settings,
             commands::update_settings,
+            commands::list_profiles,
+            commands::create_profile,
+            commands::rename_profile,
+            commands::delete_profile,
+            commands::activate_profile,
+            commands::get_llm_settings,
+            commands::update_llm_settings,
+            commands::get_network_settings,
+            commands::update_network_settings,
+            commands::import_cookies,
+            commands::set_domain_headers,
+            commands::clear_credentials,
+            commands::list_credential_domains,
+            commands::get_archive_settings,
+            commands::update_archive_settings,
+            commands::get_snapshot,
+            commands::reextract_snapshot,
+            commands::get_watch_settings,
+            commands::update_watch_settings,
+            commands::check_listing_changes,
+            commands::get_listing_changes,
+            commands::get_feed_settings,
+            commands::update_feed_settings,
+            commands::add_feed,
+            commands::remove_feed,
+            commands::list_feeds,
+            commands::poll_feeds,
+            commands::get_crawl_settings,
+            commands::update_crawl_settings,
+            commands::crawl_careers_site,
             commands::start_analysis,
             commands::list_job_matches,
+            commands::find_similar_jobs,
+            commands::set_match_decision,
+            commands::rescore_matches,
+            commands::skill_gap_report,
+            commands::get_score_history,
+            commands::rate_job_match,
+            commands::get_scoring_weights,
+            commands::reset_scoring_weights,
             commands::clear_job_matches,
         ])
         .run(tauri::generate_context!())
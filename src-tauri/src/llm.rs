@@ -0,0 +1,340 @@
+use crate::settings::LlmSettings;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::time::Duration;
+
+pub const EXTRACTION_SYSTEM_PROMPT: &str = "You extract structured data from job listings. \
+Reply with a single JSON object that matches the provided JSON schema. \
+Use null when a field is not stated in the listing. Do not add commentary.";
+
+pub const FIT_SYSTEM_PROMPT: &str = "You are a career assistant that judges how well a job listing fits a \
+candidate's search preferences. Reply with a single JSON object that matches the provided JSON schema. \
+Be concrete and base every point on the listing text. Do not add commentary.";
+
+/// Listing text sent to the model is capped to keep prompts within small local context windows.
+pub const MAX_PROMPT_CHARS: usize = 8_000;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct LlmExtraction {
+    pub title: Option<String>,
+    pub company: Option<String>,
+    pub location: Option<String>,
+    pub arrangement: Option<String>,
+    #[serde(default)]
+    pub skills: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct LlmFit {
+    pub fit_summary: String,
+    pub pros: Vec<String>,
+    pub cons: Vec<String>,
+}
+
+pub fn extraction_schema() -> Value {
+    json!({
+      "type": "object",
+      "properties": {
+        "title": { "type": ["string", "null"] },
+        "company": { "type": ["string", "null"] },
+        "location": { "type": ["string", "null"] },
+        "arrangement": { "type": ["string", "null"], "enum": ["remote", "hybrid", "onsite", null] },
+        "skills": { "type": "array", "items": { "type": "string" }, "maxItems": 40 }
+      },
+      "required": ["title", "company", "location", "arrangement", "skills"]
+    })
+}
+
+pub fn fit_schema() -> Value {
+    json!({
+      "type": "object",
+      "properties": {
+        "fit_summary": { "type": "string", "minLength": 1 },
+        "pros": { "type": "array", "items": { "type": "string" }, "maxItems": 8 },
+        "cons": { "type": "array", "items": { "type": "string" }, "maxItems": 8 }
+      },
+      "required": ["fit_summary", "pros", "cons"]
+    })
+}
+
+pub fn extraction_prompt(listing_text: &str) -> String {
+    format!(
+        "JSON schema:\n{}\n\nJob listing:\n\"\"\"\n{}\n\"\"\"",
+        extraction_schema(),
+        clip(listing_text)
+    )
+}
+
+pub fn fit_prompt(listing_text: &str, preferences: &Value) -> String {
+    format!(
+        "JSON schema:\n{}\n\nCandidate preferences:\n{}\n\nJob listing:\n\"\"\"\n{}\n\"\"\"",
+        fit_schema(),
+        preferences,
+        clip(listing_text)
+    )
+}
+
+pub fn parse_extraction(value: Value) -> Result<LlmExtraction, String> {
+    validate(&value, &extraction_schema(), "$")?;
+    serde_json::from_value(value).map_err(|err| format!("extraction parse: {err}"))
+}
+
+pub fn parse_fit(value: Value) -> Result<LlmFit, String> {
+    validate(&value, &fit_schema(), "$")?;
+    serde_json::from_value(value).map_err(|err| format!("fit parse: {err}"))
+}
+
+/// Sends a chat completion to an OpenAI-compatible endpoint and returns the reply parsed as JSON.
+pub async fn complete_json(
+    settings: &LlmSettings,
+    system_prompt: &str,
+    prompt: &str,
+) -> Result<Value, String> {
+    if !settings.enabled {
+        return Err("llm stage is disabled".to_string());
+    }
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(settings.timeout_secs.max(1)))
+        .build()
+        .map_err(|err| format!("llm client: {err}"))?;
+    let endpoint = format!(
+        "{}/chat/completions",
+        settings.base_url.trim_end_matches('/')
+    );
+    let mut request = client.post(endpoint).json(&json!({
+      "model": settings.model,
+      "temperature": settings.temperature,
+      "response_format": { "type": "json_object" },
+      "messages": [
+        { "role": "system", "content": system_prompt },
+        { "role": "user", "content": prompt }
+      ]
+    }));
+    if let Some(key) = settings.api_key.as_deref().filter(|key| !key.is_empty()) {
+        request = request.bearer_auth(key);
+    }
+
+    let response = request
+        .send()
+        .await
+        .map_err(|err| format!("llm request: {err}"))?;
+    let status = response.status();
+    let body = response
+        .text()
+        .await
+        .map_err(|err| format!("llm body: {err}"))?;
+    if !status.is_success() {
+        return Err(format!("llm status {}: {}", status.as_u16(), clip(&body)));
+    }
+    let body: Value = serde_json::from_str(&body).map_err(|err| format!("llm response: {err}"))?;
+    let content = body
+        .pointer("/choices/0/message/content")
+        .and_then(|v| v.as_str())
+        .ok_or("llm response missing message content")?;
+    parse_json_content(content)
+}
+
+/// Local models often wrap JSON in prose or code fences, so only the outermost object is parsed.
+fn parse_json_content(content: &str) -> Result<Value, String> {
+    let start = content
+        .find('{')
+        .ok_or("llm reply contains no json object")?;
+    let end = content
+        .rfind('}')
+        .ok_or("llm reply contains no json object")?;
+    if end < start {
+        return Err("llm reply contains no json object".to_string());
+    }
+    serde_json::from_str(&content[start..=end]).map_err(|err| format!("llm reply parse: {err}"))
+}
+
+/// Validates a value against the subset of JSON Schema used by the prompt templates.
+pub fn validate(value: &Value, schema: &Value, path: &str) -> Result<(), String> {
+    if let Some(expected) = schema.get("type") {
+        let allowed: Vec<&str> = match expected {
+            Value::String(name) => vec![name.as_str()],
+            Value::Array(names) => names.iter().filter_map(|v| v.as_str()).collect(),
+            _ => Vec::new(),
+        };
+        if !allowed.is_empty() && !allowed.iter().any(|name| type_matches(value, name)) {
+            return Err(format!("{path}: expected {}", allowed.join(" or ")));
+        }
+    }
+    if let Some(options) = schema.get("enum").and_then(|v| v.as_array()) {
+        if !options.contains(value) {
+            return Err(format!("{path}: value {value} is not allowed"));
+        }
+    }
+    if let Some(text) = value.as_str() {
+        if let Some(min) = schema.get("minLength").and_then(|v| v.as_u64()) {
+            if (text.trim().chars().count() as u64) < min {
+                return Err(format!("{path}: shorter than {min} characters"));
+            }
+        }
+    }
+    if let Some(number) = value.as_f64() {
+        if let Some(min) = schema.get("minimum").and_then(|v| v.as_f64()) {
+            if number < min {
+                return Err(format!("{path}: below minimum {min}"));
+            }
+        }
+        if let Some(max) = schema.get("maximum").and_then(|v| v.as_f64()) {
+            if number > max {
+                return Err(format!("{path}: above maximum {max}"));
+            }
+        }
+    }
+    if let Some(items) = value.as_array() {
+        if let Some(max) = schema.get("maxItems").and_then(|v| v.as_u64()) {
+            if items.len() as u64 > max {
+                return Err(format!("{path}: more than {max} items"));
+            }
+        }
+        if let Some(item_schema) = schema.get("items") {
+            for (index, item) in items.iter().enumerate() {
+                validate(item, item_schema, &format!("{path}[{index}]"))?;
+            }
+        }
+    }
+    if let Some(object) = value.as_object() {
+        if let Some(required) = schema.get("required").and_then(|v| v.as_array()) {
+            for key in required.iter().filter_map(|v| v.as_str()) {
+                if !object.contains_key(key) {
+                    return Err(format!("{path}.{key}: missing required field"));
+                }
+            }
+        }
+        if let Some(properties) = schema.get("properties").and_then(|v| v.as_object()) {
+            for (key, property_schema) in properties {
+                if let Some(property) = object.get(key) {
+                    validate(property, property_schema, &format!("{path}.{key}"))?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn type_matches(value: &Value, name: &str) -> bool {
+    match name {
+        "string" => value.is_string(),
+        "number" => value.is_number(),
+        "integer" => value.is_i64() || value.is_u64(),
+        "boolean" => value.is_boolean(),
+        "array" => value.is_array(),
+        "object" => value.is_object(),
+        "null" => value.is_null(),
+        _ => false,
+    }
+}
+
+fn clip(text: &str) -> String {
+    text.chars().take(MAX_PROMPT_CHARS).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    /// Serves one HTTP response with `status` and `body`, returning the base URL.
+    async fn mock_server(status: u16, body: String) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = Vec::new();
+            let mut buf = [0u8; 4096];
+            // Read the headers, then as much body as Content-Length announces.
+            loop {
+                let read = socket.read(&mut buf).await.unwrap();
+                if read == 0 {
+                    break;
+                }
+                request.extend_from_slice(&buf[..read]);
+                let text = String::from_utf8_lossy(&request);
+                if let Some(head_end) = text.find("\r\n\r\n") {
+                    let length = text[..head_end]
+                        .lines()
+                        .find_map(|line| {
+                            let (name, value) = line.split_once(':')?;
+                            name.eq_ignore_ascii_case("content-length")
+                                .then(|| value.trim().parse::<usize>().ok())?
+                        })
+                        .unwrap_or(0);
+                    if request.len() >= head_end + 4 + length {
+                        break;
+                    }
+                }
+            }
+            let response = format!(
+                "HTTP/1.1 {status} Mock\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            );
+            socket.write_all(response.as_bytes()).await.unwrap();
+            socket.shutdown().await.unwrap();
+        });
+        format!("http://{addr}/v1")
+    }
+
+    fn completion(content: &str) -> String {
+        json!({ "choices": [{ "message": { "role": "assistant", "content": content } }] })
+            .to_string()
+    }
+
+    fn settings(base_url: String) -> LlmSettings {
+        LlmSettings {
+            enabled: true,
+            base_url,
+            timeout_secs: 5,
+            ..LlmSettings::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn valid_reply_parses_and_validates() {
+        let reply = json!({ "fit_summary": "Strong match", "pros": ["Rust"], "cons": [] });
+        let base_url = mock_server(200, completion(&reply.to_string())).await;
+        let value = complete_json(&settings(base_url), FIT_SYSTEM_PROMPT, "prompt")
+            .await
+            .unwrap();
+        let fit = parse_fit(value).unwrap();
+        assert_eq!(fit.fit_summary, "Strong match");
+        assert_eq!(fit.pros, vec!["Rust"]);
+    }
+
+    #[tokio::test]
+    async fn code_fenced_reply_is_unwrapped() {
+        let content = "Here you go:\n```json\n{\"title\": \"Engineer\", \"company\": null, \
+                       \"location\": null, \"arrangement\": \"remote\", \"skills\": [\"go\"]}\n```";
+        let base_url = mock_server(200, completion(content)).await;
+        let value = complete_json(&settings(base_url), EXTRACTION_SYSTEM_PROMPT, "prompt")
+            .await
+            .unwrap();
+        let extraction = parse_extraction(value).unwrap();
+        assert_eq!(extraction.title.as_deref(), Some("Engineer"));
+        assert_eq!(extraction.arrangement.as_deref(), Some("remote"));
+    }
+
+    #[tokio::test]
+    async fn reply_failing_schema_is_rejected() {
+        let reply = json!({ "fit_summary": "", "pros": "many", "cons": [] });
+        let base_url = mock_server(200, completion(&reply.to_string())).await;
+        let value = complete_json(&settings(base_url), FIT_SYSTEM_PROMPT, "prompt")
+            .await
+            .unwrap();
+        let err = parse_fit(value).unwrap_err();
+        assert!(err.starts_with("$.fit_summary"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn error_status_is_reported() {
+        let base_url = mock_server(503, "{\"error\":\"model loading\"}".to_string()).await;
+        let err = complete_json(&settings(base_url), FIT_SYSTEM_PROMPT, "prompt")
+            .await
+            .unwrap_err();
+        assert!(err.starts_with("llm status 503"), "{err}");
+        assert!(err.contains("model loading"), "{err}");
+    }
+}
@@ -1,16 +1,76 @@
-use crate::db::{Db, JobMatchInput};
-use crate::settings::{load_settings, save_settings, JobSettings};
-use regex::Regex;
+use crate::crawler;
+use crate::db::{Db, Decision, JobMatchInput, MatchQuery, MatchSort};
+use crate::embedding;
+use crate::feedback::{self, Rating};
+use crate::feeds;
+use crate::http::{ErrorCategory, FetchError, FetchOrigin, HttpClient};
+use crate::llm;
+use crate::rescore;
+use crate::settings::{
+    load_crawl_settings, load_llm_settings, load_profiles, load_settings, save_settings,
+    update_profiles, JobSettings,
+};
+use crate::skills::{self, ReportFormat, SkillReportOptions};
+use crate::snapshots;
+use crate::state::AppState;
+use crate::text;
+use crate::truncate;
+use crate::watch;
 use scraper::{Html, Selector};
+use serde::Serialize;
 use serde_json::{json, Value};
 use std::net::TcpListener as StdTcpListener;
-use tauri::{AppHandle, Emitter};
+use tauri::{AppHandle, Emitter, Manager};
 use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
 use tokio::net::TcpListener;
 
 const MCP_VERSION: &str = "0.1";
+/// Size of the plain-text preview returned by `fetch_content`.
+pub const TEXT_EXCERPT_BYTES: usize = 2000;
 
-pub fn start(app: AppHandle, db: Db) -> Result<u16, String> {
+/// The `error` payload of a failed tool call. Fetch failures carry a category, the HTTP status
+/// and any `Retry-After` so callers can tell a block from a transient failure.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ToolError {
+    message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    category: Option<ErrorCategory>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    status: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    retry_after: Option<u64>,
+}
+
+impl From<String> for ToolError {
+    fn from(message: String) -> Self {
+        Self {
+            message,
+            category: None,
+            status: None,
+            retry_after: None,
+        }
+    }
+}
+
+impl From<&str> for ToolError {
+    fn from(message: &str) -> Self {
+        Self::from(message.to_string())
+    }
+}
+
+impl From<FetchError> for ToolError {
+    fn from(err: FetchError) -> Self {
+        Self {
+            message: err.message,
+            category: err.category,
+            status: err.status,
+            retry_after: err.retry_after,
+        }
+    }
+}
+
+pub fn start(app: AppHandle, db: Db, http: HttpClient) -> Result<u16, String> {
     let listener = StdTcpListener::bind("127.0.0.1:0").map_err(|err| format!("mcp bind: {err}"))?;
     listener
         .set_nonblocking(true)
@@ -38,8 +98,9 @@ pub fn start(app: AppHandle, db: Db) -> Result<u16, String> {
 
             let app = app.clone();
             let db = db.clone();
+            let http = http.clone();
             tokio::spawn(async move {
-                if let Err(err) = handle_client(stream, app, db).await {
+                if let Err(err) = handle_client(stream, app, db, http).await {
                     log::error!("mcp client error: {err}");
                 }
             });
@@ -53,6 +114,7 @@ async fn handle_client(
     stream: tokio::net::TcpStream,
     app: AppHandle,
     db: Db,
+    http: HttpClient,
 ) -> Result<(), String> {
     let (reader, mut writer) = stream.into_split();
     let mut reader = BufReader::new(reader);
@@ -110,9 +172,9 @@ async fn handle_client(
                     .get("arguments")
                     .cloned()
                     .unwrap_or_else(|| json!({}));
-                match handle_tool(name, arguments, &app, &db).await {
+                match handle_tool(name, arguments, &app, &db, &http).await {
                     Ok(result) => json!({ "id": id, "result": result }),
-                    Err(err) => json!({ "id": id, "error": { "message": err } }),
+                    Err(err) => json!({ "id": id, "error": err }),
                 }
             }
             _ => json!({
@@ -145,16 +207,129 @@ fn tool_definitions() -> Vec<Value> {
         }),
         json!({
           "name": "fetch_content",
-          "description": "Retrieve HTML content for a given URL.",
+          "description": "Retrieve HTML content for a given URL through the HTTP cache; `cache` in the result is hit, revalidated, miss or bypass. `truncated` and `textTruncated` report whether the html or text preview were cut short. The full page is archived; pass analysisId to link the snapshot to the analysis, and `snapshotId` in the result identifies it. The site's robots.txt is checked first under the user mode, or the automated mode when automated is set; `robotsWarning` is set when a disallowed page was fetched anyway. Transient failures are retried; errors carry a category: dns, tls, timeout, connection, rate_limited, blocked, not_found, server_error or disallowed_by_robots.",
           "inputSchema": {
             "type": "object",
             "properties": {
               "url": { "type": "string" },
+              "maxLength": { "type": "number" },
+              "bypassCache": { "type": "boolean" },
+              "analysisId": { "type": "string" },
+              "automated": { "type": "boolean" }
+            },
+            "required": ["url"]
+          }
+        }),
+        json!({
+          "name": "get_snapshot",
+          "description": "Load an archived page by snapshotId, or the latest one behind a matchId: full HTML, headers, final URL, fetch time and content hash.",
+          "inputSchema": {
+            "type": "object",
+            "properties": {
+              "snapshotId": { "type": "string" },
+              "matchId": { "type": "string" },
               "maxLength": { "type": "number" }
+            }
+          }
+        }),
+        json!({
+          "name": "add_feed",
+          "description": "Subscribe to an RSS or Atom feed of job postings. New items are analyzed automatically; items already in the feed are only analyzed with analyzeExisting.",
+          "inputSchema": {
+            "type": "object",
+            "properties": {
+              "url": { "type": "string" },
+              "analyzeExisting": { "type": "boolean" }
             },
             "required": ["url"]
           }
         }),
+        json!({
+          "name": "remove_feed",
+          "description": "Unsubscribe from a feed, given its id or URL.",
+          "inputSchema": {
+            "type": "object",
+            "properties": { "feed": { "type": "string" } },
+            "required": ["feed"]
+          }
+        }),
+        json!({
+          "name": "list_feeds",
+          "description": "List feed subscriptions with their last poll time, last error and item counts.",
+          "inputSchema": { "type": "object" }
+        }),
+        json!({
+          "name": "poll_feeds",
+          "description": "Poll one feed (id or URL) or every feed now and analyze new items.",
+          "inputSchema": {
+            "type": "object",
+            "properties": { "feed": { "type": "string" } }
+          }
+        }),
+        json!({
+          "name": "crawl_careers_site",
+          "description": "Crawl a company careers site from a start URL, staying on its host and under its path. Reads robots.txt and sitemaps, follows links up to maxDepth hops and maxPages pages (defaults from the crawl settings), detects job postings by their schema.org JobPosting markup and queues new, unexpired ones for analysis unless analyze is false.",
+          "inputSchema": {
+            "type": "object",
+            "properties": {
+              "url": { "type": "string" },
+              "maxDepth": { "type": "number" },
+              "maxPages": { "type": "number" },
+              "analyze": { "type": "boolean" }
+            },
+            "required": ["url"]
+          }
+        }),
+        json!({
+          "name": "check_listing_changes",
+          "description": "Refetch a watched match (or every watched match) and compare it with its latest snapshot, recording changes such as salary_added, salary_changed, content_edited or closed with a text diff.",
+          "inputSchema": {
+            "type": "object",
+            "properties": { "matchId": { "type": "string" } }
+          }
+        }),
+        json!({
+          "name": "get_listing_changes",
+          "description": "List recorded listing changes, newest first, for one match or all of them.",
+          "inputSchema": {
+            "type": "object",
+            "properties": {
+              "matchId": { "type": "string" },
+              "limit": { "type": "number" }
+            }
+          }
+        }),
+        json!({
+          "name": "reextract_snapshot",
+          "description": "Re-run listing extraction on an archived page without fetching it. With save, the result replaces the stored listing of the matches from that analysis.",
+          "inputSchema": {
+            "type": "object",
+            "properties": {
+              "snapshotId": { "type": "string" },
+              "matchId": { "type": "string" },
+              "save": { "type": "boolean" }
+            }
+          }
+        }),
+        json!({
+          "name": "list_credential_domains",
+          "description": "List domains with imported cookies or custom headers, by cookie and header name only; values are never returned.",
+          "inputSchema": { "type": "object" }
+        }),
+        json!({
+          "name": "report_analysis_error",
+          "description": "Report that an analysis failed, emitting `analysis:failed` with the error category from the failing tool.",
+          "inputSchema": {
+            "type": "object",
+            "properties": {
+              "analysisId": { "type": "string" },
+              "url": { "type": "string" },
+              "message": { "type": "string" },
+              "category": { "type": "string" }
+            },
+            "required": ["message"]
+          }
+        }),
         json!({
           "name": "reload_page",
           "description": "Reload the current webview.",
@@ -173,6 +348,70 @@ fn tool_definitions() -> Vec<Value> {
             "properties": { "settings": { "type": "object" } }
           }
         }),
+        json!({
+          "name": "list_profiles",
+          "description": "List named search profiles and the active one.",
+          "inputSchema": { "type": "object" }
+        }),
+        json!({
+          "name": "create_profile",
+          "description": "Create a search profile, copying the active profile's settings unless settings are given.",
+          "inputSchema": {
+            "type": "object",
+            "properties": {
+              "name": { "type": "string" },
+              "settings": { "type": "object" }
+            },
+            "required": ["name"]
+          }
+        }),
+        json!({
+          "name": "rename_profile",
+          "description": "Rename a search profile.",
+          "inputSchema": {
+            "type": "object",
+            "properties": {
+              "name": { "type": "string" },
+              "newName": { "type": "string" }
+            },
+            "required": ["name", "newName"]
+          }
+        }),
+        json!({
+          "name": "delete_profile",
+          "description": "Delete a search profile and its per-profile scores.",
+          "inputSchema": {
+            "type": "object",
+            "properties": { "name": { "type": "string" } },
+            "required": ["name"]
+          }
+        }),
+        json!({
+          "name": "activate_profile",
+          "description": "Make a search profile the active one.",
+          "inputSchema": {
+            "type": "object",
+            "properties": { "name": { "type": "string" } },
+            "required": ["name"]
+          }
+        }),
+        json!({
+          "name": "get_llm_settings",
+          "description": "Load the optional LLM stage configuration (the API key is never returned).",
+          "inputSchema": { "type": "object" }
+        }),
+        json!({
+          "name": "llm_complete",
+          "description": "Run a JSON-mode chat completion against the configured OpenAI-compatible endpoint.",
+          "inputSchema": {
+            "type": "object",
+            "properties": {
+              "system": { "type": "string" },
+              "prompt": { "type": "string" }
+            },
+            "required": ["prompt"]
+          }
+        }),
         json!({
           "name": "save_job_match",
           "description": "Save a job match to SQLite.",
@@ -186,16 +425,127 @@ fn tool_definitions() -> Vec<Value> {
               "location": { "type": "string" },
               "match_score": { "type": "number" },
               "summary": { "type": "string" },
-              "raw_excerpt": { "type": "string" }
+              "raw_excerpt": { "type": "string" },
+              "insights": { "type": "object" },
+              "content_hash": { "type": "string" },
+              "score_breakdown": { "type": "object" },
+              "profile_scores": { "type": "array" },
+              "rejection": { "type": "object" },
+              "listing": { "type": "object" },
+              "role_family": { "type": "string" },
+              "settings_hash": { "type": "string" },
+              "scorer_version": { "type": "integer" }
+            }
+          }
+        }),
+        json!({
+          "name": "rate_job_match",
+          "description": "Rate a saved job match as good or bad and retrain the scoring weights.",
+          "inputSchema": {
+            "type": "object",
+            "properties": {
+              "matchId": { "type": "string" },
+              "rating": { "type": "string", "enum": ["good", "bad"] },
+              "profile": { "type": "string" }
+            },
+            "required": ["matchId", "rating"]
+          }
+        }),
+        json!({
+          "name": "get_scoring_weights",
+          "description": "Show default, learned and effective scoring weights for a profile (default: active).",
+          "inputSchema": {
+            "type": "object",
+            "properties": { "profile": { "type": "string" } }
+          }
+        }),
+        json!({
+          "name": "reset_scoring_weights",
+          "description": "Discard learned scoring weights and the ratings behind them.",
+          "inputSchema": {
+            "type": "object",
+            "properties": { "profile": { "type": "string" } }
+          }
+        }),
+        json!({
+          "name": "semantic_similarity",
+          "description": "Embed text on CPU and return its cosine similarity to the search profile (or a reference text).",
+          "inputSchema": {
+            "type": "object",
+            "properties": {
+              "text": { "type": "string" },
+              "reference": { "type": "string" }
+            },
+            "required": ["text"]
+          }
+        }),
+        json!({
+          "name": "find_similar_jobs",
+          "description": "Rank stored job matches by semantic similarity to a match, a text, or the search profile.",
+          "inputSchema": {
+            "type": "object",
+            "properties": {
+              "matchId": { "type": "string" },
+              "text": { "type": "string" },
+              "limit": { "type": "number" }
             }
           }
         }),
         json!({
           "name": "list_job_matches",
-          "description": "List recent job matches.",
+          "description": "List job matches, optionally scored and sorted by a profile. Rejected matches are hidden unless includeRejected is set.",
+          "inputSchema": {
+            "type": "object",
+            "properties": {
+              "limit": { "type": "number" },
+              "profile": { "type": "string" },
+              "sort": { "type": "string", "enum": ["recent", "score"] },
+              "includeRejected": { "type": "boolean" }
+            }
+          }
+        }),
+        json!({
+          "name": "set_match_decision",
+          "description": "Accept or reject a job match by hand, overriding the hard filters. A null decision restores the filters' verdict.",
           "inputSchema": {
             "type": "object",
-            "properties": { "limit": { "type": "number" } }
+            "properties": {
+              "matchId": { "type": "string" },
+              "decision": { "type": ["string", "null"], "enum": ["accepted", "rejected", null] }
+            },
+            "required": ["matchId"]
+          }
+        }),
+        json!({
+          "name": "rescore_matches",
+          "description": "Re-run the matcher over stored listings with the current settings, keeping each previous score.",
+          "inputSchema": { "type": "object" }
+        }),
+        json!({
+          "name": "get_score_history",
+          "description": "List every score recorded for a job match, oldest first, with the settings hash and scorer version behind each.",
+          "inputSchema": {
+            "type": "object",
+            "properties": {
+              "matchId": { "type": "string" },
+              "profile": { "type": "string" }
+            },
+            "required": ["matchId"]
+          }
+        }),
+        json!({
+          "name": "skill_gap_report",
+          "description": "Aggregate the skills required across accepted matches and list those missing from the profile's keywords and resume.",
+          "inputSchema": {
+            "type": "object",
+            "properties": {
+              "profile": { "type": "string" },
+              "weighting": { "type": "string", "enum": ["count", "score"] },
+              "minScore": { "type": "number" },
+              "limit": { "type": "integer", "minimum": 0 },
+              "examples": { "type": "integer", "minimum": 0 },
+              "format": { "type": "string", "enum": ["json", "markdown"] }
+            }
           }
         }),
         json!({
@@ -211,7 +561,8 @@ async fn handle_tool(
     arguments: Value,
     app: &AppHandle,
     db: &Db,
-) -> Result<Value, String> {
+    http: &HttpClient,
+) -> Result<Value, ToolError> {
     match name {
         "set_query_params" => {
             let payload = json!({
@@ -231,27 +582,28 @@ async fn handle_tool(
                 .and_then(|v| v.as_u64())
                 .unwrap_or(60_000) as usize;
 
-            let client = reqwest::Client::builder()
-                .user_agent("JobHunter/1.0")
-                .build()
-                .map_err(|err| format!("http client: {err}"))?;
-            let response = client
-                .get(url)
-                .send()
-                .await
-                .map_err(|err| format!("http fetch: {err}"))?;
-            let status = response.status().as_u16();
-            let html = response
-                .text()
-                .await
-                .map_err(|err| format!("http body: {err}"))?;
-            let trimmed = if html.len() > max_length {
-                html[..max_length].to_string()
+            let bypass_cache = arguments
+                .get("bypassCache")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false);
+            let analysis_id = arguments.get("analysisId").and_then(|v| v.as_str());
+            let origin = if arguments.get("automated").and_then(|v| v.as_bool()) == Some(true) {
+                FetchOrigin::Automated
             } else {
-                html.clone()
+                FetchOrigin::User
             };
+            let page = http.fetch(url, bypass_cache, origin).await?;
+            if let Some(warning) = &page.robots_warning {
+                let _ = app.emit(
+                    "robots:warning",
+                    json!({ "url": url, "analysisId": analysis_id, "message": warning }),
+                );
+            }
+            let snapshot_id = snapshots::archive(app, db, url, &page, analysis_id);
+            let html = &page.body;
+            let trimmed = truncate::at_char_boundary(html, max_length);
 
-            let document = Html::parse_document(&trimmed);
+            let document = Html::parse_document(trimmed);
             let title_selector = Selector::parse("title").map_err(|err| err.to_string())?;
             let title = document
                 .select(&title_selector)
@@ -259,22 +611,127 @@ async fn handle_tool(
                 .map(|node| node.text().collect::<String>())
                 .unwrap_or_default();
             let text_raw = document.root_element().text().collect::<Vec<_>>().join(" ");
-            let whitespace = Regex::new(r"\s+").map_err(|err| err.to_string())?;
-            let text = whitespace.replace_all(&text_raw, " ").trim().to_string();
-            let text_excerpt = if text.len() > 2000 {
-                text[..2000].to_string()
-            } else {
-                text.clone()
-            };
+            let text = text::collapse_whitespace(&text_raw);
+            let text_excerpt = truncate::at_sentence_boundary(&text, TEXT_EXCERPT_BYTES);
 
             Ok(json!({
-              "status": status,
+              "status": page.status,
               "url": url,
+              "finalUrl": page.final_url,
               "title": title,
               "html": trimmed,
-              "text": text_excerpt
+              "text": text_excerpt,
+              "cache": page.cache,
+              "encoding": page.encoding,
+              "truncated": page.truncated || trimmed.len() < html.len(),
+              "textTruncated": text_excerpt.len() < text.len(),
+              "snapshotId": snapshot_id,
+              "robotsWarning": page.robots_warning
             }))
         }
+        "get_snapshot" => {
+            let snapshot = snapshots::load(
+                db,
+                arguments.get("snapshotId").and_then(|v| v.as_str()),
+                arguments.get("matchId").and_then(|v| v.as_str()),
+                arguments
+                    .get("maxLength")
+                    .and_then(|v| v.as_u64())
+                    .map(|value| value as usize),
+            )?;
+            Ok(json!({ "snapshot": snapshot }))
+        }
+        "add_feed" => {
+            let url = arguments
+                .get("url")
+                .and_then(|v| v.as_str())
+                .ok_or("url is required")?;
+            let analyze_existing = arguments
+                .get("analyzeExisting")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false);
+            let feed = feeds::add_feed(db, http, url, analyze_existing).await?;
+            Ok(json!({ "feed": feed }))
+        }
+        "remove_feed" => {
+            let feed = arguments
+                .get("feed")
+                .and_then(|v| v.as_str())
+                .ok_or("feed is required")?;
+            db.delete_feed(feed)?;
+            Ok(json!({ "ok": true }))
+        }
+        "list_feeds" => Ok(json!({ "feeds": db.list_feeds(None)? })),
+        "poll_feeds" => {
+            let mcp_port = app
+                .try_state::<AppState>()
+                .map(|state| state.mcp_port)
+                .ok_or("app is still starting")?;
+            let feed = arguments.get("feed").and_then(|v| v.as_str());
+            let summary = feeds::poll(app, db, http, mcp_port, feed, None).await?;
+            Ok(json!(summary))
+        }
+        "crawl_careers_site" => {
+            let url = arguments
+                .get("url")
+                .and_then(|v| v.as_str())
+                .ok_or("url is required")?;
+            let mcp_port = app
+                .try_state::<AppState>()
+                .map(|state| state.mcp_port)
+                .ok_or("app is still starting")?;
+            let mut settings = load_crawl_settings(app)?;
+            if let Some(depth) = arguments.get("maxDepth").and_then(|v| v.as_u64()) {
+                settings.max_depth = depth as u32;
+            }
+            if let Some(pages) = arguments.get("maxPages").and_then(|v| v.as_u64()) {
+                settings.max_pages = (pages as usize).max(1);
+            }
+            let analyze = arguments
+                .get("analyze")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(true);
+            let summary = crawler::crawl(app, db, http, mcp_port, url, &settings, analyze).await?;
+            Ok(json!(summary))
+        }
+        "check_listing_changes" => {
+            let match_id = arguments.get("matchId").and_then(|v| v.as_str());
+            let summary = watch::check(app, db, http, match_id, None).await?;
+            Ok(json!(summary))
+        }
+        "get_listing_changes" => {
+            let match_id = arguments.get("matchId").and_then(|v| v.as_str());
+            let limit = arguments
+                .get("limit")
+                .and_then(|v| v.as_u64())
+                .unwrap_or(50) as usize;
+            Ok(json!({ "changes": db.listing_changes(match_id, limit)? }))
+        }
+        "reextract_snapshot" => {
+            let result = snapshots::reextract(
+                db,
+                arguments.get("snapshotId").and_then(|v| v.as_str()),
+                arguments.get("matchId").and_then(|v| v.as_str()),
+                arguments
+                    .get("save")
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(false),
+            )?;
+            Ok(json!(result))
+        }
+        "list_credential_domains" => Ok(json!({ "domains": http.credentials().domains()? })),
+        "report_analysis_error" => {
+            let _ = app.emit(
+                "analysis:failed",
+                json!({
+                  "analysisId": arguments.get("analysisId").cloned().unwrap_or(Value::Null),
+                  "url": arguments.get("url").cloned().unwrap_or(Value::Null),
+                  "message": arguments.get("message").cloned().unwrap_or(Value::Null),
+                  "category": arguments.get("category").cloned().unwrap_or(Value::Null)
+                }),
+            );
+            Ok(json!({ "ok": true }))
+        }
         "reload_page" => {
             let _ = app.emit("mcp:reload", json!({}));
             Ok(json!({ "ok": true }))
@@ -293,6 +750,77 @@ async fn handle_tool(
             let saved = save_settings(app, &settings)?;
             Ok(json!({ "settings": saved }))
         }
+        "list_profiles" => Ok(json!(load_profiles(app)?)),
+        "create_profile" => {
+            let name = arguments
+                .get("name")
+                .and_then(|v| v.as_str())
+                .ok_or("name is required")?;
+            let settings = match arguments.get("settings") {
+                Some(value) => Some(
+                    serde_json::from_value::<JobSettings>(value.clone())
+                        .map_err(|err| format!("settings parse: {err}"))?,
+                ),
+                None => None,
+            };
+            Ok(json!(
+                update_profiles(app, |profiles| profiles.create(name, settings))?
+            ))
+        }
+        "rename_profile" => {
+            let name = arguments
+                .get("name")
+                .and_then(|v| v.as_str())
+                .ok_or("name is required")?;
+            let new_name = arguments
+                .get("newName")
+                .and_then(|v| v.as_str())
+                .ok_or("newName is required")?;
+            let profiles = update_profiles(app, |profiles| profiles.rename(name, new_name))?;
+            db.rename_profile(name, new_name.trim())?;
+            Ok(json!(profiles))
+        }
+        "delete_profile" => {
+            let name = arguments
+                .get("name")
+                .and_then(|v| v.as_str())
+                .ok_or("name is required")?;
+            let profiles = update_profiles(app, |profiles| profiles.delete(name))?;
+            db.delete_profile(name)?;
+            Ok(json!(profiles))
+        }
+        "activate_profile" => {
+            let name = arguments
+                .get("name")
+                .and_then(|v| v.as_str())
+                .ok_or("name is required")?;
+            Ok(json!(
+                update_profiles(app, |profiles| profiles.activate(name))?
+            ))
+        }
+        "get_llm_settings" => {
+            let settings = load_llm_settings(app)?;
+            Ok(json!({
+              "enabled": settings.enabled,
+              "baseUrl": settings.base_url,
+              "model": settings.model,
+              "hasApiKey": settings.api_key.as_deref().is_some_and(|key| !key.is_empty()),
+              "timeoutSecs": settings.timeout_secs
+            }))
+        }
+        "llm_complete" => {
+            let prompt = arguments
+                .get("prompt")
+                .and_then(|v| v.as_str())
+                .ok_or("prompt is required")?;
+            let system = arguments
+                .get("system")
+                .and_then(|v| v.as_str())
+                .unwrap_or_default();
+            let settings = load_llm_settings(app)?;
+            let content = llm::complete_json(&settings, system, prompt).await?;
+            Ok(json!({ "content": content }))
+        }
         "save_job_match" => {
             let input: JobMatchInput = serde_json::from_value(arguments)
                 .map_err(|err| format!("job match parse: {err}"))?;
@@ -300,18 +828,134 @@ async fn handle_tool(
             let _ = app.emit("analysis:completed", json!({ "match": saved }));
             Ok(json!({ "match": saved }))
         }
+        "semantic_similarity" => {
+            let text = arguments
+                .get("text")
+                .and_then(|v| v.as_str())
+                .ok_or("text is required")?;
+            let reference = match arguments.get("reference").and_then(|v| v.as_str()) {
+                Some(reference) => reference.to_string(),
+                None => embedding::profile_text(&load_settings(app)?.unwrap_or_default()),
+            };
+            let (content_hash, vector) = db.embedding_for(text)?;
+            let (_, reference_vector) = db.embedding_for(&reference)?;
+            Ok(json!({
+              "similarity": embedding::cosine(&vector, &reference_vector),
+              "contentHash": content_hash,
+              "model": embedding::MODEL_ID
+            }))
+        }
+        "find_similar_jobs" => {
+            let match_id = arguments.get("matchId").and_then(|v| v.as_str());
+            let limit = arguments
+                .get("limit")
+                .and_then(|v| v.as_u64())
+                .unwrap_or(10) as usize;
+            let reference = match (match_id, arguments.get("text").and_then(|v| v.as_str())) {
+                (Some(id), _) => db.match_embedding(id)?,
+                (None, Some(text)) => db.embedding_for(text)?.1,
+                (None, None) => {
+                    let settings = load_settings(app)?.unwrap_or_default();
+                    db.embedding_for(&embedding::profile_text(&settings))?.1
+                }
+            };
+            let similar = db.find_similar(&reference, match_id, limit)?;
+            Ok(json!({ "similar": similar }))
+        }
+        "rate_job_match" => {
+            let match_id = arguments
+                .get("matchId")
+                .and_then(|v| v.as_str())
+                .ok_or("matchId is required")?;
+            let rating: Rating =
+                serde_json::from_value(arguments.get("rating").cloned().unwrap_or(Value::Null))
+                    .map_err(|err| format!("rating parse: {err}"))?;
+            let profile = profile_argument(&arguments, app)?;
+            let report = feedback::rate_match(db, &profile, match_id, rating)?;
+            Ok(json!(report))
+        }
+        "get_scoring_weights" => {
+            let profile = profile_argument(&arguments, app)?;
+            Ok(json!(feedback::weights_report(db, &profile)?))
+        }
+        "reset_scoring_weights" => {
+            let profile = profile_argument(&arguments, app)?;
+            Ok(json!(feedback::reset_weights(db, &profile)?))
+        }
         "list_job_matches" => {
             let limit = arguments
                 .get("limit")
                 .and_then(|v| v.as_u64())
                 .unwrap_or(50) as usize;
-            let matches = db.list_matches(limit)?;
+            let sort: MatchSort = match arguments.get("sort") {
+                Some(value) => serde_json::from_value(value.clone())
+                    .map_err(|err| format!("sort parse: {err}"))?,
+                None => MatchSort::default(),
+            };
+            let matches = db.list_matches(&MatchQuery {
+                limit,
+                profile: arguments
+                    .get("profile")
+                    .and_then(|v| v.as_str())
+                    .map(str::to_string),
+                sort,
+                include_rejected: arguments
+                    .get("includeRejected")
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(false),
+            })?;
             Ok(json!({ "matches": matches }))
         }
+        "set_match_decision" => {
+            let match_id = arguments
+                .get("matchId")
+                .and_then(|v| v.as_str())
+                .ok_or("matchId is required")?;
+            let decision: Option<Decision> =
+                serde_json::from_value(arguments.get("decision").cloned().unwrap_or(Value::Null))
+                    .map_err(|err| format!("decision parse: {err}"))?;
+            db.set_decision(match_id, decision)?;
+            Ok(json!({ "ok": true }))
+        }
+        "rescore_matches" => {
+            let app = app.clone();
+            let db = db.clone();
+            let summary =
+                tauri::async_runtime::spawn_blocking(move || rescore::rescore_all(&app, &db))
+                    .await
+                    .map_err(|err| format!("rescore task: {err}"))??;
+            Ok(json!(summary))
+        }
+        "get_score_history" => {
+            let match_id = arguments
+                .get("matchId")
+                .and_then(|v| v.as_str())
+                .ok_or("matchId is required")?;
+            let profile = arguments.get("profile").and_then(|v| v.as_str());
+            Ok(json!({ "history": db.score_history(match_id, profile)? }))
+        }
+        "skill_gap_report" => {
+            let options: SkillReportOptions = serde_json::from_value(arguments.clone())
+                .map_err(|err| format!("options parse: {err}"))?;
+            let format: ReportFormat = match arguments.get("format") {
+                Some(value) => serde_json::from_value(value.clone())
+                    .map_err(|err| format!("format parse: {err}"))?,
+                None => ReportFormat::default(),
+            };
+            Ok(json!(skills::build_report(app, db, &options, format)?))
+        }
         "clear_job_matches" => {
             db.clear()?;
             Ok(json!({ "ok": true }))
         }
-        _ => Err(format!("unknown tool: {name}")),
+        _ => Err(format!("unknown tool: {name}").into()),
+    }
+}
+
+/// Reads the optional `profile` argument, defaulting to the active profile.
+fn profile_argument(arguments: &Value, app: &AppHandle) -> Result<String, String> {
+    match arguments.get("profile").and_then(|v| v.as_str()) {
+        Some(profile) => Ok(profile.to_string()),
+        None => Ok(load_profiles(app)?.active),
     }
 }
@@ -0,0 +1,137 @@
+use crate::db::{Db, ProfileScore, Rescore};
+use crate::embedding;
+use crate::feedback;
+use crate::rules::CompiledRule;
+use crate::scoring::{self, match_listing, settings_hash, ScoreWeights, SCORER_VERSION};
+use crate::settings::{load_profiles, JobSettings};
+use serde::Serialize;
+use serde_json::json;
+use tauri::{AppHandle, Emitter};
+
+/// Scores that move less than this are not counted as changed.
+const CHANGE_EPSILON: f64 = 0.5;
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RescoreSummary {
+    /// Matches with a stored listing that were rescored.
+    pub rescored: usize,
+    /// Matches whose active-profile score moved.
+    pub changed: usize,
+    /// Matches saved before listings were stored, which need a fresh analysis to rescore.
+    pub skipped: usize,
+}
+
+struct ProfileContext {
+    name: String,
+    settings: JobSettings,
+    rules: Vec<CompiledRule>,
+    weights: ScoreWeights,
+    settings_hash: String,
+    reference: Vec<f32>,
+}
+
+/// Re-runs the matcher over every stored listing with the current profiles and weights,
+/// emitting `rescore:progress` after each match and `rescore:completed` at the end.
+pub fn rescore_all(app: &AppHandle, db: &Db) -> Result<RescoreSummary, String> {
+    let profiles = load_profiles(app)?;
+    let mut contexts = Vec::new();
+    for profile in &profiles.profiles {
+        let weights = feedback::weights_report(db, &profile.name)?.effective;
+        contexts.push(ProfileContext {
+            name: profile.name.clone(),
+            settings: profile.settings.clone(),
+            rules: scoring::compile_rules(&profile.settings),
+            settings_hash: settings_hash(&profile.settings, &weights),
+            weights,
+            reference: db
+                .embedding_for(&embedding::profile_text(&profile.settings))?
+                .1,
+        });
+    }
+    // Stored scores are the active profile's, so without it nothing could be written.
+    let active = profiles
+        .active_profile()
+        .map(|profile| profile.name.clone())
+        .filter(|name| contexts.iter().any(|context| context.name == *name))
+        .ok_or_else(|| "rescore: the active profile could not be resolved".to_string())?;
+
+    let listings = db.stored_listings()?;
+    let total = listings.len();
+    let skipped = db.count_matches()?.saturating_sub(total);
+    let _ = app.emit(
+        "rescore:started",
+        json!({ "total": total, "skipped": skipped }),
+    );
+
+    let mut rescored = 0;
+    let mut changed = 0;
+    for (index, stored) in listings.iter().enumerate() {
+        let mut profile_scores = Vec::new();
+        let mut active_result = None;
+        for context in &contexts {
+            let similarity = stored
+                .embedding
+                .as_ref()
+                .map(|vector| embedding::cosine(vector, &context.reference));
+            let result = match_listing(
+                &stored.listing,
+                &context.settings,
+                &context.rules,
+                similarity,
+                &context.weights,
+            );
+            profile_scores.push(ProfileScore {
+                profile: context.name.clone(),
+                match_score: result.match_score,
+                score_breakdown: Some(result.breakdown.clone()),
+                rejection: result.rejection.clone(),
+                previous_score: None,
+                settings_hash: Some(context.settings_hash.clone()),
+                scorer_version: Some(SCORER_VERSION),
+            });
+            if context.name == active {
+                active_result = Some((result, context.settings_hash.clone()));
+            }
+        }
+        let Some((result, active_hash)) = active_result else {
+            continue;
+        };
+        let previous = db.match_score(&stored.match_id)?;
+        if previous.map_or(true, |score| {
+            (score - result.match_score).abs() >= CHANGE_EPSILON
+        }) {
+            changed += 1;
+        }
+        db.update_scores(
+            &stored.match_id,
+            &Rescore {
+                match_score: result.match_score,
+                score_breakdown: result.breakdown,
+                rejection: result.rejection,
+                settings_hash: Some(active_hash),
+                scorer_version: Some(SCORER_VERSION),
+                profile_scores,
+            },
+        )?;
+        rescored += 1;
+        let _ = app.emit(
+            "rescore:progress",
+            json!({
+              "matchId": stored.match_id,
+              "done": index + 1,
+              "total": total,
+              "previousScore": previous,
+              "matchScore": result.match_score
+            }),
+        );
+    }
+
+    let summary = RescoreSummary {
+        rescored,
+        changed,
+        skipped,
+    };
+    let _ = app.emit("rescore:completed", json!(summary));
+    Ok(summary)
+}
@@ -0,0 +1,187 @@
+use regex::Regex;
+
+/// Rules for one set of user agents.
+#[derive(Debug, Clone, Default)]
+struct Group {
+    /// Lowercase product tokens; `*` matches every crawler.
+    agents: Vec<String>,
+    rules: Vec<Rule>,
+    crawl_delay: Option<f64>,
+}
+
+#[derive(Debug, Clone)]
+struct Rule {
+    allow: bool,
+    pattern: String,
+    /// Compiled form of a pattern with `*` wildcards; plain patterns compare as strings.
+    wildcard: Option<Regex>,
+}
+
+impl Rule {
+    /// `None` when a wildcard pattern is too large to compile, which leaves the rule out.
+    fn new(allow: bool, pattern: &str) -> Option<Self> {
+        let (body, anchored) = match pattern.strip_suffix('$') {
+            Some(body) => (body, true),
+            None => (pattern, false),
+        };
+        let wildcard = if body.contains('*') {
+            let body = body
+                .split('*')
+                .map(regex::escape)
+                .collect::<Vec<_>>()
+                .join(".*");
+            let anchor = if anchored { "$" } else { "" };
+            Some(Regex::new(&format!("^{body}{anchor}")).ok()?)
+        } else {
+            None
+        };
+        Some(Self {
+            allow,
+            pattern: pattern.to_string(),
+            wildcard,
+        })
+    }
+
+    /// Matches a path per RFC 9309: a prefix match where `*` spans any characters and a
+    /// trailing `$` anchors the end.
+    fn matches(&self, path: &str) -> bool {
+        if let Some(wildcard) = &self.wildcard {
+            return wildcard.is_match(path);
+        }
+        match self.pattern.strip_suffix('$') {
+            Some(pattern) => path == pattern,
+            None => path.starts_with(&self.pattern),
+        }
+    }
+}
+
+/// A parsed robots.txt. An empty one allows everything.
+#[derive(Debug, Clone, Default)]
+pub struct Robots {
+    groups: Vec<Group>,
+    sitemaps: Vec<String>,
+}
+
+impl Robots {
+    /// Stands in for a robots.txt that could not be fetched.
+    pub fn disallow_all() -> Self {
+        Self {
+            groups: vec![Group {
+                agents: vec!["*".to_string()],
+                rules: Rule::new(false, "/").into_iter().collect(),
+                crawl_delay: None,
+            }],
+            sitemaps: Vec::new(),
+        }
+    }
+
+    pub fn parse(text: &str) -> Self {
+        let mut robots = Robots::default();
+        let mut current: Option<Group> = None;
+        for line in text.lines() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+            let value = value.trim();
+            match key.trim().to_lowercase().as_str() {
+                "user-agent" => {
+                    // Consecutive user-agent lines share one group; one after rules starts a new group.
+                    let started = current
+                        .as_ref()
+                        .is_some_and(|group| group.rules.is_empty() && group.crawl_delay.is_none());
+                    if !started {
+                        robots.groups.extend(current.take());
+                        current = Some(Group::default());
+                    }
+                    if let Some(group) = current.as_mut() {
+                        group.agents.push(value.to_lowercase());
+                    }
+                }
+                key @ ("allow" | "disallow") => {
+                    // An empty disallow allows everything, which is already the default.
+                    if let (Some(group), false) = (current.as_mut(), value.is_empty()) {
+                        group.rules.extend(Rule::new(key == "allow", value));
+                    }
+                }
+                "crawl-delay" => {
+                    if let Some(group) = current.as_mut() {
+                        group.crawl_delay = value.parse::<f64>().ok().filter(|delay| *delay >= 0.0);
+                    }
+                }
+                "sitemap" if !value.is_empty() => robots.sitemaps.push(value.to_string()),
+                _ => {}
+            }
+        }
+        robots.groups.extend(current);
+        robots
+    }
+
+    /// The group for `user_agent`, matched on its product token (`JobHunter` in
+    /// `JobHunter/1.0`), falling back to the `*` group.
+    fn group_for(&self, user_agent: &str) -> Option<&Group> {
+        let token = user_agent
+            .split(['/', ' '])
+            .next()
+            .unwrap_or_default()
+            .to_lowercase();
+        self.groups
+            .iter()
+            .find(|group| {
+                group
+                    .agents
+                    .iter()
+                    .any(|agent| agent != "*" && !token.is_empty() && *agent == token)
+            })
+            .or_else(|| {
+                self.groups
+                    .iter()
+                    .find(|group| group.agents.iter().any(|agent| agent == "*"))
+            })
+    }
+
+    /// Whether `path` (with its query) may be fetched. The longest matching rule wins, and
+    /// `Allow` wins a tie.
+    pub fn is_allowed(&self, user_agent: &str, path: &str) -> bool {
+        if path == "/robots.txt" {
+            return true;
+        }
+        let Some(group) = self.group_for(user_agent) else {
+            return true;
+        };
+        group
+            .rules
+            .iter()
+            .filter(|rule| rule.matches(path))
+            .max_by_key(|rule| (rule.pattern.len(), rule.allow))
+            .map_or(true, |rule| rule.allow)
+    }
+
+    /// Seconds to wait between requests, when the site asks for it.
+    pub fn crawl_delay(&self, user_agent: &str) -> Option<f64> {
+        self.group_for(user_agent)
+            .and_then(|group| group.crawl_delay)
+    }
+
+    pub fn sitemaps(&self) -> &[String] {
+        &self.sitemaps
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wildcards_and_anchors_follow_rfc_9309() {
+        let robots = Robots::parse(
+            "User-agent: *\nDisallow: /jobs/*.pdf$\nDisallow: /search\nAllow: /search/jobs$\n",
+        );
+        assert!(!robots.is_allowed("JobHunter/1.0", "/jobs/2024/offer.pdf"));
+        assert!(robots.is_allowed("JobHunter/1.0", "/jobs/2024/offer.pdf?page=2"));
+        assert!(!robots.is_allowed("JobHunter/1.0", "/search?q=rust"));
+        assert!(robots.is_allowed("JobHunter/1.0", "/search/jobs"));
+        assert!(!robots.is_allowed("JobHunter/1.0", "/search/jobs/1"));
+        assert!(robots.is_allowed("JobHunter/1.0", "/careers"));
+    }
+}
@@ -0,0 +1,795 @@
+//! A small, sandboxed rule language evaluated against extracted listing fields.
+//!
+//! Each rule is one statement:
+//! - a boolean expression, used as a hard filter the listing must satisfy, e.g.
+//!   `salary.min >= 150000 && arrangement == "remote" && !(company in blacklist)`
+//! - a score adjustment, e.g. `if title ~ "manager" then score -= 20`
+//!
+//! Rules can only read listing fields and settings lists; there are no calls, loops or I/O, and
+//! expression depth, rule length and regex size are bounded.
+//!
+//! A field the listing does not state is `null`, and anything computed from it is unknown rather
+//! than false: `&&`, `||` and `!` follow three-valued logic, a filter that comes out unknown does
+//! not reject the listing, and an adjustment that comes out unknown does not apply. Comparing
+//! with the `null` literal (`salary.min == null`) is always true or false.
+
+use regex::{Regex, RegexBuilder};
+use std::fmt;
+
+const MAX_RULE_LENGTH: usize = 1_000;
+const MAX_DEPTH: usize = 32;
+const MAX_REGEX_SIZE: usize = 64 * 1024;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RuleError {
+    /// 1-based character column where the problem was found.
+    pub column: usize,
+    pub message: String,
+}
+
+impl fmt::Display for RuleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "column {}: {}", self.column, self.message)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Null,
+    Bool(bool),
+    Number(f64),
+    Text(String),
+    List(Vec<Value>),
+}
+
+impl Value {
+    fn truthy(&self) -> bool {
+        match self {
+            Value::Null => false,
+            Value::Bool(flag) => *flag,
+            Value::Number(number) => *number != 0.0,
+            Value::Text(text) => !text.is_empty(),
+            Value::List(items) => !items.is_empty(),
+        }
+    }
+}
+
+impl From<Option<String>> for Value {
+    fn from(value: Option<String>) -> Self {
+        value.map(Value::Text).unwrap_or(Value::Null)
+    }
+}
+
+impl From<Option<i64>> for Value {
+    fn from(value: Option<i64>) -> Self {
+        value
+            .map(|number| Value::Number(number as f64))
+            .unwrap_or(Value::Null)
+    }
+}
+
+impl From<Option<bool>> for Value {
+    fn from(value: Option<bool>) -> Self {
+        value.map(Value::Bool).unwrap_or(Value::Null)
+    }
+}
+
+impl From<&[String]> for Value {
+    fn from(items: &[String]) -> Self {
+        Value::List(items.iter().cloned().map(Value::Text).collect())
+    }
+}
+
+/// Supplies field values to rules; unknown fields are rejected when the rule is parsed.
+pub trait RuleContext {
+    fn field(&self, path: &str) -> Option<Value>;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AdjustOp {
+    Add,
+    Subtract,
+    Set,
+}
+
+/// A rule parsed once, with its source kept for reporting.
+#[derive(Debug, Clone)]
+pub struct CompiledRule {
+    pub source: String,
+    pub rule: Rule,
+}
+
+#[derive(Debug, Clone)]
+pub enum Rule {
+    Filter(Expr),
+    Adjust {
+        condition: Expr,
+        op: AdjustOp,
+        amount: f64,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub enum Expr {
+    Literal(Value),
+    Field(String),
+    List(Vec<Expr>),
+    Not(Box<Expr>),
+    Negate(Box<Expr>),
+    Binary(Box<Expr>, BinaryOp, Box<Expr>),
+    /// `~` against a literal pattern, compiled when the rule is parsed.
+    Matches(Box<Expr>, Regex),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BinaryOp {
+    And,
+    Or,
+    Eq,
+    NotEq,
+    Lt,
+    LtEq,
+    Gt,
+    GtEq,
+    In,
+    Matches,
+}
+
+/// Outcome of running a rule against a listing.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RuleOutcome {
+    Passed,
+    Rejected,
+    Adjusted(AdjustOp, f64),
+}
+
+pub fn parse_rule(source: &str, fields: &[&str]) -> Result<Rule, RuleError> {
+    if source.chars().count() > MAX_RULE_LENGTH {
+        return Err(RuleError {
+            column: MAX_RULE_LENGTH,
+            message: format!("rule is longer than {MAX_RULE_LENGTH} characters"),
+        });
+    }
+    let tokens = tokenize(source)?;
+    let mut parser = Parser {
+        tokens,
+        position: 0,
+        depth: 0,
+        fields,
+    };
+    let rule = parser.rule()?;
+    parser.expect_end()?;
+    Ok(rule)
+}
+
+/// Parses every non-empty rule once, for reuse across listings. Invalid rules are logged and
+/// skipped, so one saved before validation existed cannot block scoring.
+pub fn compile_rules(sources: &[String], fields: &[&str]) -> Vec<CompiledRule> {
+    sources
+        .iter()
+        .filter(|source| !source.trim().is_empty())
+        .filter_map(|source| match parse_rule(source, fields) {
+            Ok(rule) => Some(CompiledRule {
+                source: source.clone(),
+                rule,
+            }),
+            Err(err) => {
+                log::warn!("skipping invalid rule '{source}': {err}");
+                None
+            }
+        })
+        .collect()
+}
+
+/// Parses every rule, reporting the first error as `rules[index] column N: message`.
+pub fn validate_rules(rules: &[String], fields: &[&str]) -> Result<(), String> {
+    for (index, rule) in rules.iter().enumerate() {
+        if rule.trim().is_empty() {
+            continue;
+        }
+        parse_rule(rule, fields).map_err(|err| format!("rules[{index}] {err}"))?;
+    }
+    Ok(())
+}
+
+pub fn evaluate(rule: &Rule, context: &dyn RuleContext) -> RuleOutcome {
+    match rule {
+        Rule::Filter(expr) => match truth(&eval(expr, context)) {
+            Some(false) => RuleOutcome::Rejected,
+            Some(true) | None => RuleOutcome::Passed,
+        },
+        Rule::Adjust {
+            condition,
+            op,
+            amount,
+        } => {
+            if eval(condition, context).truthy() {
+                RuleOutcome::Adjusted(*op, *amount)
+            } else {
+                RuleOutcome::Passed
+            }
+        }
+    }
+}
+
+/// Truth value of a result, `None` when it is unknown because a field was missing.
+fn truth(value: &Value) -> Option<bool> {
+    match value {
+        Value::Null => None,
+        value => Some(value.truthy()),
+    }
+}
+
+fn from_truth(truth: Option<bool>) -> Value {
+    truth.map(Value::Bool).unwrap_or(Value::Null)
+}
+
+fn eval(expr: &Expr, context: &dyn RuleContext) -> Value {
+    match expr {
+        Expr::Literal(value) => value.clone(),
+        Expr::Field(path) => context.field(path).unwrap_or(Value::Null),
+        Expr::List(items) => Value::List(items.iter().map(|item| eval(item, context)).collect()),
+        Expr::Not(inner) => from_truth(truth(&eval(inner, context)).map(|value| !value)),
+        Expr::Negate(inner) => match eval(inner, context) {
+            Value::Number(number) => Value::Number(-number),
+            _ => Value::Null,
+        },
+        Expr::Matches(inner, regex) => match eval(inner, context) {
+            Value::Text(text) => Value::Bool(regex.is_match(&text)),
+            Value::Null => Value::Null,
+            _ => Value::Bool(false),
+        },
+        Expr::Binary(left, op, right) => {
+            let left_value = eval(left, context);
+            let right_value = eval(right, context);
+            match op {
+                BinaryOp::And => match (truth(&left_value), truth(&right_value)) {
+                    (Some(false), _) | (_, Some(false)) => Value::Bool(false),
+                    (Some(true), Some(true)) => Value::Bool(true),
+                    _ => Value::Null,
+                },
+                BinaryOp::Or => match (truth(&left_value), truth(&right_value)) {
+                    (Some(true), _) | (_, Some(true)) => Value::Bool(true),
+                    (Some(false), Some(false)) => Value::Bool(false),
+                    _ => Value::Null,
+                },
+                _ => {
+                    let null_literal = |expr: &Expr| matches!(expr, Expr::Literal(Value::Null));
+                    let explicit_null = matches!(op, BinaryOp::Eq | BinaryOp::NotEq)
+                        && (null_literal(left) || null_literal(right));
+                    if !explicit_null && (left_value == Value::Null || right_value == Value::Null) {
+                        Value::Null
+                    } else {
+                        Value::Bool(compare(&left_value, *op, &right_value))
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Compares two known values; `eval` has already turned comparisons with a missing value into
+/// unknown, except equality with the `null` literal.
+fn compare(left: &Value, op: BinaryOp, right: &Value) -> bool {
+    match op {
+        BinaryOp::Eq => values_equal(left, right),
+        BinaryOp::NotEq => !values_equal(left, right),
+        BinaryOp::Lt | BinaryOp::LtEq | BinaryOp::Gt | BinaryOp::GtEq => {
+            let ordering = match (left, right) {
+                (Value::Number(a), Value::Number(b)) => a.partial_cmp(b),
+                (Value::Text(a), Value::Text(b)) => Some(a.to_lowercase().cmp(&b.to_lowercase())),
+                _ => None,
+            };
+            match ordering {
+                Some(ordering) => match op {
+                    BinaryOp::Lt => ordering.is_lt(),
+                    BinaryOp::LtEq => ordering.is_le(),
+                    BinaryOp::Gt => ordering.is_gt(),
+                    _ => ordering.is_ge(),
+                },
+                None => false,
+            }
+        }
+        BinaryOp::In => match (left, right) {
+            (Value::Text(needle), Value::List(items)) => items.iter().any(|item| match item {
+                Value::Text(item) => contains_phrase(needle, item),
+                _ => false,
+            }),
+            (Value::Text(needle), Value::Text(haystack)) => {
+                haystack.to_lowercase().contains(&needle.to_lowercase())
+            }
+            (Value::Null, _) => false,
+            (value, Value::List(items)) => items.iter().any(|item| values_equal(value, item)),
+            _ => false,
+        },
+        // Only reached when the pattern is not a literal, such as `title ~ text`.
+        BinaryOp::Matches => match (left, right) {
+            (Value::Text(text), Value::Text(pattern)) => build_pattern(pattern)
+                .map(|regex| regex.is_match(text))
+                .unwrap_or(false),
+            _ => false,
+        },
+        BinaryOp::And | BinaryOp::Or => false,
+    }
+}
+
+fn build_pattern(pattern: &str) -> Result<Regex, regex::Error> {
+    RegexBuilder::new(pattern)
+        .case_insensitive(true)
+        .size_limit(MAX_REGEX_SIZE)
+        .build()
+}
+
+fn values_equal(left: &Value, right: &Value) -> bool {
+    match (left, right) {
+        (Value::Text(a), Value::Text(b)) => a.to_lowercase() == b.to_lowercase(),
+        _ => left == right,
+    }
+}
+
+/// A text is "in" a list when it equals an item or contains it as a whole word or phrase, so
+/// `"Acme Corp" in ["acme"]` holds.
+fn contains_phrase(text: &str, item: &str) -> bool {
+    let text = text.to_lowercase();
+    let item = item.trim().to_lowercase();
+    if item.is_empty() {
+        return false;
+    }
+    text.match_indices(&item).any(|(start, _)| {
+        let end = start + item.len();
+        let before = text[..start].chars().next_back();
+        let after = text[end..].chars().next();
+        !before.is_some_and(char::is_alphanumeric) && !after.is_some_and(char::is_alphanumeric)
+    })
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f64),
+    Text(String),
+    Ident(String),
+    Symbol(&'static str),
+}
+
+struct Spanned {
+    token: Token,
+    column: usize,
+}
+
+fn tokenize(source: &str) -> Result<Vec<Spanned>, RuleError> {
+    const SYMBOLS: [&str; 19] = [
+        "&&", "||", "==", "!=", "<=", ">=", "+=", "-=", "<", ">", "!", "~", "=", "(", ")", "[",
+        "]", ",", "-",
+    ];
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = Vec::new();
+    let mut index = 0;
+    while index < chars.len() {
+        let column = index + 1;
+        let ch = chars[index];
+        if ch.is_whitespace() {
+            index += 1;
+            continue;
+        }
+        if ch.is_ascii_digit() {
+            let start = index;
+            while index < chars.len()
+                && (chars[index].is_ascii_digit() || chars[index] == '.' || chars[index] == '_')
+            {
+                index += 1;
+            }
+            let literal: String = chars[start..index].iter().filter(|c| **c != '_').collect();
+            let number = literal.parse::<f64>().map_err(|_| RuleError {
+                column,
+                message: format!("invalid number '{literal}'"),
+            })?;
+            tokens.push(Spanned {
+                token: Token::Number(number),
+                column,
+            });
+            continue;
+        }
+        if ch == '"' || ch == '\'' {
+            let quote = ch;
+            index += 1;
+            let mut text = String::new();
+            loop {
+                match chars.get(index) {
+                    None => {
+                        return Err(RuleError {
+                            column,
+                            message: "unterminated string".to_string(),
+                        })
+                    }
+                    Some('\\') if chars.get(index + 1).is_some() => {
+                        text.push(chars[index + 1]);
+                        index += 2;
+                    }
+                    Some(c) if *c == quote => {
+                        index += 1;
+                        break;
+                    }
+                    Some(c) => {
+                        text.push(*c);
+                        index += 1;
+                    }
+                }
+            }
+            tokens.push(Spanned {
+                token: Token::Text(text),
+                column,
+            });
+            continue;
+        }
+        if ch.is_alphabetic() || ch == '_' {
+            let start = index;
+            while index < chars.len()
+                && (chars[index].is_alphanumeric() || chars[index] == '_' || chars[index] == '.')
+            {
+                index += 1;
+            }
+            tokens.push(Spanned {
+                token: Token::Ident(chars[start..index].iter().collect()),
+                column,
+            });
+            continue;
+        }
+        let rest: String = chars[index..chars.len().min(index + 2)].iter().collect();
+        match SYMBOLS
+            .iter()
+            .copied()
+            .find(|symbol| rest.starts_with(symbol))
+        {
+            Some(symbol) => {
+                tokens.push(Spanned {
+                    token: Token::Symbol(symbol),
+                    column,
+                });
+                index += symbol.len();
+            }
+            None => {
+                return Err(RuleError {
+                    column,
+                    message: format!("unexpected character '{ch}'"),
+                })
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser<'a> {
+    tokens: Vec<Spanned>,
+    position: usize,
+    depth: usize,
+    fields: &'a [&'a str],
+}
+
+impl Parser<'_> {
+    fn rule(&mut self) -> Result<Rule, RuleError> {
+        if !self.eat_keyword("if") {
+            return Ok(Rule::Filter(self.expr()?));
+        }
+        let condition = self.expr()?;
+        if !self.eat_keyword("then") {
+            return Err(self.error("expected 'then'"));
+        }
+        if !self.eat_keyword("score") {
+            return Err(self.error("expected 'score' after 'then'"));
+        }
+        let op = if self.eat_symbol("+=") {
+            AdjustOp::Add
+        } else if self.eat_symbol("-=") {
+            AdjustOp::Subtract
+        } else if self.eat_symbol("=") {
+            AdjustOp::Set
+        } else {
+            return Err(self.error("expected '+=', '-=' or '='"));
+        };
+        let negative = self.eat_symbol("-");
+        match self.peek().cloned() {
+            Some(Token::Number(amount)) => {
+                self.position += 1;
+                Ok(Rule::Adjust {
+                    condition,
+                    op,
+                    amount: if negative { -amount } else { amount },
+                })
+            }
+            _ => Err(self.error("expected a number")),
+        }
+    }
+
+    fn expr(&mut self) -> Result<Expr, RuleError> {
+        self.depth += 1;
+        if self.depth > MAX_DEPTH {
+            return Err(self.error("expression is nested too deeply"));
+        }
+        let result = self.or();
+        self.depth -= 1;
+        result
+    }
+
+    fn or(&mut self) -> Result<Expr, RuleError> {
+        let mut left = self.and()?;
+        while self.eat_symbol("||") || self.eat_keyword("or") {
+            let right = self.and()?;
+            left = Expr::Binary(Box::new(left), BinaryOp::Or, Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn and(&mut self) -> Result<Expr, RuleError> {
+        let mut left = self.comparison()?;
+        while self.eat_symbol("&&") || self.eat_keyword("and") {
+            let right = self.comparison()?;
+            left = Expr::Binary(Box::new(left), BinaryOp::And, Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn comparison(&mut self) -> Result<Expr, RuleError> {
+        let left = self.unary()?;
+        let op = [
+            ("==", BinaryOp::Eq),
+            ("!=", BinaryOp::NotEq),
+            ("<=", BinaryOp::LtEq),
+            (">=", BinaryOp::GtEq),
+            ("<", BinaryOp::Lt),
+            (">", BinaryOp::Gt),
+            ("~", BinaryOp::Matches),
+        ]
+        .into_iter()
+        .find(|(symbol, _)| self.eat_symbol(symbol))
+        .map(|(_, op)| op)
+        .or_else(|| self.eat_keyword("in").then_some(BinaryOp::In));
+        match op {
+            Some(op) => {
+                let column = self.column();
+                let right = self.unary()?;
+                if let (BinaryOp::Matches, Expr::Literal(Value::Text(pattern))) = (op, &right) {
+                    let regex = build_pattern(pattern).map_err(|err| RuleError {
+                        column,
+                        message: format!(
+                            "invalid pattern: {}",
+                            err.to_string().lines().last().unwrap_or_default()
+                        ),
+                    })?;
+                    return Ok(Expr::Matches(Box::new(left), regex));
+                }
+                Ok(Expr::Binary(Box::new(left), op, Box::new(right)))
+            }
+            None => Ok(left),
+        }
+    }
+
+    fn unary(&mut self) -> Result<Expr, RuleError> {
+        let not = self.eat_symbol("!") || self.eat_keyword("not");
+        if !not && !self.eat_symbol("-") {
+            return self.primary();
+        }
+        // Prefix operators nest without going through `expr`, so they count toward the depth too.
+        self.depth += 1;
+        if self.depth > MAX_DEPTH {
+            return Err(self.error("expression is nested too deeply"));
+        }
+        let operand = self.unary();
+        self.depth -= 1;
+        let operand = Box::new(operand?);
+        Ok(if not {
+            Expr::Not(operand)
+        } else {
+            Expr::Negate(operand)
+        })
+    }
+
+    fn primary(&mut self) -> Result<Expr, RuleError> {
+        let column = self.column();
+        let token = match self.peek().cloned() {
+            Some(token) => token,
+            None => return Err(self.error("unexpected end of rule")),
+        };
+        self.position += 1;
+        match token {
+            Token::Number(number) => Ok(Expr::Literal(Value::Number(number))),
+            Token::Text(text) => Ok(Expr::Literal(Value::Text(text))),
+            Token::Ident(name) => match name.as_str() {
+                "true" => Ok(Expr::Literal(Value::Bool(true))),
+                "false" => Ok(Expr::Literal(Value::Bool(false))),
+                "null" => Ok(Expr::Literal(Value::Null)),
+                _ if self.fields.contains(&name.as_str()) => Ok(Expr::Field(name)),
+                _ => Err(RuleError {
+                    column,
+                    message: format!("unknown field '{name}'"),
+                }),
+            },
+            Token::Symbol("(") => {
+                let inner = self.expr()?;
+                if !self.eat_symbol(")") {
+                    return Err(self.error("expected ')'"));
+                }
+                Ok(inner)
+            }
+            Token::Symbol("[") => {
+                let mut items = Vec::new();
+                if !self.eat_symbol("]") {
+                    loop {
+                        items.push(self.expr()?);
+                        if self.eat_symbol("]") {
+                            break;
+                        }
+                        if !self.eat_symbol(",") {
+                            return Err(self.error("expected ',' or ']'"));
+                        }
+                    }
+                }
+                Ok(Expr::List(items))
+            }
+            Token::Symbol(symbol) => Err(RuleError {
+                column,
+                message: format!("unexpected '{symbol}'"),
+            }),
+        }
+    }
+
+    fn expect_end(&self) -> Result<(), RuleError> {
+        if self.position < self.tokens.len() {
+            return Err(self.error("unexpected input after end of rule"));
+        }
+        Ok(())
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position).map(|spanned| &spanned.token)
+    }
+
+    fn eat_symbol(&mut self, symbol: &str) -> bool {
+        if matches!(self.peek(), Some(Token::Symbol(found)) if *found == symbol) {
+            self.position += 1;
+            return true;
+        }
+        false
+    }
+
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        if matches!(self.peek(), Some(Token::Ident(found)) if found == keyword) {
+            self.position += 1;
+            return true;
+        }
+        false
+    }
+
+    fn column(&self) -> usize {
+        match self.tokens.get(self.position) {
+            Some(spanned) => spanned.column,
+            None => self
+                .tokens
+                .last()
+                .map(|spanned| spanned.column + 1)
+                .unwrap_or(1),
+        }
+    }
+
+    fn error(&self, message: &str) -> RuleError {
+        RuleError {
+            column: self.column(),
+            message: message.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    const FIELDS: &[&str] = &["salary.min", "arrangement", "company", "blacklist", "title"];
+
+    struct Listing(HashMap<&'static str, Value>);
+
+    impl RuleContext for Listing {
+        fn field(&self, path: &str) -> Option<Value> {
+            self.0.get(path).cloned()
+        }
+    }
+
+    fn listing(fields: &[(&'static str, Value)]) -> Listing {
+        Listing(fields.iter().cloned().collect())
+    }
+
+    fn text(value: &str) -> Value {
+        Value::Text(value.to_string())
+    }
+
+    fn run(source: &str, context: &Listing) -> RuleOutcome {
+        evaluate(&parse_rule(source, FIELDS).unwrap(), context)
+    }
+
+    const FILTER: &str =
+        r#"salary.min >= 150000 && arrangement == "remote" && !(company in blacklist)"#;
+
+    #[test]
+    fn filter_from_the_docs() {
+        let blacklist = Value::List(vec![text("Initech")]);
+        let good = listing(&[
+            ("salary.min", Value::Number(160_000.0)),
+            ("arrangement", text("Remote")),
+            ("company", text("Acme")),
+            ("blacklist", blacklist.clone()),
+        ]);
+        assert_eq!(run(FILTER, &good), RuleOutcome::Passed);
+
+        let blacklisted = listing(&[
+            ("salary.min", Value::Number(160_000.0)),
+            ("arrangement", text("remote")),
+            ("company", text("Initech")),
+            ("blacklist", blacklist.clone()),
+        ]);
+        assert_eq!(run(FILTER, &blacklisted), RuleOutcome::Rejected);
+
+        let underpaid = listing(&[
+            ("salary.min", Value::Number(120_000.0)),
+            ("arrangement", text("remote")),
+            ("company", text("Acme")),
+            ("blacklist", blacklist),
+        ]);
+        assert_eq!(run(FILTER, &underpaid), RuleOutcome::Rejected);
+    }
+
+    #[test]
+    fn adjustment_from_the_docs() {
+        let rule = r#"if title ~ "manager" then score -= 20"#;
+        let manager = listing(&[("title", text("Engineering Manager"))]);
+        assert_eq!(
+            run(rule, &manager),
+            RuleOutcome::Adjusted(AdjustOp::Subtract, 20.0)
+        );
+        let engineer = listing(&[("title", text("Backend Engineer"))]);
+        assert_eq!(run(rule, &engineer), RuleOutcome::Passed);
+    }
+
+    #[test]
+    fn missing_salary_is_unknown_not_false() {
+        let no_salary = listing(&[("arrangement", text("remote")), ("company", text("Acme"))]);
+        // Unknown does not reject, but a known false still does.
+        assert_eq!(run("salary.min >= 150000", &no_salary), RuleOutcome::Passed);
+        assert_eq!(
+            run(
+                r#"salary.min >= 150000 && arrangement == "onsite""#,
+                &no_salary
+            ),
+            RuleOutcome::Rejected
+        );
+        assert_eq!(
+            run("salary.min != null && salary.min >= 150000", &no_salary),
+            RuleOutcome::Rejected
+        );
+        assert_eq!(
+            run("if salary.min < 100000 then score -= 10", &no_salary),
+            RuleOutcome::Passed
+        );
+    }
+
+    #[test]
+    fn errors_report_the_column() {
+        let err = parse_rule("salary.max >= 1", FIELDS).unwrap_err();
+        assert_eq!(err.column, 1);
+        assert_eq!(err.message, "unknown field 'salary.max'");
+
+        let err = parse_rule(r#"title == "lead"#, FIELDS).unwrap_err();
+        assert_eq!(err.column, 10);
+        assert_eq!(err.message, "unterminated string");
+
+        let err = parse_rule(r#"title ~ "(senior""#, FIELDS).unwrap_err();
+        assert_eq!(err.column, 9);
+        assert!(err.message.starts_with("invalid pattern"));
+    }
+
+    #[test]
+    fn prefix_operators_are_depth_limited() {
+        let err = parse_rule(&format!("{}true", "!".repeat(900)), FIELDS).unwrap_err();
+        assert_eq!(err.message, "expression is nested too deeply");
+        assert!(parse_rule("!!true", FIELDS).is_ok());
+        assert!(parse_rule("salary.min > --5", FIELDS).is_ok());
+    }
+}
@@ -0,0 +1,675 @@
+use crate::company::ResolvedCompany;
+use crate::embedding;
+use crate::geo;
+use crate::rules::{self, AdjustOp, CompiledRule, RuleContext, RuleOutcome, Value};
+use crate::settings::JobSettings;
+use crate::timezone::{self, TimezoneRequirement};
+use crate::titles;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Score at which a match is treated as neither good nor bad when learning weights.
+const DECISION_THRESHOLD: f64 = 50.0;
+/// Width of the logistic curve in score points.
+const DECISION_SCALE: f64 = 10.0;
+const TRAINING_EPOCHS: usize = 400;
+const LEARNING_RATE: f64 = 20.0;
+/// Pulls learned weights back toward the defaults so a handful of ratings cannot swing them far.
+const REGULARIZATION: f64 = 0.002;
+const MAX_WEIGHT: f64 = 250.0;
+
+/// Bumped whenever `match_listing` changes in a way that moves scores, so stored scores from
+/// older versions can be told apart and rescored.
+pub const SCORER_VERSION: u32 = 2;
+
+pub type ScoreWeights = BTreeMap<String, f64>;
+/// Feature values of a rated match and whether it was rated good.
+pub type FeedbackSample = (BTreeMap<String, f64>, bool);
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ExtractedListing {
+    pub title: Option<String>,
+    pub company: Option<String>,
+    pub location: Option<String>,
+    pub text: String,
+    pub raw_excerpt: Option<String>,
+    /// `remote`, `hybrid` or `onsite`.
+    #[serde(default)]
+    pub arrangement: Option<String>,
+    #[serde(default)]
+    pub employment_type: Option<String>,
+    #[serde(default)]
+    pub salary_min: Option<i64>,
+    #[serde(default)]
+    pub salary_max: Option<i64>,
+    #[serde(default)]
+    pub salary_currency: Option<String>,
+    /// `Some(false)` when the listing says it cannot sponsor work visas.
+    #[serde(default)]
+    pub visa_sponsorship: Option<bool>,
+    #[serde(default)]
+    pub timezone: Option<TimezoneRequirement>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ScoreFactor {
+    pub name: String,
+    /// Normalized feature value, usually in `0.0..=1.0`.
+    pub value: f64,
+    pub weight: f64,
+    pub points: f64,
+    /// Human-readable explanation of the value, when the raw number is not self-explanatory.
+    #[serde(default)]
+    pub detail: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ScoreAdjustment {
+    pub source: String,
+    pub reason: String,
+    pub points: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ScoreBreakdown {
+    pub factors: Vec<ScoreFactor>,
+    /// Fixed point changes applied after the weighted factors, such as rule adjustments.
+    #[serde(default)]
+    pub adjustments: Vec<ScoreAdjustment>,
+}
+
+impl ScoreBreakdown {
+    pub fn features(&self) -> BTreeMap<String, f64> {
+        self.factors
+            .iter()
+            .map(|factor| (factor.name.clone(), factor.value))
+            .collect()
+    }
+}
+
+/// Why a listing was rejected by a hard filter.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Rejection {
+    /// Filter that fired: `blacklist`, `agency`, `location`, `employment_type`, `sponsorship`,
+    /// `salary` or `rule`.
+    pub rule: String,
+    pub reason: String,
+}
+
+pub struct MatchResult {
+    pub summary: String,
+    pub match_score: f64,
+    pub breakdown: ScoreBreakdown,
+    /// The first hard filter the listing failed, if any.
+    pub rejection: Option<Rejection>,
+}
+
+/// Fields rules may reference.
+pub const RULE_FIELDS: &[&str] = &[
+    "title",
+    "role_family",
+    "company",
+    "location",
+    "arrangement",
+    "employment_type",
+    "salary.min",
+    "salary.max",
+    "salary.currency",
+    "visa_sponsorship",
+    "text",
+    "score",
+    "similarity",
+    "keywords_matched",
+    "blacklist",
+    "keywords",
+    "preferred_titles",
+    "locations",
+];
+
+struct ListingContext<'a> {
+    extracted: &'a ExtractedListing,
+    settings: &'a JobSettings,
+    similarity: Option<f64>,
+    keywords_matched: usize,
+    score: f64,
+}
+
+impl RuleContext for ListingContext<'_> {
+    fn field(&self, path: &str) -> Option<Value> {
+        let listing = self.extracted;
+        let value = match path {
+            "title" => listing.title.clone().into(),
+            "role_family" => listing
+                .title
+                .as_deref()
+                .and_then(titles::role_family)
+                .map(str::to_string)
+                .into(),
+            "company" => listing.company.clone().into(),
+            "location" => listing.location.clone().into(),
+            "arrangement" => listing.arrangement.clone().into(),
+            "employment_type" => listing.employment_type.clone().into(),
+            "salary.min" => listing.salary_min.into(),
+            "salary.max" => listing.salary_max.into(),
+            "salary.currency" => listing.salary_currency.clone().into(),
+            "visa_sponsorship" => listing.visa_sponsorship.into(),
+            "text" => Value::Text(listing.text.clone()),
+            "score" => Value::Number(self.score),
+            "similarity" => self.similarity.map(Value::Number).unwrap_or(Value::Null),
+            "keywords_matched" => Value::Number(self.keywords_matched as f64),
+            "blacklist" => self.settings.company_blacklist.as_slice().into(),
+            "keywords" => self.settings.keywords.as_slice().into(),
+            "preferred_titles" => self.settings.preferred_titles.as_slice().into(),
+            "locations" => self.settings.locations.as_slice().into(),
+            _ => return None,
+        };
+        Some(value)
+    }
+}
+
+pub fn default_weights() -> ScoreWeights {
+    [
+        ("keywords", 100.0),
+        ("title", 10.0),
+        ("location", 6.0),
+        ("remote", 8.0),
+        ("blacklist", -15.0),
+        ("favorite", 10.0),
+        ("tier", 15.0),
+        ("agency", -5.0),
+        ("semantic", 25.0),
+        ("timezone", 10.0),
+        ("commute", 10.0),
+    ]
+    .into_iter()
+    .map(|(name, weight)| (name.to_string(), weight))
+    .collect()
+}
+
+/// Fingerprint of the settings and weights a score was computed with.
+pub fn settings_hash(settings: &JobSettings, weights: &ScoreWeights) -> String {
+    let snapshot = serde_json::to_string(&(settings, weights)).unwrap_or_default();
+    embedding::content_hash(&snapshot)
+}
+
+/// Parses a profile's rules for `match_listing`; do it once per settings load, not per listing.
+pub fn compile_rules(settings: &JobSettings) -> Vec<CompiledRule> {
+    rules::compile_rules(&settings.rules, RULE_FIELDS)
+}
+
+/// Scores a listing. `rules` must come from `compile_rules` for the same `settings`.
+pub fn match_listing(
+    extracted: &ExtractedListing,
+    settings: &JobSettings,
+    rules: &[CompiledRule],
+    similarity: Option<f64>,
+    weights: &ScoreWeights,
+) -> MatchResult {
+    let text_lower = extracted.text.to_lowercase();
+    let mut features: Vec<(&str, f64)> = Vec::new();
+
+    let hits = settings
+        .keywords
+        .iter()
+        .filter(|keyword| text_lower.contains(&keyword.to_lowercase()))
+        .count();
+    let coverage = if settings.keywords.is_empty() {
+        0.5
+    } else {
+        hits as f64 / settings.keywords.len() as f64
+    };
+    features.push(("keywords", coverage));
+
+    let mut details: BTreeMap<&str, String> = BTreeMap::new();
+    let title_match = extracted
+        .title
+        .as_deref()
+        .and_then(|title| titles::best_match(title, &settings.preferred_titles));
+    features.push((
+        "title",
+        title_match
+            .as_ref()
+            .map(|found| found.similarity)
+            .unwrap_or(0.0),
+    ));
+    if let Some(found) = &title_match {
+        details.insert(
+            "title",
+            format!("{:.2} similar to '{}'", found.similarity, found.preferred),
+        );
+    }
+
+    let location_hit = extracted.location.as_ref().is_some_and(|location_value| {
+        let location_lower = location_value.to_lowercase();
+        settings
+            .locations
+            .iter()
+            .any(|value| !value.is_empty() && location_lower.contains(&value.to_lowercase()))
+    });
+    features.push(("location", indicator(location_hit)));
+
+    features.push((
+        "remote",
+        indicator(settings.remote_only && text_lower.contains("remote")),
+    ));
+
+    let preferences = &settings.companies;
+    let company = extracted
+        .company
+        .as_deref()
+        .map(|name| preferences.resolve(name, &extracted.text));
+    let blacklist_entry = company
+        .as_ref()
+        .and_then(|company| preferences.find_in(company, &settings.company_blacklist));
+    features.push(("blacklist", indicator(blacklist_entry.is_some())));
+    if let Some(entry) = blacklist_entry {
+        details.insert("blacklist", format!("matches '{entry}'"));
+    }
+
+    let favorite = company
+        .as_ref()
+        .and_then(|company| preferences.find_in(company, &preferences.favorites));
+    features.push(("favorite", indicator(favorite.is_some())));
+    if let Some(entry) = favorite {
+        details.insert("favorite", format!("matches '{entry}'"));
+    }
+
+    let tier = company
+        .as_ref()
+        .and_then(|company| preferences.tier(company));
+    features.push(("tier", tier.map(|tier| tier.value()).unwrap_or(0.0)));
+    if let Some(tier) = tier {
+        details.insert("tier", tier.as_str().to_string());
+    }
+
+    let agency = company.as_ref().filter(|company| company.agency);
+    features.push(("agency", indicator(agency.is_some())));
+    if let Some(agency) = agency {
+        details.insert(
+            "agency",
+            match &agency.client {
+                Some(client) => format!("{} recruiting for {client}", agency.posted),
+                None => format!("{} recruiting for an unnamed client", agency.posted),
+            },
+        );
+    }
+
+    if let Some(similarity) = similarity {
+        features.push(("semantic", similarity.clamp(0.0, 1.0)));
+    }
+
+    if let (Some(requirement), Some(home_offset)) = (
+        &extracted.timezone,
+        settings
+            .home_timezone
+            .as_deref()
+            .and_then(timezone::utc_offset_hours),
+    ) {
+        let hours = &settings.working_hours;
+        let overlap = timezone::overlap_hours(requirement, home_offset, hours.start, hours.end);
+        let needed = requirement
+            .overlap_hours
+            .unwrap_or_else(|| {
+                timezone::window_length(requirement.start_hour, requirement.end_hour)
+            })
+            .min(timezone::window_length(hours.start, hours.end));
+        let value = if needed > 0.0 {
+            (overlap / needed).min(1.0)
+        } else {
+            0.0
+        };
+        features.push(("timezone", value));
+        details.insert(
+            "timezone",
+            format!(
+                "{overlap:.1}h overlap with {} (needs {needed:.1}h)",
+                requirement.label
+            ),
+        );
+    }
+
+    let commutes = matches!(extracted.arrangement.as_deref(), Some("hybrid" | "onsite"));
+    let home = settings
+        .commute
+        .home_location
+        .as_deref()
+        .filter(|_| commutes)
+        .and_then(geo::resolve);
+    let office = extracted
+        .location
+        .as_deref()
+        .filter(|_| commutes)
+        .and_then(geo::resolve);
+    if let (Some(home), Some(office)) = (home, office) {
+        let distance = geo::distance_km(&home, &office);
+        // Full marks at home, half at the maximum radius, nothing beyond twice the radius.
+        let ratio = distance / settings.commute.max_km.max(1.0);
+        let value = (1.0 - 0.5 * ratio).max(0.0);
+        features.push(("commute", value));
+        details.insert(
+            "commute",
+            format!(
+                "{distance:.0} km from {} to {}",
+                home.label(),
+                office.label()
+            ),
+        );
+    }
+
+    let defaults = default_weights();
+    let factors: Vec<ScoreFactor> = features
+        .into_iter()
+        .map(|(name, value)| {
+            let weight = weights
+                .get(name)
+                .or_else(|| defaults.get(name))
+                .copied()
+                .unwrap_or(0.0);
+            ScoreFactor {
+                name: name.to_string(),
+                value,
+                weight,
+                points: value * weight,
+                detail: details.get(name).cloned(),
+            }
+        })
+        .collect();
+    let mut score = factors.iter().map(|factor| factor.points).sum::<f64>();
+
+    let mut adjustments = Vec::new();
+    let mut rejection = check_filters(extracted, settings, company.as_ref(), blacklist_entry);
+    for CompiledRule { source, rule } in rules {
+        let context = ListingContext {
+            extracted,
+            settings,
+            similarity,
+            keywords_matched: hits,
+            score,
+        };
+        match rules::evaluate(rule, &context) {
+            RuleOutcome::Passed => {}
+            RuleOutcome::Rejected => {
+                if rejection.is_none() {
+                    rejection = Some(Rejection {
+                        rule: "rule".to_string(),
+                        reason: format!("failed rule: {source}"),
+                    });
+                }
+            }
+            RuleOutcome::Adjusted(op, amount) => {
+                let points = match op {
+                    AdjustOp::Add => amount,
+                    AdjustOp::Subtract => -amount,
+                    AdjustOp::Set => amount - score,
+                };
+                score += points;
+                adjustments.push(ScoreAdjustment {
+                    source: "rule".to_string(),
+                    reason: source.clone(),
+                    points,
+                });
+            }
+        }
+    }
+    let score = score.clamp(0.0, 100.0);
+
+    let mut summary = format!(
+        "Matched {:.0}% of keywords. Remote preference: {}. Title signal: {}.",
+        score,
+        if settings.remote_only { "on" } else { "off" },
+        extracted
+            .title
+            .clone()
+            .unwrap_or_else(|| "unknown".to_string())
+    );
+    if let Some(similarity) = similarity {
+        summary.push_str(&format!(" Profile similarity: {similarity:.2}."));
+    }
+    if let Some(rejection) = &rejection {
+        summary.push_str(&format!(" Rejected: {}.", rejection.reason));
+    }
+
+    MatchResult {
+        summary,
+        match_score: score,
+        breakdown: ScoreBreakdown {
+            factors,
+            adjustments,
+        },
+        rejection,
+    }
+}
+
+/// Applies the profile's hard filters. Fields the listing does not state never cause a rejection.
+fn check_filters(
+    extracted: &ExtractedListing,
+    settings: &JobSettings,
+    company: Option<&ResolvedCompany>,
+    blacklist_entry: Option<&str>,
+) -> Option<Rejection> {
+    let filters = &settings.filters;
+    let reject = |rule: &str, reason: String| {
+        Some(Rejection {
+            rule: rule.to_string(),
+            reason,
+        })
+    };
+
+    if let (true, Some(company), Some(entry)) = (filters.blacklist, company, blacklist_entry) {
+        let name = company.client.as_deref().unwrap_or(&company.posted);
+        return reject(
+            "blacklist",
+            format!("company '{name}' matches blacklist entry '{entry}'"),
+        );
+    }
+
+    if let (true, Some(company)) = (filters.agencies, company) {
+        if company.agency {
+            return reject(
+                "agency",
+                format!("posted by recruiting agency '{}'", company.posted),
+            );
+        }
+    }
+
+    if filters.location && !settings.locations.is_empty() {
+        if let Some(location) = &extracted.location {
+            let location_lower = location.to_lowercase();
+            let remote = extracted.arrangement.as_deref() == Some("remote");
+            let allowed = settings.locations.iter().any(|value| {
+                let value = value.to_lowercase();
+                location_lower.contains(&value) || (remote && value == "remote")
+            });
+            if !allowed {
+                return reject(
+                    "location",
+                    format!("location '{location}' is not in the allowed locations"),
+                );
+            }
+        }
+    }
+
+    if !filters.employment_types.is_empty() {
+        if let Some(kind) = &extracted.employment_type {
+            if !filters
+                .employment_types
+                .iter()
+                .any(|allowed| allowed.eq_ignore_ascii_case(kind))
+            {
+                return reject("employment_type", format!("employment type is {kind}"));
+            }
+        }
+    }
+
+    if filters.require_sponsorship && extracted.visa_sponsorship == Some(false) {
+        return reject(
+            "sponsorship",
+            "listing does not offer visa sponsorship".to_string(),
+        );
+    }
+
+    if let Some(minimum) = filters.min_salary {
+        if let Some(top) = extracted.salary_max.or(extracted.salary_min) {
+            if top < minimum {
+                return reject(
+                    "salary",
+                    format!("salary tops out at {top}, below the minimum of {minimum}"),
+                );
+            }
+        }
+    }
+
+    None
+}
+
+/// Fits factor weights to thumbs-up/thumbs-down ratings with L2-regularized logistic regression.
+///
+/// The model predicts `P(good) = sigmoid((score - 50) / 10)` where `score` is the weighted sum of
+/// the stored features, so learned weights stay in the same units as the defaults.
+pub fn train_weights(defaults: &ScoreWeights, samples: &[FeedbackSample]) -> ScoreWeights {
+    let mut weights = defaults.clone();
+    if samples.is_empty() {
+        return weights;
+    }
+    let count = samples.len() as f64;
+    for _ in 0..TRAINING_EPOCHS {
+        let mut gradient: BTreeMap<String, f64> = BTreeMap::new();
+        for (features, good) in samples {
+            let score: f64 = features
+                .iter()
+                .map(|(name, value)| value * weights.get(name).copied().unwrap_or(0.0))
+                .sum();
+            let predicted = sigmoid((score - DECISION_THRESHOLD) / DECISION_SCALE);
+            let error = predicted - if *good { 1.0 } else { 0.0 };
+            for (name, value) in features {
+                if weights.contains_key(name) {
+                    *gradient.entry(name.clone()).or_default() +=
+                        error * value / DECISION_SCALE / count;
+                }
+            }
+        }
+        for (name, weight) in weights.iter_mut() {
+            let anchor = defaults.get(name).copied().unwrap_or(0.0);
+            let step = gradient.get(name).copied().unwrap_or(0.0)
+                + 2.0 * REGULARIZATION * (*weight - anchor);
+            *weight = (*weight - LEARNING_RATE * step).clamp(-MAX_WEIGHT, MAX_WEIGHT);
+        }
+    }
+    weights
+}
+
+fn sigmoid(value: f64) -> f64 {
+    1.0 / (1.0 + (-value).exp())
+}
+
+fn indicator(flag: bool) -> f64 {
+    if flag {
+        1.0
+    } else {
+        0.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn listing() -> ExtractedListing {
+        ExtractedListing {
+            title: Some("Software Engineer".to_string()),
+            company: Some("Acme".to_string()),
+            location: None,
+            text: String::new(),
+            raw_excerpt: None,
+            arrangement: None,
+            employment_type: None,
+            salary_min: None,
+            salary_max: None,
+            salary_currency: None,
+            visa_sponsorship: None,
+            timezone: None,
+        }
+    }
+
+    fn company(posted: &str, agency: bool) -> ResolvedCompany {
+        ResolvedCompany {
+            posted: posted.to_string(),
+            agency,
+            client: None,
+        }
+    }
+
+    fn rejected_by(
+        extracted: &ExtractedListing,
+        settings: &JobSettings,
+        company: Option<&ResolvedCompany>,
+        blacklist_entry: Option<&str>,
+    ) -> Option<String> {
+        check_filters(extracted, settings, company, blacklist_entry).map(|rejection| rejection.rule)
+    }
+
+    #[test]
+    fn each_filter_reports_its_rule() {
+        let mut settings = JobSettings::default();
+        let acme = company("Acme", false);
+        assert_eq!(
+            rejected_by(&listing(), &settings, Some(&acme), Some("acme")).as_deref(),
+            Some("blacklist")
+        );
+
+        settings.filters.agencies = true;
+        let agency = company("Hays", true);
+        assert_eq!(
+            rejected_by(&listing(), &settings, Some(&agency), None).as_deref(),
+            Some("agency")
+        );
+
+        settings.filters.location = true;
+        settings.locations = vec!["Berlin".to_string()];
+        let mut extracted = listing();
+        extracted.location = Some("Paris, France".to_string());
+        assert_eq!(
+            rejected_by(&extracted, &settings, None, None).as_deref(),
+            Some("location")
+        );
+        extracted.location = Some("Berlin, Germany".to_string());
+        assert_eq!(rejected_by(&extracted, &settings, None, None), None);
+
+        settings.filters.employment_types = vec!["full-time".to_string()];
+        extracted.employment_type = Some("contract".to_string());
+        assert_eq!(
+            rejected_by(&extracted, &settings, None, None).as_deref(),
+            Some("employment_type")
+        );
+        extracted.employment_type = Some("Full-Time".to_string());
+
+        settings.filters.require_sponsorship = true;
+        extracted.visa_sponsorship = Some(false);
+        assert_eq!(
+            rejected_by(&extracted, &settings, None, None).as_deref(),
+            Some("sponsorship")
+        );
+        extracted.visa_sponsorship = Some(true);
+
+        settings.filters.min_salary = Some(100_000);
+        extracted.salary_min = Some(70_000);
+        extracted.salary_max = Some(90_000);
+        let rejection = check_filters(&extracted, &settings, None, None).unwrap();
+        assert_eq!(rejection.rule, "salary");
+        assert_eq!(
+            rejection.reason,
+            "salary tops out at 90000, below the minimum of 100000"
+        );
+    }
+
+    #[test]
+    fn unstated_fields_never_reject() {
+        let mut settings = JobSettings::default();
+        settings.filters.location = true;
+        settings.locations = vec!["Berlin".to_string()];
+        settings.filters.employment_types = vec!["full-time".to_string()];
+        settings.filters.require_sponsorship = true;
+        settings.filters.min_salary = Some(100_000);
+        assert_eq!(rejected_by(&listing(), &settings, None, None), None);
+    }
+}
@@ -1,3 +1,6 @@
+use crate::company::CompanyPreferences;
+use crate::{geo, rules, scoring, timezone};
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use std::collections::HashMap;
@@ -5,7 +8,17 @@ use tauri::AppHandle;
 use tauri_plugin_store::{StoreBuilder, StoreExt};
 
 const STORE_FILENAME: &str = "job_settings.json";
-const SETTINGS_KEY: &str = "settings";
+/// Single-profile settings written before named profiles existed; migrated on first load.
+const LEGACY_SETTINGS_KEY: &str = "settings";
+const PROFILES_KEY: &str = "profiles";
+const LLM_SETTINGS_KEY: &str = "llm";
+const NETWORK_SETTINGS_KEY: &str = "network";
+const ARCHIVE_SETTINGS_KEY: &str = "archive";
+const WATCH_SETTINGS_KEY: &str = "watch";
+const FEED_SETTINGS_KEY: &str = "feeds";
+const CRAWL_SETTINGS_KEY: &str = "crawl";
+
+pub const DEFAULT_PROFILE: &str = "Default";
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
@@ -17,6 +30,89 @@ pub struct JobSettings {
     pub salary_min: Option<i64>,
     pub salary_max: Option<i64>,
     pub company_blacklist: Vec<String>,
+    /// Favorites, tiers, alias groups and agencies used when matching company names.
+    #[serde(default)]
+    pub companies: CompanyPreferences,
+    #[serde(default)]
+    pub resume: Option<String>,
+    /// Filter and score-adjustment rules, see `rules.rs` for the syntax.
+    #[serde(default)]
+    pub rules: Vec<String>,
+    #[serde(default)]
+    pub filters: HardFilters,
+    /// IANA timezone the user works from, e.g. `Europe/Berlin`.
+    #[serde(default)]
+    pub home_timezone: Option<String>,
+    #[serde(default)]
+    pub working_hours: WorkingHours,
+    #[serde(default)]
+    pub commute: Commute,
+}
+
+/// Where the user lives and how far they will travel to a hybrid or onsite office.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Commute {
+    /// A city from the bundled dataset or `latitude, longitude`.
+    pub home_location: Option<String>,
+    pub max_km: f64,
+}
+
+impl Default for Commute {
+    fn default() -> Self {
+        Self {
+            home_location: None,
+            max_km: 30.0,
+        }
+    }
+}
+
+/// Local hours the user is willing to work, `0..=24`; an end before the start crosses midnight.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default, rename_all = "camelCase")]
+pub struct WorkingHours {
+    pub start: f64,
+    pub end: f64,
+}
+
+impl Default for WorkingHours {
+    fn default() -> Self {
+        Self {
+            start: 9.0,
+            end: 17.0,
+        }
+    }
+}
+
+/// Filters that reject a listing outright instead of lowering its score.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default, rename_all = "camelCase")]
+pub struct HardFilters {
+    /// Rejects companies on the blacklist, including their aliases.
+    pub blacklist: bool,
+    /// Rejects listings posted by recruiting agencies.
+    pub agencies: bool,
+    /// Rejects listings whose location matches none of the preferred locations.
+    pub location: bool,
+    /// Allowed employment types such as `full-time` or `contract`; empty allows any.
+    pub employment_types: Vec<String>,
+    /// Rejects listings that state they do not sponsor work visas.
+    pub require_sponsorship: bool,
+    /// Rejects listings whose advertised salary tops out below this amount.
+    pub min_salary: Option<i64>,
+}
+
+impl Default for HardFilters {
+    fn default() -> Self {
+        Self {
+            blacklist: true,
+            agencies: false,
+            location: false,
+            employment_types: Vec::new(),
+            require_sponsorship: false,
+            min_salary: None,
+        }
+    }
 }
 
 impl Default for JobSettings {
@@ -41,44 +137,689 @@ impl Default for JobSettings {
             salary_min: Some(120_000),
             salary_max: Some(200_000),
             company_blacklist: Vec::new(),
+            companies: CompanyPreferences::default(),
+            resume: None,
+            rules: Vec::new(),
+            filters: HardFilters::default(),
+            home_timezone: None,
+            working_hours: WorkingHours::default(),
+            commute: Commute::default(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LlmSettings {
+    pub enabled: bool,
+    pub base_url: String,
+    pub model: String,
+    pub api_key: Option<String>,
+    pub timeout_secs: u64,
+    pub temperature: f64,
+}
+
+impl Default for LlmSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            base_url: "http://127.0.0.1:11434/v1".to_string(),
+            model: "llama3.1".to_string(),
+            api_key: None,
+            timeout_secs: 60,
+            temperature: 0.1,
+        }
+    }
+}
+
+/// Politeness limits for one host and its subdomains.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default, rename_all = "camelCase")]
+pub struct HostLimit {
+    /// Host suffix such as `linkedin.com`; empty for the default limit.
+    pub host: String,
+    /// Sustained request rate; 0 disables rate limiting.
+    pub requests_per_second: f64,
+    /// Requests allowed back to back before the rate applies.
+    pub burst: u32,
+    /// Requests in flight at once.
+    pub max_concurrent: usize,
+}
+
+impl Default for HostLimit {
+    fn default() -> Self {
+        Self {
+            host: String::new(),
+            requests_per_second: 2.0,
+            burst: 4,
+            max_concurrent: 4,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default, rename_all = "camelCase")]
+pub struct RetrySettings {
+    /// Retries after the first attempt for timeouts, dropped connections, 429s and 5xx responses.
+    pub max_retries: u32,
+    /// Delay before the first retry; doubles on each further retry.
+    pub base_delay_ms: u64,
+    /// Upper bound for a single delay. A `Retry-After` longer than this fails the fetch instead.
+    pub max_delay_ms: u64,
+}
+
+impl Default for RetrySettings {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            base_delay_ms: 1_000,
+            max_delay_ms: 30_000,
+        }
+    }
+}
+
+/// Client options that replace the global ones for a domain and its subdomains.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct DomainOverride {
+    pub domain: String,
+    pub proxy: Option<String>,
+    pub user_agent: Option<String>,
+    pub connect_timeout_secs: Option<u64>,
+    pub read_timeout_secs: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum RobotsMode {
+    /// Disallowed pages fail with a `disallowed_by_robots` error.
+    Respect,
+    /// Disallowed pages are fetched, with a warning.
+    Warn,
+    /// robots.txt is not read at all, so `Crawl-delay` does not apply either.
+    Ignore,
+}
+
+/// How robots.txt applies to each kind of fetch.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default, rename_all = "camelCase")]
+pub struct RobotsSettings {
+    /// Crawls, feed polls, listing watch and analyses those start.
+    pub automated: RobotsMode,
+    /// A single URL the user pasted for analysis.
+    pub user: RobotsMode,
+}
+
+impl Default for RobotsSettings {
+    fn default() -> Self {
+        Self {
+            automated: RobotsMode::Respect,
+            user: RobotsMode::Warn,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default, rename_all = "camelCase")]
+pub struct NetworkSettings {
+    /// Applies to every host without an entry in `host_limits`; each host gets its own bucket.
+    pub default_limit: HostLimit,
+    /// Checked in order; the first entry whose host matches wins and is shared by its subdomains.
+    pub host_limits: Vec<HostLimit>,
+    pub retry: RetrySettings,
+    /// Response bodies are cut off after this many bytes.
+    pub max_body_bytes: usize,
+    /// `http://`, `https://`, `socks5://` or `socks5h://` URL; credentials may be embedded as
+    /// `user:password@`. Without one, the `HTTP_PROXY` / `HTTPS_PROXY` environment applies.
+    pub proxy: Option<String>,
+    /// Hosts, domains or IP ranges reached without the proxy, as in `NO_PROXY`.
+    pub no_proxy: Vec<String>,
+    pub user_agent: String,
+    /// PEM file with extra root certificates, for proxies that inspect TLS.
+    pub ca_bundle: Option<String>,
+    pub connect_timeout_secs: u64,
+    /// Longest wait for the next chunk of a response.
+    pub read_timeout_secs: u64,
+    /// Checked in order; the first entry whose domain matches wins.
+    pub domain_overrides: Vec<DomainOverride>,
+    pub robots: RobotsSettings,
+}
+
+impl Default for NetworkSettings {
+    fn default() -> Self {
+        let limit =
+            |host: &str, requests_per_second: f64, burst: u32, max_concurrent: usize| HostLimit {
+                host: host.to_string(),
+                requests_per_second,
+                burst,
+                max_concurrent,
+            };
+        Self {
+            default_limit: HostLimit::default(),
+            host_limits: vec![
+                // LinkedIn blocks quickly; one request every five seconds stays under its radar.
+                limit("linkedin.com", 0.2, 1, 1),
+                limit("indeed.com", 0.5, 1, 1),
+                limit("greenhouse.io", 1.0, 2, 2),
+                limit("lever.co", 1.0, 2, 2),
+                limit("ashbyhq.com", 1.0, 2, 2),
+                limit("myworkdayjobs.com", 0.5, 2, 2),
+                limit("smartrecruiters.com", 1.0, 2, 2),
+                limit("workable.com", 1.0, 2, 2),
+            ],
+            retry: RetrySettings::default(),
+            max_body_bytes: 4 * 1024 * 1024,
+            proxy: None,
+            no_proxy: Vec::new(),
+            user_agent: "JobHunter/1.0".to_string(),
+            ca_bundle: None,
+            connect_timeout_secs: 10,
+            read_timeout_secs: 30,
+            domain_overrides: Vec::new(),
+            robots: RobotsSettings::default(),
+        }
+    }
+}
+
+fn host_in_domain(host: &str, domain: &str) -> bool {
+    let suffix = domain.trim().trim_start_matches('.').to_lowercase();
+    !suffix.is_empty() && (host == suffix || host.ends_with(&format!(".{suffix}")))
+}
+
+impl NetworkSettings {
+    /// The limit for `host`, matching entries by exact host or parent domain.
+    pub fn limit_for(&self, host: &str) -> Option<&HostLimit> {
+        let host = host.to_lowercase();
+        self.host_limits
+            .iter()
+            .find(|limit| host_in_domain(&host, &limit.host))
+    }
+
+    /// Index of the override for `host` in `domain_overrides`, matched like `limit_for`.
+    pub fn override_for(&self, host: &str) -> Option<usize> {
+        let host = host.to_lowercase();
+        self.domain_overrides
+            .iter()
+            .position(|entry| host_in_domain(&host, &entry.domain))
+    }
+
+    /// The `User-Agent` sent to `host`, after any domain override.
+    pub fn user_agent_for(&self, host: &str) -> &str {
+        self.override_for(host)
+            .and_then(|index| self.domain_overrides[index].user_agent.as_deref())
+            .unwrap_or(&self.user_agent)
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        for limit in std::iter::once(&self.default_limit).chain(&self.host_limits) {
+            let name = if limit.host.is_empty() {
+                "default"
+            } else {
+                limit.host.as_str()
+            };
+            if !limit.requests_per_second.is_finite() || limit.requests_per_second < 0.0 {
+                return Err(format!(
+                    "requests per second for {name} must be zero or more"
+                ));
+            }
+            if limit.burst == 0 || limit.max_concurrent == 0 {
+                return Err(format!(
+                    "burst and concurrency for {name} must be at least 1"
+                ));
+            }
+        }
+        if self
+            .host_limits
+            .iter()
+            .any(|limit| limit.host.trim().is_empty())
+        {
+            return Err("host limits need a host".to_string());
+        }
+        if self.max_body_bytes == 0 {
+            return Err("max body bytes must be at least 1".to_string());
+        }
+        if self.retry.base_delay_ms == 0 || self.retry.max_delay_ms < self.retry.base_delay_ms {
+            return Err(
+                "retry delays must be positive with the maximum above the base".to_string(),
+            );
+        }
+        if self.user_agent.trim().is_empty() {
+            return Err("user agent is required".to_string());
+        }
+        let proxies = std::iter::once(&self.proxy)
+            .chain(self.domain_overrides.iter().map(|entry| &entry.proxy))
+            .flatten();
+        for proxy in proxies {
+            let scheme = proxy.split_once("://").map(|(scheme, _)| scheme);
+            if !matches!(scheme, Some("http" | "https" | "socks5" | "socks5h")) {
+                return Err(format!(
+                    "proxy {proxy} must start with http://, https://, socks5:// or socks5h://"
+                ));
+            }
+        }
+        let timeouts = [self.connect_timeout_secs, self.read_timeout_secs]
+            .into_iter()
+            .chain(self.domain_overrides.iter().flat_map(|entry| {
+                [entry.connect_timeout_secs, entry.read_timeout_secs]
+                    .into_iter()
+                    .flatten()
+            }));
+        for timeout in timeouts {
+            if timeout == 0 {
+                return Err("timeouts must be at least 1 second".to_string());
+            }
+        }
+        if self
+            .domain_overrides
+            .iter()
+            .any(|entry| entry.domain.trim().is_empty())
+        {
+            return Err("domain overrides need a domain".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Page snapshot archive options.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ArchiveSettings {
+    pub enabled: bool,
+    /// Compressed bodies beyond this total are pruned, oldest unlinked snapshots first.
+    pub max_total_bytes: u64,
+}
+
+impl Default for ArchiveSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_total_bytes: 256 * 1024 * 1024,
+        }
+    }
+}
+
+/// Listing change monitoring options.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default, rename_all = "camelCase")]
+pub struct WatchSettings {
+    pub enabled: bool,
+    /// Each watched listing is refetched at most this often.
+    pub interval_hours: u32,
+    /// Also watch matches the filters rejected.
+    pub include_rejected: bool,
+    /// Matches saved longer ago than this are no longer watched.
+    pub max_age_days: u32,
+}
+
+impl Default for WatchSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_hours: 12,
+            include_rejected: false,
+            max_age_days: 60,
         }
     }
 }
 
+/// Feed subscription polling options.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default, rename_all = "camelCase")]
+pub struct FeedSettings {
+    pub enabled: bool,
+    pub poll_interval_minutes: u32,
+    /// New items analyzed per feed and poll; the rest wait for the next poll.
+    pub max_items_per_poll: usize,
+}
+
+impl Default for FeedSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            poll_interval_minutes: 60,
+            max_items_per_poll: 10,
+        }
+    }
+}
+
+/// Bounds for careers-site crawls.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default, rename_all = "camelCase")]
+pub struct CrawlSettings {
+    /// Link hops followed from the start page; sitemap entries count as one hop.
+    pub max_depth: u32,
+    /// Pages fetched per crawl, not counting robots.txt and sitemaps.
+    pub max_pages: usize,
+}
+
+impl Default for CrawlSettings {
+    fn default() -> Self {
+        Self {
+            max_depth: 3,
+            max_pages: 200,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchProfile {
+    pub name: String,
+    pub settings: JobSettings,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileStore {
+    pub active: String,
+    pub profiles: Vec<SearchProfile>,
+}
+
+impl ProfileStore {
+    pub fn with_default(settings: JobSettings) -> Self {
+        Self {
+            active: DEFAULT_PROFILE.to_string(),
+            profiles: vec![SearchProfile {
+                name: DEFAULT_PROFILE.to_string(),
+                settings,
+            }],
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<&SearchProfile> {
+        self.profiles.iter().find(|profile| profile.name == name)
+    }
+
+    pub fn active_profile(&self) -> Option<&SearchProfile> {
+        self.get(&self.active).or_else(|| self.profiles.first())
+    }
+
+    pub fn create(&mut self, name: &str, settings: Option<JobSettings>) -> Result<(), String> {
+        let name = self.available_name(name)?;
+        let settings = settings
+            .or_else(|| {
+                self.active_profile()
+                    .map(|profile| profile.settings.clone())
+            })
+            .unwrap_or_default();
+        self.profiles.push(SearchProfile { name, settings });
+        Ok(())
+    }
+
+    pub fn rename(&mut self, from: &str, to: &str) -> Result<(), String> {
+        let to = self.available_name(to)?;
+        let profile = self
+            .profiles
+            .iter_mut()
+            .find(|profile| profile.name == from)
+            .ok_or_else(|| format!("profile not found: {from}"))?;
+        profile.name = to.clone();
+        if self.active == from {
+            self.active = to;
+        }
+        Ok(())
+    }
+
+    pub fn delete(&mut self, name: &str) -> Result<(), String> {
+        if self.get(name).is_none() {
+            return Err(format!("profile not found: {name}"));
+        }
+        if self.profiles.len() == 1 {
+            return Err("cannot delete the only profile".to_string());
+        }
+        self.profiles.retain(|profile| profile.name != name);
+        if self.active == name {
+            self.active = self.profiles[0].name.clone();
+        }
+        Ok(())
+    }
+
+    pub fn activate(&mut self, name: &str) -> Result<(), String> {
+        if self.get(name).is_none() {
+            return Err(format!("profile not found: {name}"));
+        }
+        self.active = name.to_string();
+        Ok(())
+    }
+
+    fn available_name(&self, name: &str) -> Result<String, String> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err("profile name is required".to_string());
+        }
+        if self.get(name).is_some() {
+            return Err(format!("profile already exists: {name}"));
+        }
+        Ok(name.to_string())
+    }
+}
+
 pub fn ensure_defaults(app: &AppHandle) -> Result<JobSettings, String> {
-    let defaults = JobSettings::default();
     let mut default_map = HashMap::new();
-    default_map.insert(SETTINGS_KEY.to_string(), json!(defaults));
+    default_map.insert(LLM_SETTINGS_KEY.to_string(), json!(LlmSettings::default()));
+    default_map.insert(
+        NETWORK_SETTINGS_KEY.to_string(),
+        json!(NetworkSettings::default()),
+    );
+    default_map.insert(
+        ARCHIVE_SETTINGS_KEY.to_string(),
+        json!(ArchiveSettings::default()),
+    );
+    default_map.insert(
+        WATCH_SETTINGS_KEY.to_string(),
+        json!(WatchSettings::default()),
+    );
+    default_map.insert(
+        FEED_SETTINGS_KEY.to_string(),
+        json!(FeedSettings::default()),
+    );
+    default_map.insert(
+        CRAWL_SETTINGS_KEY.to_string(),
+        json!(CrawlSettings::default()),
+    );
 
-    let _store = StoreBuilder::new(app, STORE_FILENAME)
+    let store = StoreBuilder::new(app, STORE_FILENAME)
         .defaults(default_map)
         .auto_save(std::time::Duration::from_millis(400))
         .build()
         .map_err(|err| format!("store build: {err}"))?;
-    match load_settings(app)? {
-        Some(settings) => Ok(settings),
-        None => save_settings(app, &defaults),
+    if store.get(PROFILES_KEY).is_none() {
+        let profiles = load_profiles(app)?;
+        save_profiles(app, &profiles)?;
+        store.delete(LEGACY_SETTINGS_KEY);
     }
+    Ok(load_settings(app)?.unwrap_or_default())
 }
 
-pub fn load_settings(app: &AppHandle) -> Result<Option<JobSettings>, String> {
+pub fn load_profiles(app: &AppHandle) -> Result<ProfileStore, String> {
     let store = app
         .store(STORE_FILENAME)
         .map_err(|err| format!("store load: {err}"))?;
-    let value = store.get(SETTINGS_KEY);
-    match value {
-        Some(val) => serde_json::from_value(val)
-            .map(Some)
-            .map_err(|err| format!("settings parse: {err}")),
-        None => Ok(None),
+    if let Some(value) = store.get(PROFILES_KEY) {
+        return serde_json::from_value(value).map_err(|err| format!("profiles parse: {err}"));
     }
+    let legacy = match store.get(LEGACY_SETTINGS_KEY) {
+        Some(value) => {
+            serde_json::from_value(value).map_err(|err| format!("settings parse: {err}"))?
+        }
+        None => JobSettings::default(),
+    };
+    Ok(ProfileStore::with_default(legacy))
 }
 
-pub fn save_settings(app: &AppHandle, settings: &JobSettings) -> Result<JobSettings, String> {
+pub fn save_profiles(app: &AppHandle, profiles: &ProfileStore) -> Result<ProfileStore, String> {
+    for profile in &profiles.profiles {
+        rules::validate_rules(&profile.settings.rules, scoring::RULE_FIELDS)
+            .map_err(|err| format!("profile '{}': {err}", profile.name))?;
+        if let Some(zone) = &profile.settings.home_timezone {
+            if !timezone::is_valid_timezone(zone) {
+                return Err(format!(
+                    "profile '{}': unknown timezone '{zone}'",
+                    profile.name
+                ));
+            }
+        }
+        if let Some(home) = &profile.settings.commute.home_location {
+            if !home.trim().is_empty() && geo::resolve(home).is_none() {
+                return Err(format!(
+                    "profile '{}': unknown home location '{home}', use a city or 'latitude, longitude'",
+                    profile.name
+                ));
+            }
+        }
+        if profile.settings.commute.max_km <= 0.0 {
+            return Err(format!(
+                "profile '{}': maximum commute must be positive",
+                profile.name
+            ));
+        }
+        let hours = &profile.settings.working_hours;
+        if !(0.0..=24.0).contains(&hours.start) || !(0.0..=24.0).contains(&hours.end) {
+            return Err(format!(
+                "profile '{}': working hours must be between 0 and 24",
+                profile.name
+            ));
+        }
+    }
     let store = app
         .store(STORE_FILENAME)
         .map_err(|err| format!("store load: {err}"))?;
-    store.set(SETTINGS_KEY.to_string(), json!(settings));
+    store.set(PROFILES_KEY.to_string(), json!(profiles));
     store.save().map_err(|err| format!("store save: {err}"))?;
+    Ok(profiles.clone())
+}
+
+/// Applies a change to the stored profiles and persists the result.
+pub fn update_profiles<F>(app: &AppHandle, change: F) -> Result<ProfileStore, String>
+where
+    F: FnOnce(&mut ProfileStore) -> Result<(), String>,
+{
+    let mut profiles = load_profiles(app)?;
+    change(&mut profiles)?;
+    save_profiles(app, &profiles)
+}
+
+/// Loads the settings of the active profile.
+pub fn load_settings(app: &AppHandle) -> Result<Option<JobSettings>, String> {
+    let profiles = load_profiles(app)?;
+    Ok(profiles
+        .active_profile()
+        .map(|profile| profile.settings.clone()))
+}
+
+/// Saves settings into the active profile.
+pub fn save_settings(app: &AppHandle, settings: &JobSettings) -> Result<JobSettings, String> {
+    let mut profiles = load_profiles(app)?;
+    let active = profiles.active.clone();
+    match profiles
+        .profiles
+        .iter_mut()
+        .find(|profile| profile.name == active)
+    {
+        Some(profile) => profile.settings = settings.clone(),
+        None => {
+            profiles.profiles.push(SearchProfile {
+                name: active,
+                settings: settings.clone(),
+            });
+        }
+    }
+    save_profiles(app, &profiles)?;
     Ok(settings.clone())
 }
+
+/// Reads one settings section from the store, or its defaults when it was never saved.
+fn load_section<T: DeserializeOwned + Default>(app: &AppHandle, key: &str) -> Result<T, String> {
+    let store = app
+        .store(STORE_FILENAME)
+        .map_err(|err| format!("store load: {err}"))?;
+    match store.get(key) {
+        Some(val) => {
+            serde_json::from_value(val).map_err(|err| format!("{key} settings parse: {err}"))
+        }
+        None => Ok(T::default()),
+    }
+}
+
+/// Writes one settings section to the store and returns what was saved.
+fn save_section<T: Serialize + Clone>(app: &AppHandle, key: &str, value: &T) -> Result<T, String> {
+    let store = app
+        .store(STORE_FILENAME)
+        .map_err(|err| format!("store load: {err}"))?;
+    store.set(key.to_string(), json!(value));
+    store.save().map_err(|err| format!("store save: {err}"))?;
+    Ok(value.clone())
+}
+
+pub fn load_llm_settings(app: &AppHandle) -> Result<LlmSettings, String> {
+    load_section(app, LLM_SETTINGS_KEY)
+}
+
+pub fn save_llm_settings(app: &AppHandle, settings: &LlmSettings) -> Result<LlmSettings, String> {
+    save_section(app, LLM_SETTINGS_KEY, settings)
+}
+
+pub fn load_network_settings(app: &AppHandle) -> Result<NetworkSettings, String> {
+    load_section(app, NETWORK_SETTINGS_KEY)
+}
+
+pub fn save_network_settings(
+    app: &AppHandle,
+    settings: &NetworkSettings,
+) -> Result<NetworkSettings, String> {
+    settings.validate()?;
+    save_section(app, NETWORK_SETTINGS_KEY, settings)
+}
+
+pub fn load_archive_settings(app: &AppHandle) -> Result<ArchiveSettings, String> {
+    load_section(app, ARCHIVE_SETTINGS_KEY)
+}
+
+pub fn save_archive_settings(
+    app: &AppHandle,
+    settings: &ArchiveSettings,
+) -> Result<ArchiveSettings, String> {
+    save_section(app, ARCHIVE_SETTINGS_KEY, settings)
+}
+
+pub fn load_watch_settings(app: &AppHandle) -> Result<WatchSettings, String> {
+    load_section(app, WATCH_SETTINGS_KEY)
+}
+
+pub fn save_watch_settings(
+    app: &AppHandle,
+    settings: &WatchSettings,
+) -> Result<WatchSettings, String> {
+    if settings.interval_hours == 0 || settings.max_age_days == 0 {
+        return Err("watch interval and maximum age must be at least 1".to_string());
+    }
+    save_section(app, WATCH_SETTINGS_KEY, settings)
+}
+
+pub fn load_feed_settings(app: &AppHandle) -> Result<FeedSettings, String> {
+    load_section(app, FEED_SETTINGS_KEY)
+}
+
+pub fn save_feed_settings(
+    app: &AppHandle,
+    settings: &FeedSettings,
+) -> Result<FeedSettings, String> {
+    if settings.poll_interval_minutes == 0 || settings.max_items_per_poll == 0 {
+        return Err("feed poll interval and items per poll must be at least 1".to_string());
+    }
+    save_section(app, FEED_SETTINGS_KEY, settings)
+}
+
+pub fn load_crawl_settings(app: &AppHandle) -> Result<CrawlSettings, String> {
+    load_section(app, CRAWL_SETTINGS_KEY)
+}
+
+pub fn save_crawl_settings(
+    app: &AppHandle,
+    settings: &CrawlSettings,
+) -> Result<CrawlSettings, String> {
+    if settings.max_pages == 0 {
+        return Err("crawl page budget must be at least 1".to_string());
+    }
+    save_section(app, CRAWL_SETTINGS_KEY, settings)
+}
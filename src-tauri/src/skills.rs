@@ -0,0 +1,390 @@
+use crate::db::{Db, SkillSource};
+use crate::settings::{load_profiles, JobSettings};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::OnceLock;
+use tauri::AppHandle;
+
+/// Skills recognized in listing text: canonical name and the spellings that count as it.
+const SKILLS: &[(&str, &[&str])] = &[
+    ("JavaScript", &["javascript", "js", "ecmascript"]),
+    ("TypeScript", &["typescript", "ts"]),
+    ("Python", &["python"]),
+    ("Java", &["java"]),
+    ("Kotlin", &["kotlin"]),
+    ("Scala", &["scala"]),
+    ("Go", &["golang", "go lang"]),
+    ("Rust", &["rust"]),
+    ("C", &["c language", "ansi c"]),
+    ("C++", &["c++", "cpp"]),
+    ("C#", &["c#", "csharp"]),
+    (".NET", &[".net", "dotnet", "asp.net"]),
+    ("Ruby", &["ruby"]),
+    ("Rails", &["rails", "ruby on rails"]),
+    ("PHP", &["php"]),
+    ("Elixir", &["elixir"]),
+    ("Haskell", &["haskell"]),
+    ("Swift", &["swift"]),
+    ("Objective-C", &["objective-c"]),
+    ("Dart", &["dart"]),
+    ("Flutter", &["flutter"]),
+    ("React", &["react", "react.js", "reactjs"]),
+    ("React Native", &["react native"]),
+    ("Next.js", &["next.js", "nextjs"]),
+    ("Vue", &["vue", "vue.js", "vuejs"]),
+    ("Angular", &["angular"]),
+    ("Svelte", &["svelte", "sveltekit"]),
+    ("Node.js", &["node.js", "nodejs", "node"]),
+    ("Deno", &["deno"]),
+    ("HTML", &["html", "html5"]),
+    ("CSS", &["css", "css3", "sass", "scss"]),
+    ("Tailwind", &["tailwind", "tailwindcss"]),
+    ("GraphQL", &["graphql"]),
+    ("REST", &["restful", "rest api", "rest apis"]),
+    ("gRPC", &["grpc"]),
+    ("SQL", &["sql"]),
+    ("PostgreSQL", &["postgresql", "postgres"]),
+    ("MySQL", &["mysql", "mariadb"]),
+    ("SQLite", &["sqlite"]),
+    ("MongoDB", &["mongodb", "mongo"]),
+    ("Redis", &["redis"]),
+    ("Elasticsearch", &["elasticsearch", "opensearch"]),
+    ("DynamoDB", &["dynamodb"]),
+    ("Cassandra", &["cassandra"]),
+    ("Kafka", &["kafka"]),
+    ("RabbitMQ", &["rabbitmq"]),
+    ("Spark", &["spark", "pyspark"]),
+    ("Airflow", &["airflow"]),
+    ("dbt", &["dbt"]),
+    ("Snowflake", &["snowflake"]),
+    ("BigQuery", &["bigquery"]),
+    ("Pandas", &["pandas"]),
+    ("PyTorch", &["pytorch"]),
+    ("TensorFlow", &["tensorflow"]),
+    ("LLMs", &["llm", "llms", "large language models"]),
+    ("AWS", &["aws", "amazon web services"]),
+    ("GCP", &["gcp", "google cloud"]),
+    ("Azure", &["azure"]),
+    ("Docker", &["docker", "containers"]),
+    ("Kubernetes", &["kubernetes", "k8s"]),
+    ("Helm", &["helm"]),
+    ("Terraform", &["terraform"]),
+    ("Ansible", &["ansible"]),
+    ("Linux", &["linux"]),
+    (
+        "CI/CD",
+        &[
+            "ci/cd",
+            "continuous integration",
+            "github actions",
+            "gitlab ci",
+            "jenkins",
+        ],
+    ),
+    ("Git", &["git"]),
+    ("Prometheus", &["prometheus"]),
+    ("Grafana", &["grafana"]),
+    ("Microservices", &["microservices", "microservice"]),
+    ("WebAssembly", &["webassembly", "wasm"]),
+    ("Tauri", &["tauri"]),
+    ("Electron", &["electron"]),
+    ("OAuth", &["oauth", "oauth2", "openid connect"]),
+];
+
+fn skill_patterns() -> &'static [(&'static str, Regex)] {
+    static PATTERNS: OnceLock<Vec<(&'static str, Regex)>> = OnceLock::new();
+    PATTERNS.get_or_init(|| {
+        SKILLS
+            .iter()
+            .filter_map(|(name, spellings)| {
+                let alternatives = spellings
+                    .iter()
+                    .map(|spelling| regex::escape(spelling))
+                    .collect::<Vec<_>>()
+                    .join("|");
+                // Letters, digits, `+` and `#` continue a word, so "c" never matches inside "c++".
+                let pattern = format!(r"(?i)(?:^|[^a-z0-9+#])(?:{alternatives})(?:$|[^a-z0-9+#])");
+                Regex::new(&pattern).ok().map(|regex| (*name, regex))
+            })
+            .collect()
+    })
+}
+
+/// Canonical names of the known skills mentioned in `text`.
+pub fn extract_skills(text: &str) -> Vec<&'static str> {
+    skill_patterns()
+        .iter()
+        .filter(|(_, regex)| regex.is_match(text))
+        .map(|(name, _)| *name)
+        .collect()
+}
+
+/// Maps a free-form skill such as an LLM-extracted `"react.js"` to its canonical name.
+fn canonical_skill(skill: &str) -> String {
+    extract_skills(skill)
+        .first()
+        .map(|name| name.to_string())
+        .unwrap_or_else(|| skill.trim().to_string())
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Weighting {
+    /// Every listing counts once.
+    #[default]
+    Count,
+    /// Listings count in proportion to their match score.
+    Score,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportFormat {
+    #[default]
+    Json,
+    Markdown,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct SkillReportOptions {
+    /// Scores and settings come from this profile; defaults to the active one.
+    pub profile: Option<String>,
+    pub weighting: Weighting,
+    /// Only matches scoring at least this much are counted.
+    pub min_score: Option<f64>,
+    /// Number of skills to list; 0 lists all.
+    pub limit: usize,
+    /// Example listings kept per skill.
+    pub examples: usize,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SkillExample {
+    pub match_id: String,
+    pub title: Option<String>,
+    pub company: Option<String>,
+    pub url: String,
+    pub match_score: f64,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SkillStat {
+    pub skill: String,
+    pub count: usize,
+    /// Count, or sum of match scores / 100 when weighting by score.
+    pub weight: f64,
+    /// Fraction of the counted listings that mention the skill.
+    pub share: f64,
+    /// Whether the skill appears in the profile's keywords or résumé.
+    pub known: bool,
+    pub examples: Vec<SkillExample>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SkillReport {
+    pub profile: String,
+    pub weighting: Weighting,
+    pub min_score: Option<f64>,
+    /// Matches that passed the score threshold.
+    pub listings: usize,
+    pub skills: Vec<SkillStat>,
+    /// The most in-demand skills the profile does not mention, in order.
+    pub gaps: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(untagged)]
+pub enum ReportOutput {
+    Json(SkillReport),
+    Markdown { markdown: String },
+}
+
+/// Skills the profile already claims, from its keywords and résumé.
+fn known_skills(settings: &JobSettings) -> Vec<String> {
+    let mut known: Vec<String> = settings
+        .keywords
+        .iter()
+        .map(|keyword| canonical_skill(keyword).to_lowercase())
+        .collect();
+    if let Some(resume) = &settings.resume {
+        known.extend(
+            extract_skills(resume)
+                .into_iter()
+                .map(|skill| skill.to_lowercase()),
+        );
+    }
+    known
+}
+
+pub fn skill_report(
+    db: &Db,
+    profile: &str,
+    settings: &JobSettings,
+    options: &SkillReportOptions,
+) -> Result<SkillReport, String> {
+    let sources: Vec<SkillSource> = db
+        .skill_sources(profile)?
+        .into_iter()
+        .filter(|source| {
+            options
+                .min_score
+                .map_or(true, |min| source.match_score >= min)
+        })
+        .collect();
+    let known = known_skills(settings);
+    let examples = if options.examples == 0 {
+        3
+    } else {
+        options.examples
+    };
+
+    let mut stats: BTreeMap<String, SkillStat> = BTreeMap::new();
+    for source in &sources {
+        let mut skills: Vec<String> = extract_skills(&source.text)
+            .into_iter()
+            .map(str::to_string)
+            .collect();
+        skills.extend(source.llm_skills.iter().map(|skill| canonical_skill(skill)));
+        skills.sort_by_key(|skill| skill.to_lowercase());
+        skills.dedup_by(|a, b| a.eq_ignore_ascii_case(b));
+
+        let weight = match options.weighting {
+            Weighting::Count => 1.0,
+            Weighting::Score => source.match_score / 100.0,
+        };
+        for skill in skills {
+            let stat = stats
+                .entry(skill.to_lowercase())
+                .or_insert_with(|| SkillStat {
+                    known: known.contains(&skill.to_lowercase()),
+                    skill: skill.clone(),
+                    count: 0,
+                    weight: 0.0,
+                    share: 0.0,
+                    examples: Vec::new(),
+                });
+            stat.count += 1;
+            stat.weight += weight;
+            if stat.examples.len() < examples {
+                stat.examples.push(SkillExample {
+                    match_id: source.match_id.clone(),
+                    title: source.title.clone(),
+                    company: source.company.clone(),
+                    url: source.url.clone(),
+                    match_score: source.match_score,
+                });
+            }
+        }
+    }
+
+    let mut skills: Vec<SkillStat> = stats.into_values().collect();
+    for stat in skills.iter_mut() {
+        stat.share = stat.count as f64 / sources.len().max(1) as f64;
+    }
+    skills.sort_by(|a, b| {
+        b.weight
+            .total_cmp(&a.weight)
+            .then_with(|| a.skill.cmp(&b.skill))
+    });
+    if options.limit > 0 {
+        skills.truncate(options.limit);
+    }
+    let gaps = skills
+        .iter()
+        .filter(|stat| !stat.known)
+        .map(|stat| stat.skill.clone())
+        .collect();
+    Ok(SkillReport {
+        profile: profile.to_string(),
+        weighting: options.weighting,
+        min_score: options.min_score,
+        listings: sources.len(),
+        skills,
+        gaps,
+    })
+}
+
+/// Builds the report for the requested profile, or the active one, in the requested format.
+pub fn build_report(
+    app: &AppHandle,
+    db: &Db,
+    options: &SkillReportOptions,
+    format: ReportFormat,
+) -> Result<ReportOutput, String> {
+    let profiles = load_profiles(app)?;
+    let name = options
+        .profile
+        .clone()
+        .unwrap_or_else(|| profiles.active.clone());
+    let profile = profiles
+        .get(&name)
+        .ok_or_else(|| format!("profile not found: {name}"))?;
+    let report = skill_report(db, &name, &profile.settings, options)?;
+    Ok(match format {
+        ReportFormat::Json => ReportOutput::Json(report),
+        ReportFormat::Markdown => ReportOutput::Markdown {
+            markdown: to_markdown(&report),
+        },
+    })
+}
+
+pub fn to_markdown(report: &SkillReport) -> String {
+    let mut out = format!("# Skill gap report: {}\n\n", report.profile);
+    out.push_str(&format!(
+        "{} listings{}, weighted by {}.\n\n",
+        report.listings,
+        report
+            .min_score
+            .map(|min| format!(" scoring at least {min:.0}"))
+            .unwrap_or_default(),
+        match report.weighting {
+            Weighting::Count => "count",
+            Weighting::Score => "match score",
+        }
+    ));
+    if report.skills.is_empty() {
+        out.push_str("No skills found in the stored listings.\n");
+        return out;
+    }
+    if !report.gaps.is_empty() {
+        out.push_str("## Missing from your profile\n\n");
+        for skill in &report.gaps {
+            out.push_str(&format!("- {skill}\n"));
+        }
+        out.push('\n');
+    }
+    out.push_str("## Skills by demand\n\n");
+    out.push_str("| Skill | Listings | Share | Weight | Known | Examples |\n");
+    out.push_str("| --- | ---: | ---: | ---: | --- | --- |\n");
+    for stat in &report.skills {
+        let examples = stat
+            .examples
+            .iter()
+            .map(|example| {
+                let label = match (&example.title, &example.company) {
+                    (Some(title), Some(company)) => format!("{title} at {company}"),
+                    (Some(title), None) => title.clone(),
+                    (None, Some(company)) => company.clone(),
+                    (None, None) => example.url.clone(),
+                };
+                format!("[{}]({})", label.replace(['[', ']', '|'], " "), example.url)
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        out.push_str(&format!(
+            "| {} | {} | {:.0}% | {:.1} | {} | {} |\n",
+            stat.skill,
+            stat.count,
+            stat.share * 100.0,
+            stat.weight,
+            if stat.known { "yes" } else { "no" },
+            examples
+        ));
+    }
+    out
+}
@@ -0,0 +1,93 @@
+use crate::analysis_agent;
+use crate::db::{Db, Snapshot, SnapshotInput};
+use crate::http::FetchedPage;
+use crate::scoring::ExtractedListing;
+use crate::settings::load_archive_settings;
+use serde::Serialize;
+use tauri::AppHandle;
+
+/// Fresh extraction from an archived page.
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Reextraction {
+    pub snapshot_id: String,
+    pub url: String,
+    pub fetched_at: String,
+    pub listing: ExtractedListing,
+    /// Matches whose stored listing was replaced; empty unless saving was requested.
+    pub updated_matches: Vec<String>,
+}
+
+/// Archives a fetched page when the archive is enabled, returning the snapshot id. Archiving
+/// never fails the fetch; problems are logged instead.
+pub fn archive(
+    app: &AppHandle,
+    db: &Db,
+    url: &str,
+    page: &FetchedPage,
+    analysis_id: Option<&str>,
+) -> Option<String> {
+    let settings = load_archive_settings(app)
+        .map_err(|err| log::warn!("snapshot archive: {err}"))
+        .ok()?;
+    if !settings.enabled {
+        return None;
+    }
+    let input = SnapshotInput {
+        analysis_id,
+        url,
+        final_url: &page.final_url,
+        status: page.status,
+        headers: &page.headers,
+        encoding: &page.encoding,
+        truncated: page.truncated,
+        html: &page.body,
+    };
+    db.insert_snapshot(&input, settings.max_total_bytes)
+        .map_err(|err| log::warn!("snapshot archive: {err}"))
+        .ok()
+}
+
+/// Loads a snapshot by id or by match, trimming the HTML to `max_length` bytes when given.
+pub fn load(
+    db: &Db,
+    snapshot_id: Option<&str>,
+    match_id: Option<&str>,
+    max_length: Option<usize>,
+) -> Result<Snapshot, String> {
+    let mut snapshot = db
+        .snapshot(snapshot_id, match_id)?
+        .ok_or("snapshot not found; it may have been pruned")?;
+    if let Some(max_length) = max_length {
+        let end = crate::truncate::at_char_boundary(&snapshot.html, max_length).len();
+        snapshot.html.truncate(end);
+    }
+    Ok(snapshot)
+}
+
+/// Runs the heuristic extraction over an archived page without touching the network. With
+/// `save`, the result replaces the stored listing of the matches from that analysis, so the
+/// next rescore uses it.
+pub fn reextract(
+    db: &Db,
+    snapshot_id: Option<&str>,
+    match_id: Option<&str>,
+    save: bool,
+) -> Result<Reextraction, String> {
+    let snapshot = load(db, snapshot_id, match_id, None)?;
+    let listing = analysis_agent::extract_page(&snapshot.html);
+    let mut updated_matches = Vec::new();
+    if save {
+        for match_id in db.snapshot_matches(&snapshot.id)? {
+            db.update_listing(&match_id, &listing)?;
+            updated_matches.push(match_id);
+        }
+    }
+    Ok(Reextraction {
+        snapshot_id: snapshot.id,
+        url: snapshot.url,
+        fetched_at: snapshot.fetched_at,
+        listing,
+        updated_matches,
+    })
+}
@@ -1,7 +1,9 @@
 use crate::db::Db;
+use crate::http::HttpClient;
 
 #[derive(Clone)]
 pub struct AppState {
     pub mcp_port: u16,
     pub db: Db,
+    pub http: HttpClient,
 }
@@ -0,0 +1,9 @@
+use regex::Regex;
+use std::sync::OnceLock;
+
+/// Collapses every run of whitespace to one space and trims the ends.
+pub fn collapse_whitespace(text: &str) -> String {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    let whitespace = PATTERN.get_or_init(|| Regex::new(r"\s+").expect("whitespace pattern"));
+    whitespace.replace_all(text, " ").trim().to_string()
+}
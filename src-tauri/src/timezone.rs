@@ -0,0 +1,310 @@
+use chrono::{Offset, Utc};
+use chrono_tz::Tz;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
+
+/// Core hours assumed when a listing names a timezone but no working window.
+const DEFAULT_START_HOUR: f64 = 9.0;
+const DEFAULT_END_HOUR: f64 = 17.0;
+
+/// Timezone abbreviations and names mapped to an IANA zone so DST is applied when resolved.
+/// Names are matched case-sensitively as whole words; bare `Eastern` is left out since it also
+/// appears in `Eastern Europe` and `Middle Eastern`.
+const NAMED_ZONES: &[(&str, &str)] = &[
+    ("ET", "America/New_York"),
+    ("EST", "America/New_York"),
+    ("EDT", "America/New_York"),
+    ("US Eastern", "America/New_York"),
+    ("Eastern Time", "America/New_York"),
+    ("Eastern hours", "America/New_York"),
+    ("CST", "America/Chicago"),
+    ("CDT", "America/Chicago"),
+    ("Central Time", "America/Chicago"),
+    ("MST", "America/Denver"),
+    ("MDT", "America/Denver"),
+    ("Mountain Time", "America/Denver"),
+    ("PST", "America/Los_Angeles"),
+    ("PDT", "America/Los_Angeles"),
+    ("Pacific Time", "America/Los_Angeles"),
+    // London is on BST half the year; a listing that says GMT means UTC+0.
+    ("GMT", "Etc/UTC"),
+    ("BST", "Europe/London"),
+    ("CET", "Europe/Berlin"),
+    ("CEST", "Europe/Berlin"),
+    ("Central European", "Europe/Berlin"),
+    ("EET", "Europe/Athens"),
+    ("IST", "Asia/Kolkata"),
+    ("SGT", "Asia/Singapore"),
+    ("JST", "Asia/Tokyo"),
+    ("AEST", "Australia/Sydney"),
+    ("AEDT", "Australia/Sydney"),
+    ("BRT", "America/Sao_Paulo"),
+];
+
+/// Names only taken as a timezone right after a time window, since `PT` alone usually means
+/// part-time (`FT/PT`).
+const WINDOW_ONLY_ZONES: &[(&str, &str)] = &[("PT", "America/Los_Angeles")];
+
+/// Working hours a listing expects, expressed as acceptable UTC offsets plus a local window.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TimezoneRequirement {
+    /// The text the requirement was read from, e.g. `UTC±2` or `9am-5pm ET`.
+    pub label: String,
+    pub min_offset: f64,
+    pub max_offset: f64,
+    /// Local working window in the listing's timezone, in hours.
+    pub start_hour: f64,
+    pub end_hour: f64,
+    /// Overlap the listing asks for, when it states one.
+    pub overlap_hours: Option<f64>,
+}
+
+/// Current UTC offset of an IANA timezone in hours.
+pub fn utc_offset_hours(name: &str) -> Option<f64> {
+    let tz: Tz = name.trim().parse().ok()?;
+    let offset = Utc::now().with_timezone(&tz).offset().fix();
+    Some(offset.local_minus_utc() as f64 / 3600.0)
+}
+
+pub fn is_valid_timezone(name: &str) -> bool {
+    name.trim().parse::<Tz>().is_ok()
+}
+
+/// Reads a timezone requirement such as `UTC±2`, `GMT-5 to GMT-3`, `overlap with US Eastern
+/// hours` or `9am-5pm PT` from listing text.
+pub fn extract_requirement(text: &str) -> Option<TimezoneRequirement> {
+    let overlap_hours = overlap_pattern()
+        .captures(text)
+        .and_then(|caps| caps.get(1)?.as_str().parse::<f64>().ok())
+        .filter(|hours| *hours > 0.0 && *hours <= 24.0);
+
+    if let Some(caps) = window_pattern().captures(text) {
+        let hour = |index: usize, minute: usize, meridiem: Option<&str>| -> Option<f64> {
+            let mut value: f64 = caps.get(index)?.as_str().parse().ok()?;
+            if let Some(minutes) = caps.get(minute) {
+                value += minutes.as_str().parse::<f64>().ok()? / 60.0;
+            }
+            match meridiem.map(str::to_lowercase).as_deref() {
+                Some("pm") if value < 12.0 => value += 12.0,
+                Some("am") if value >= 12.0 => value -= 12.0,
+                _ => {}
+            }
+            (value <= 24.0).then_some(value)
+        };
+        let end_meridiem = caps.get(6).map(|m| m.as_str());
+        let start_meridiem = caps.get(3).map(|m| m.as_str()).or(end_meridiem);
+        let offsets = zone_offsets(&caps, 7);
+        if let (Some(start), Some(end), Some((min_offset, max_offset))) = (
+            hour(1, 2, start_meridiem),
+            hour(4, 5, end_meridiem),
+            offsets,
+        ) {
+            return Some(TimezoneRequirement {
+                label: caps.get(0)?.as_str().trim().to_string(),
+                min_offset,
+                max_offset,
+                start_hour: start,
+                end_hour: end,
+                overlap_hours,
+            });
+        }
+    }
+
+    let caps = zone_only_pattern().captures(text)?;
+    let (min_offset, max_offset) = zone_offsets(&caps, 1)?;
+    Some(TimezoneRequirement {
+        label: caps.get(0)?.as_str().trim().to_string(),
+        min_offset,
+        max_offset,
+        start_hour: DEFAULT_START_HOUR,
+        end_hour: DEFAULT_END_HOUR,
+        overlap_hours,
+    })
+}
+
+/// Hours of overlap between the user's working window and the listing's, choosing the listing
+/// offset closest to the user's when the listing accepts a range.
+pub fn overlap_hours(
+    requirement: &TimezoneRequirement,
+    home_offset: f64,
+    start_hour: f64,
+    end_hour: f64,
+) -> f64 {
+    let listing_offset = home_offset.clamp(requirement.min_offset, requirement.max_offset);
+    let (user_start, user_end) = utc_window(start_hour, end_hour, home_offset);
+    let (listing_start, listing_end) =
+        utc_window(requirement.start_hour, requirement.end_hour, listing_offset);
+    [-24.0, 0.0, 24.0]
+        .iter()
+        .map(|shift| {
+            (user_end.min(listing_end + shift) - user_start.max(listing_start + shift)).max(0.0)
+        })
+        .fold(0.0, f64::max)
+}
+
+/// Length of a working window in hours, allowing windows that cross midnight.
+pub fn window_length(start_hour: f64, end_hour: f64) -> f64 {
+    let (start, end) = utc_window(start_hour, end_hour, 0.0);
+    end - start
+}
+
+fn utc_window(start_hour: f64, end_hour: f64, offset: f64) -> (f64, f64) {
+    let end_hour = if end_hour <= start_hour {
+        end_hour + 24.0
+    } else {
+        end_hour
+    };
+    (start_hour - offset, end_hour - offset)
+}
+
+/// A time window followed by a zone, such as `9am-5pm PT` or `10:00 to 18:00 (UTC+1)`.
+fn window_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| {
+        let zone = zone_pattern(true);
+        Regex::new(&format!(
+            r"(?i)(\d{{1,2}})(?::(\d{{2}}))?\s*(am|pm)?\s*(?:-|–|to)\s*(\d{{1,2}})(?::(\d{{2}}))?\s*(am|pm)?\s*\(?{zone}"
+        ))
+        .expect("timezone window pattern")
+    })
+}
+
+fn zone_only_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| {
+        Regex::new(&format!("(?i){}", zone_pattern(false))).expect("timezone zone pattern")
+    })
+}
+
+fn overlap_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| {
+        Regex::new(r"(?i)(\d{1,2})\+?\s*hours?\s+(?:of\s+)?(?:overlap|overlapping)")
+            .expect("timezone overlap pattern")
+    })
+}
+
+/// A regex alternation matching `UTC±n`, `UTC+a to UTC+b` style offsets or a named zone, with
+/// `WINDOW_ONLY_ZONES` included when `in_window`. Names stay case-sensitive inside
+/// case-insensitive patterns. Group layout relative to the first group: sign, hours, minutes,
+/// range sign, range hours, name.
+fn zone_pattern(in_window: bool) -> String {
+    let extra = if in_window { WINDOW_ONLY_ZONES } else { &[] };
+    let mut names: Vec<&str> = NAMED_ZONES
+        .iter()
+        .chain(extra)
+        .map(|(name, _)| *name)
+        .collect();
+    names.sort_by_key(|name| std::cmp::Reverse(name.len()));
+    let names = names
+        .iter()
+        .map(|name| regex::escape(name).replace(' ', r"\s+"))
+        .collect::<Vec<_>>()
+        .join("|");
+    format!(
+        r"(?:(?:UTC|GMT)\s*([+\-−±])\s*(\d{{1,2}})(?::(\d{{2}}))?(?:\s*(?:to|-|–|and)\s*(?:UTC|GMT)?\s*([+\-−])\s*(\d{{1,2}}))?|\b(?-i:(?:US\s+)?({names}))\b)"
+    )
+}
+
+/// Converts the zone groups starting at `first` into an offset range in hours.
+fn zone_offsets(caps: &regex::Captures, first: usize) -> Option<(f64, f64)> {
+    if let Some(name) = caps.get(first + 5) {
+        let name = name
+            .as_str()
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ");
+        let (_, zone) = NAMED_ZONES
+            .iter()
+            .chain(WINDOW_ONLY_ZONES)
+            .find(|(candidate, _)| *candidate == name)?;
+        let offset = utc_offset_hours(zone)?;
+        return Some((offset, offset));
+    }
+    let signed = |sign: &str, hours: f64| {
+        if matches!(sign, "-" | "−") {
+            -hours
+        } else {
+            hours
+        }
+    };
+    let sign = caps.get(first)?.as_str();
+    let mut hours: f64 = caps.get(first + 1)?.as_str().parse().ok()?;
+    if let Some(minutes) = caps.get(first + 2) {
+        hours += minutes.as_str().parse::<f64>().ok()? / 60.0;
+    }
+    if hours > 14.0 {
+        return None;
+    }
+    if sign == "±" {
+        return Some((-hours, hours));
+    }
+    let start = signed(sign, hours);
+    match (caps.get(first + 3), caps.get(first + 4)) {
+        (Some(range_sign), Some(range_hours)) => {
+            let end = signed(range_sign.as_str(), range_hours.as_str().parse().ok()?);
+            Some((start.min(end), start.max(end)))
+        }
+        _ => Some((start, start)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn offsets(requirement: &TimezoneRequirement) -> (f64, f64) {
+        (requirement.min_offset, requirement.max_offset)
+    }
+
+    #[test]
+    fn offset_ranges() {
+        let plus_minus = extract_requirement("Must work within UTC±2.").unwrap();
+        assert_eq!(offsets(&plus_minus), (-2.0, 2.0));
+        assert_eq!(
+            (plus_minus.start_hour, plus_minus.end_hour),
+            (DEFAULT_START_HOUR, DEFAULT_END_HOUR)
+        );
+
+        let range = extract_requirement("Candidates between GMT-5 to GMT-3 preferred").unwrap();
+        assert_eq!(offsets(&range), (-5.0, -3.0));
+    }
+
+    #[test]
+    fn windows_with_named_zones() {
+        let pacific = extract_requirement("Core hours 9am-5pm PT, 4 hours of overlap").unwrap();
+        let offset = utc_offset_hours("America/Los_Angeles").unwrap();
+        assert_eq!(offsets(&pacific), (offset, offset));
+        assert_eq!((pacific.start_hour, pacific.end_hour), (9.0, 17.0));
+        assert_eq!(pacific.overlap_hours, Some(4.0));
+
+        let eastern = extract_requirement("Available 10AM-6PM ET").unwrap();
+        assert_eq!((eastern.start_hour, eastern.end_hour), (10.0, 18.0));
+        let offset = utc_offset_hours("America/New_York").unwrap();
+        assert_eq!(offsets(&eastern), (offset, offset));
+
+        let us_eastern = extract_requirement("Overlap with US Eastern hours").unwrap();
+        assert_eq!(offsets(&us_eastern), (offset, offset));
+    }
+
+    #[test]
+    fn gmt_is_utc_all_year() {
+        let gmt = extract_requirement("Team works on GMT").unwrap();
+        assert_eq!(offsets(&gmt), (0.0, 0.0));
+    }
+
+    #[test]
+    fn lookalike_words_are_not_zones() {
+        for text in [
+            "We are expanding across Eastern Europe.",
+            "Experience with Eastern European Time clients.",
+            "Offices in Middle Eastern markets.",
+            "Contract type: FT/PT",
+            "Part-time (PT) role with flexible hours.",
+        ] {
+            assert_eq!(extract_requirement(text), None, "{text}");
+        }
+    }
+}
@@ -0,0 +1,339 @@
+/// Abbreviations expanded before titles are compared, applied per token.
+const ABBREVIATIONS: &[(&str, &str)] = &[
+    ("sr", "senior"),
+    ("snr", "senior"),
+    ("jr", "junior"),
+    ("eng", "engineer"),
+    ("engr", "engineer"),
+    ("dev", "developer"),
+    ("swe", "software engineer"),
+    ("sde", "software engineer"),
+    ("programmer", "developer"),
+    ("mgr", "manager"),
+    ("em", "engineering manager"),
+    ("sre", "site reliability engineer"),
+    ("ml", "machine learning"),
+    ("fe", "frontend"),
+    ("be", "backend"),
+    ("fullstack", "full stack"),
+    ("qa", "quality assurance"),
+    ("infra", "infrastructure"),
+    ("ops", "operations"),
+    ("arch", "architect"),
+    ("assoc", "associate"),
+    ("dir", "director"),
+    ("vp", "vice president"),
+];
+
+/// Seniority and level markers dropped from normalized titles.
+const LEVEL_WORDS: &[&str] = &[
+    "senior",
+    "junior",
+    "principal",
+    "staff",
+    "lead",
+    "associate",
+    "intern",
+    "entry",
+    "mid",
+    "level",
+    "i",
+    "ii",
+    "iii",
+    "iv",
+    "v",
+    "1",
+    "2",
+    "3",
+    "4",
+    "5",
+];
+
+/// Role families checked in order; the first family with a matching phrase wins.
+const ROLE_FAMILIES: &[(&str, &[&str])] = &[
+    (
+        "em",
+        &[
+            "engineering manager",
+            "manager software",
+            "software manager",
+            "head of engineering",
+            "director of engineering",
+            "engineering director",
+            "vice president of engineering",
+            "vice president engineering",
+            "cto",
+        ],
+    ),
+    (
+        "sre",
+        &[
+            "site reliability",
+            "devops",
+            "platform engineer",
+            "infrastructure",
+            "cloud engineer",
+        ],
+    ),
+    (
+        "data",
+        &[
+            "data engineer",
+            "data scientist",
+            "data science",
+            "machine learning",
+            "analytics",
+            "data analyst",
+            "ai engineer",
+        ],
+    ),
+    ("security", &["security", "appsec"]),
+    ("mobile", &["ios", "android", "mobile"]),
+    ("fullstack", &["full stack"]),
+    (
+        "frontend",
+        &["frontend", "front end", "ui engineer", "web developer"],
+    ),
+    ("backend", &["backend", "back end", "api", "server"]),
+    ("qa", &["quality assurance", "test", "sdet"]),
+    ("product", &["product manager", "product owner"]),
+    ("design", &["designer", "ux"]),
+    ("software", &["software", "developer", "engineer"]),
+];
+
+/// Minimum similarity that counts as a title match at all.
+const MIN_SIMILARITY: f64 = 0.7;
+/// Similarity credited when titles differ in wording but share a specific role family.
+const FAMILY_SIMILARITY: f64 = 0.75;
+/// Applied when both titles have specific but different families, e.g. frontend vs backend.
+const FAMILY_MISMATCH_PENALTY: f64 = 0.6;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TitleMatch {
+    pub preferred: String,
+    pub similarity: f64,
+}
+
+/// Lowercases, splits on punctuation and expands abbreviations, keeping qualifiers.
+fn tokens(title: &str) -> Vec<String> {
+    let lowered = title
+        .to_lowercase()
+        .replace("front-end", "frontend")
+        .replace("back-end", "backend");
+    lowered
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .flat_map(|word| {
+            ABBREVIATIONS
+                .iter()
+                .find(|(short, _)| *short == word)
+                .map(|(_, long)| long.split(' ').map(str::to_string).collect())
+                .unwrap_or_else(|| vec![word.to_string()])
+        })
+        .collect()
+}
+
+/// Normalizes a title for comparison: `Sr. Software Eng. II (Platform)` and
+/// `Engineer, Software` both become `software engineer`. Parenthesized team names are dropped,
+/// `Engineer, Software` is reordered, and seniority levels are removed.
+pub fn normalize(title: &str) -> String {
+    let mut base = String::new();
+    let mut depth = 0usize;
+    for c in title.chars() {
+        match c {
+            '(' | '[' => depth += 1,
+            ')' | ']' => depth = depth.saturating_sub(1),
+            _ if depth == 0 => base.push(c),
+            _ => {}
+        }
+    }
+    // Team qualifiers after a dash or bar: "Backend Engineer - Payments".
+    let base = [" - ", " – ", " | ", " @ "]
+        .iter()
+        .fold(base.as_str(), |acc, separator| {
+            acc.split(separator).next().unwrap_or(acc)
+        })
+        .to_string();
+    // Inverted form: "Engineer, Software" -> "Software Engineer".
+    let base = match base.split_once(',') {
+        Some((head, tail)) if tokens(head).len() == 1 => format!("{} {}", tail, head),
+        Some((head, _)) => head.to_string(),
+        None => base,
+    };
+    tokens(&base)
+        .into_iter()
+        .filter(|word| !LEVEL_WORDS.contains(&word.as_str()))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Maps a title to a role family such as `backend`, `frontend`, `sre`, `data` or `em`. The
+/// title itself decides first, so `Backend Engineer, Security` is `backend`; team qualifiers
+/// only count when the title alone is generic, as in `Software Engineer (Security)`.
+pub fn role_family(title: &str) -> Option<&'static str> {
+    family_of(&normalize(title))
+        .filter(|family| *family != "software")
+        .or_else(|| family_of(title))
+}
+
+/// The first family in `ROLE_FAMILIES` with a phrase in `title`.
+fn family_of(title: &str) -> Option<&'static str> {
+    let padded = format!(" {} ", tokens(title).join(" "));
+    ROLE_FAMILIES
+        .iter()
+        .find(|(_, phrases)| {
+            phrases
+                .iter()
+                .any(|phrase| padded.contains(&format!(" {phrase}")))
+        })
+        .map(|(family, _)| *family)
+}
+
+/// Token-set similarity in `0.0..=1.0`, tolerant of word order, extra words and small typos.
+/// Compares the shared tokens against each side's full token set, as fuzzywuzzy's
+/// `token_set_ratio` does, and keeps the best ratio.
+pub fn token_set_similarity(a: &str, b: &str) -> f64 {
+    let mut left: Vec<&str> = a.split_whitespace().collect();
+    let mut right: Vec<&str> = b.split_whitespace().collect();
+    left.sort_unstable();
+    left.dedup();
+    right.sort_unstable();
+    right.dedup();
+    if left.is_empty() || right.is_empty() {
+        return 0.0;
+    }
+    let shared: Vec<&str> = left
+        .iter()
+        .filter(|word| right.contains(word))
+        .copied()
+        .collect();
+    let only_left: Vec<&str> = left
+        .iter()
+        .filter(|word| !shared.contains(word))
+        .copied()
+        .collect();
+    let only_right: Vec<&str> = right
+        .iter()
+        .filter(|word| !shared.contains(word))
+        .copied()
+        .collect();
+    let joined = |parts: &[&[&str]]| {
+        parts
+            .iter()
+            .flat_map(|part| part.iter().copied())
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+    let base = shared.join(" ");
+    let with_left = joined(&[&shared, &only_left]);
+    let with_right = joined(&[&shared, &only_right]);
+    let mut best = ratio(&with_left, &with_right);
+    if !shared.is_empty() {
+        best = best
+            .max(ratio(&base, &with_left))
+            .max(ratio(&base, &with_right));
+    }
+    best
+}
+
+/// Best fuzzy match of a listing title against the preferred titles, or `None` below the
+/// minimum similarity.
+pub fn best_match(title: &str, preferred: &[String]) -> Option<TitleMatch> {
+    let normalized = normalize(title);
+    let family = role_family(title);
+    preferred
+        .iter()
+        .filter(|value| !value.trim().is_empty())
+        .map(|value| {
+            let mut similarity = token_set_similarity(&normalized, &normalize(value));
+            let specific =
+                |family: Option<&'static str>| family.filter(|family| *family != "software");
+            match (specific(family), specific(role_family(value))) {
+                (Some(listing), Some(wanted)) if listing == wanted => {
+                    similarity = similarity.max(FAMILY_SIMILARITY);
+                }
+                (Some(_), Some(_)) => similarity *= FAMILY_MISMATCH_PENALTY,
+                _ => {}
+            }
+            TitleMatch {
+                preferred: value.clone(),
+                similarity,
+            }
+        })
+        .filter(|candidate| candidate.similarity >= MIN_SIMILARITY)
+        .max_by(|a, b| a.similarity.total_cmp(&b.similarity))
+}
+
+/// Levenshtein ratio: `1 - distance / longer length`.
+fn ratio(a: &str, b: &str) -> f64 {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let longest = a.len().max(b.len());
+    if longest == 0 {
+        return 1.0;
+    }
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, left) in a.iter().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, right) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(left != right);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+    1.0 - previous[b.len()] as f64 / longest as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn request_examples_normalize_to_software_engineer() {
+        for title in [
+            "Software Engineer II",
+            "Sr. Software Eng.",
+            "Engineer, Software (Platform)",
+            "Senior Software Engineer - Payments",
+            "SWE",
+        ] {
+            assert_eq!(normalize(title), "software engineer", "{title}");
+        }
+    }
+
+    #[test]
+    fn role_family_prefers_the_title_over_its_team() {
+        assert_eq!(role_family("Backend Engineer, Security"), Some("backend"));
+        assert_eq!(
+            role_family("Software Engineer (Security)"),
+            Some("security")
+        );
+        assert_eq!(role_family("Sr. SRE"), Some("sre"));
+        assert_eq!(role_family("EM, Payments"), Some("em"));
+        assert_eq!(role_family("Front-end Developer"), Some("frontend"));
+        assert_eq!(role_family("Software Engineer"), Some("software"));
+        assert_eq!(role_family("Account Executive"), None);
+    }
+
+    #[test]
+    fn token_set_similarity_ignores_order_and_extra_words() {
+        assert_eq!(
+            token_set_similarity("software engineer", "engineer software"),
+            1.0
+        );
+        assert_eq!(
+            token_set_similarity("software engineer", "software engineer payments"),
+            1.0
+        );
+        assert!(token_set_similarity("software engineer", "sofware engineer") > 0.9);
+        assert!(token_set_similarity("software engineer", "account executive") < 0.5);
+        assert_eq!(token_set_similarity("", "software engineer"), 0.0);
+    }
+
+    #[test]
+    fn best_match_penalizes_different_families() {
+        let preferred = vec!["Backend Engineer".to_string()];
+        assert!(best_match("Sr. Back-end Engineer II", &preferred).is_some());
+        assert_eq!(best_match("Frontend Engineer", &preferred), None);
+    }
+}
@@ -0,0 +1,121 @@
+/// Characters that end a sentence. CJK full stops need no following space.
+const SENTENCE_ENDS: &[char] = &['.', '!', '?', '…'];
+const CJK_SENTENCE_ENDS: &[char] = &['。', '！', '？'];
+
+/// Longest prefix of `text` no longer than `max_bytes` that ends on a character boundary, so
+/// multi-byte characters are never split.
+pub fn at_char_boundary(text: &str, max_bytes: usize) -> &str {
+    if text.len() <= max_bytes {
+        return text;
+    }
+    let mut end = max_bytes;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    &text[..end]
+}
+
+/// Shortens `text` to at most `max_bytes`, preferring to end after a sentence and then between
+/// words, as long as that keeps at least four fifths of the budget.
+pub fn at_sentence_boundary(text: &str, max_bytes: usize) -> &str {
+    let head = at_char_boundary(text, max_bytes);
+    if head.len() == text.len() {
+        return text;
+    }
+    let floor = head.len() * 4 / 5;
+    let mut sentence_end = None;
+    let mut word_end = None;
+    let mut chars = head.char_indices().peekable();
+    while let Some((index, c)) = chars.next() {
+        let end = index + c.len_utf8();
+        // The character after the head decides whether its last character ends a sentence.
+        let next = chars
+            .peek()
+            .map(|(_, next)| *next)
+            .or_else(|| text[head.len()..].chars().next());
+        if CJK_SENTENCE_ENDS.contains(&c)
+            || (SENTENCE_ENDS.contains(&c) && next.map_or(true, char::is_whitespace))
+        {
+            sentence_end = Some(end);
+        } else if c.is_whitespace() {
+            word_end = Some(index);
+        }
+    }
+    match (sentence_end, word_end) {
+        (Some(end), _) if end >= floor => &head[..end],
+        (_, Some(end)) if end >= floor => head[..end].trim_end(),
+        _ => head,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn char_boundary_never_splits_multibyte_text() {
+        for text in [
+            "求人情報：シニアエンジニア",
+            "Größere Verantwortung für Prüfungen",
+            "Remote 🚀 team 👩‍💻 hiring 🎉",
+        ] {
+            for max_bytes in 0..=text.len() + 1 {
+                let head = at_char_boundary(text, max_bytes);
+                assert!(head.len() <= max_bytes, "{text:?} at {max_bytes}");
+                assert!(text.starts_with(head));
+                // At most the bytes of one partial character are dropped.
+                assert!(max_bytes.min(text.len()) - head.len() < 4);
+            }
+        }
+    }
+
+    #[test]
+    fn sentence_boundary_ends_after_cjk_full_stop() {
+        let text = "募集職種はエンジニアです。勤務地は東京です。詳細は面接で説明します。";
+        let max_bytes = "募集職種はエンジニアです。勤務地は東京です。詳細は".len();
+        assert_eq!(
+            at_sentence_boundary(text, max_bytes),
+            "募集職種はエンジニアです。勤務地は東京です。"
+        );
+    }
+
+    #[test]
+    fn sentence_boundary_ends_after_ellipsis() {
+        let text = "We build tools for hiring teams… Our stack is Rust and TypeScript.";
+        let max_bytes = "We build tools for hiring teams… Our".len();
+        assert_eq!(
+            at_sentence_boundary(text, max_bytes),
+            "We build tools for hiring teams…"
+        );
+    }
+
+    #[test]
+    fn ellipsis_inside_a_word_is_not_a_sentence_end() {
+        let text = "Options…Stock and bonus are included in every offer we make.";
+        let max_bytes = "Options…Stock and bonus are included in".len();
+        assert_eq!(
+            at_sentence_boundary(text, max_bytes),
+            "Options…Stock and bonus are included"
+        );
+    }
+
+    #[test]
+    fn boundaries_below_four_fifths_are_ignored() {
+        // The only sentence end is far too early, so the cut falls between words instead.
+        let text = "Hi. This paragraph runs on for quite a while without stopping anywhere";
+        let max_bytes = 50;
+        let cut = at_sentence_boundary(text, max_bytes);
+        assert_eq!(cut, "Hi. This paragraph runs on for quite a while");
+        assert!(cut.len() >= max_bytes * 4 / 5);
+
+        // With no usable boundary at all the plain character cut is kept.
+        let text = "Aaaa aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa";
+        assert_eq!(at_sentence_boundary(text, 40), &text[..40]);
+    }
+
+    #[test]
+    fn short_text_is_returned_whole() {
+        assert_eq!(at_sentence_boundary("Short.", 100), "Short.");
+        assert_eq!(at_char_boundary("", 0), "");
+    }
+}
@@ -0,0 +1,349 @@
+use crate::analysis_agent;
+use crate::db::{Db, ListingChange, SnapshotInput, WatchTarget};
+use crate::http::{ErrorCategory, FetchOrigin, HttpClient};
+use crate::scoring::ExtractedListing;
+use crate::settings::{load_archive_settings, load_watch_settings};
+use crate::text;
+use crate::truncate;
+use chrono::{Duration as ChronoDuration, Utc};
+use scraper::Html;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use similar::TextDiff;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+
+/// How often the scheduler looks for listings that are due.
+const POLL_INTERVAL: Duration = Duration::from_secs(5 * 60);
+/// Diffs longer than this are cut off.
+const MAX_DIFF_BYTES: usize = 16 * 1024;
+/// Phrases job boards show in place of a removed posting.
+const CLOSED_PHRASES: &[&str] = &[
+    "no longer accepting applications",
+    "job is no longer available",
+    "position is no longer available",
+    "position has been filled",
+    "this job has expired",
+    "job posting has expired",
+    "this job is closed",
+    "posting is closed",
+    "job is no longer open",
+];
+
+static RUNNING: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeKind {
+    SalaryAdded,
+    SalaryChanged,
+    SalaryRemoved,
+    TitleChanged,
+    LocationChanged,
+    ArrangementChanged,
+    /// The text changed without a recognized field changing, such as edited requirements.
+    ContentEdited,
+    /// The page is gone (`404` / `410`) or says the posting is closed.
+    Closed,
+    /// A closed posting is back.
+    Reopened,
+}
+
+#[derive(Debug, Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct WatchSummary {
+    pub checked: usize,
+    pub changed: usize,
+    pub closed: usize,
+    /// Listings that could not be fetched for reasons other than being gone.
+    pub failed: usize,
+}
+
+/// Starts the background loop that refetches watched listings once they are due.
+pub fn start(app: AppHandle, db: Db, http: HttpClient) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(POLL_INTERVAL).await;
+            let settings = match load_watch_settings(&app) {
+                Ok(settings) => settings,
+                Err(err) => {
+                    log::warn!("listing watch: {err}");
+                    continue;
+                }
+            };
+            if !settings.enabled {
+                continue;
+            }
+            let due = Utc::now() - ChronoDuration::hours(i64::from(settings.interval_hours));
+            if let Err(err) = check(&app, &db, &http, None, Some(&due.to_rfc3339())).await {
+                log::warn!("listing watch: {err}");
+            }
+        }
+    });
+}
+
+/// Refetches one match, or every watched match last checked before `checked_before`, and
+/// records what changed. Each change emits `watch:changed`; the run ends with `watch:completed`.
+pub async fn check(
+    app: &AppHandle,
+    db: &Db,
+    http: &HttpClient,
+    match_id: Option<&str>,
+    checked_before: Option<&str>,
+) -> Result<WatchSummary, String> {
+    if RUNNING.swap(true, Ordering::SeqCst) {
+        return Err("a listing watch run is already in progress".to_string());
+    }
+    let result = check_targets(app, db, http, match_id, checked_before).await;
+    RUNNING.store(false, Ordering::SeqCst);
+    let summary = result?;
+    let _ = app.emit("watch:completed", json!(summary));
+    Ok(summary)
+}
+
+async fn check_targets(
+    app: &AppHandle,
+    db: &Db,
+    http: &HttpClient,
+    match_id: Option<&str>,
+    checked_before: Option<&str>,
+) -> Result<WatchSummary, String> {
+    let settings = load_watch_settings(app)?;
+    let archive_limit = load_archive_settings(app)?.max_total_bytes;
+    let targets = db.watch_targets(
+        match_id,
+        settings.include_rejected,
+        settings.max_age_days,
+        checked_before,
+    )?;
+    if match_id.is_some() && targets.is_empty() {
+        return Err("job match not found or has no analysis to compare against".to_string());
+    }
+    let mut summary = WatchSummary::default();
+    for target in &targets {
+        summary.checked += 1;
+        match check_one(db, http, target, archive_limit).await {
+            Ok(Some(change)) => {
+                summary.changed += 1;
+                if change.kinds.contains(&ChangeKind::Closed) {
+                    summary.closed += 1;
+                }
+                let _ = app.emit("watch:changed", json!({ "change": change }));
+            }
+            Ok(None) => {}
+            Err(err) => {
+                summary.failed += 1;
+                log::warn!("listing watch {}: {err}", target.url);
+            }
+        }
+    }
+    Ok(summary)
+}
+
+/// Fetches a listing and compares it with its latest snapshot. A listing without a snapshot
+/// gets one as the baseline for the next check.
+async fn check_one(
+    db: &Db,
+    http: &HttpClient,
+    target: &WatchTarget,
+    archive_limit: u64,
+) -> Result<Option<ListingChange>, String> {
+    let previous = db.snapshot(None, Some(&target.match_id))?;
+    let page = match http.fetch(&target.url, true, FetchOrigin::Automated).await {
+        Ok(page) => page,
+        Err(err) if err.category == Some(ErrorCategory::NotFound) => {
+            db.mark_watched(&target.match_id, Some(true))?;
+            if target.closed {
+                return Ok(None);
+            }
+            let status = err.status.map_or("an error".to_string(), |s| s.to_string());
+            let change = db.insert_change(
+                &target.match_id,
+                &[ChangeKind::Closed],
+                &format!("listing closed: the page returned {status}"),
+                "",
+                previous.as_ref().map(|snapshot| snapshot.id.as_str()),
+                None,
+            )?;
+            return Ok(Some(change));
+        }
+        Err(err) => return Err(err.message),
+    };
+
+    let new_text = normalized_text(&page.body);
+    let closed_now = says_closed(&new_text);
+    let old_text = previous
+        .as_ref()
+        .map(|snapshot| normalized_text(&snapshot.html));
+    if old_text.as_deref() == Some(new_text.as_str()) && closed_now == target.closed {
+        db.mark_watched(&target.match_id, Some(closed_now))?;
+        return Ok(None);
+    }
+
+    let snapshot_id = db.insert_snapshot(
+        &SnapshotInput {
+            analysis_id: Some(&target.analysis_id),
+            url: &target.url,
+            final_url: &page.final_url,
+            status: page.status,
+            headers: &page.headers,
+            encoding: &page.encoding,
+            truncated: page.truncated,
+            html: &page.body,
+        },
+        archive_limit,
+    )?;
+    db.mark_watched(&target.match_id, Some(closed_now))?;
+    let (Some(previous), Some(old_text)) = (previous, old_text) else {
+        return Ok(None);
+    };
+
+    let old_listing = analysis_agent::extract_page(&previous.html);
+    let new_listing = analysis_agent::extract_page(&page.body);
+    let mut kinds = field_changes(&old_listing, &new_listing);
+    if closed_now && !target.closed {
+        kinds.push(ChangeKind::Closed);
+    } else if !closed_now && target.closed {
+        kinds.push(ChangeKind::Reopened);
+    }
+    if kinds.is_empty() {
+        kinds.push(ChangeKind::ContentEdited);
+    }
+    let diff = TextDiff::from_lines(&old_text, &new_text)
+        .unified_diff()
+        .context_radius(2)
+        .header("previous", "current")
+        .to_string();
+    let change = db.insert_change(
+        &target.match_id,
+        &kinds,
+        &summarize(&kinds, &old_listing, &new_listing),
+        &capped_diff(&diff),
+        Some(&previous.id),
+        Some(&snapshot_id),
+    )?;
+    Ok(Some(change))
+}
+
+/// Cuts a long diff after its last whole line within `MAX_DIFF_BYTES` and says how much was
+/// left out, so the stored diff never ends mid-line.
+fn capped_diff(diff: &str) -> String {
+    if diff.len() <= MAX_DIFF_BYTES {
+        return diff.to_string();
+    }
+    let head = truncate::at_char_boundary(diff, MAX_DIFF_BYTES);
+    let head = head.rfind('\n').map_or("", |end| &head[..=end]);
+    let omitted = diff[head.len()..].lines().count();
+    format!("{head}\\ diff truncated, {omitted} more lines\n")
+}
+
+/// Visible text of a page, one text node per line with whitespace collapsed, so markup and
+/// script changes do not count as listing changes.
+fn normalized_text(html: &str) -> String {
+    let document = Html::parse_document(html);
+    let mut lines = String::new();
+    for node in document.root_element().descendants() {
+        let Some(node_text) = node.value().as_text() else {
+            continue;
+        };
+        let hidden = node
+            .parent()
+            .and_then(|parent| parent.value().as_element().map(|element| element.name()))
+            .is_some_and(|name| matches!(name, "script" | "style" | "noscript" | "template"));
+        if hidden {
+            continue;
+        }
+        let line = text::collapse_whitespace(node_text);
+        if !line.is_empty() {
+            lines.push_str(&line);
+            lines.push('\n');
+        }
+    }
+    lines
+}
+
+fn says_closed(text: &str) -> bool {
+    let text = text.to_lowercase();
+    CLOSED_PHRASES.iter().any(|phrase| text.contains(phrase))
+}
+
+fn field_changes(old: &ExtractedListing, new: &ExtractedListing) -> Vec<ChangeKind> {
+    let mut kinds = Vec::new();
+    let salary = |listing: &ExtractedListing| {
+        (listing.salary_min.is_some() || listing.salary_max.is_some()).then(|| {
+            (
+                listing.salary_min,
+                listing.salary_max,
+                listing.salary_currency.clone(),
+            )
+        })
+    };
+    match (salary(old), salary(new)) {
+        (None, Some(_)) => kinds.push(ChangeKind::SalaryAdded),
+        (Some(_), None) => kinds.push(ChangeKind::SalaryRemoved),
+        (Some(before), Some(after)) if before != after => kinds.push(ChangeKind::SalaryChanged),
+        _ => {}
+    }
+    let differs = |before: &Option<String>, after: &Option<String>| {
+        let normalize = |value: &Option<String>| value.as_deref().map(|v| v.trim().to_lowercase());
+        normalize(before) != normalize(after)
+    };
+    if differs(&old.title, &new.title) {
+        kinds.push(ChangeKind::TitleChanged);
+    }
+    if differs(&old.location, &new.location) {
+        kinds.push(ChangeKind::LocationChanged);
+    }
+    if differs(&old.arrangement, &new.arrangement) {
+        kinds.push(ChangeKind::ArrangementChanged);
+    }
+    kinds
+}
+
+fn format_salary(listing: &ExtractedListing) -> String {
+    let range = match (listing.salary_min, listing.salary_max) {
+        (Some(min), Some(max)) if min != max => format!("{min}–{max}"),
+        (Some(value), _) | (None, Some(value)) => value.to_string(),
+        (None, None) => "none".to_string(),
+    };
+    match &listing.salary_currency {
+        Some(currency) => format!("{range} {currency}"),
+        None => range,
+    }
+}
+
+fn summarize(kinds: &[ChangeKind], old: &ExtractedListing, new: &ExtractedListing) -> String {
+    let field = |value: &Option<String>| value.clone().unwrap_or_else(|| "none".to_string());
+    kinds
+        .iter()
+        .map(|kind| match kind {
+            ChangeKind::SalaryAdded => format!("salary added: {}", format_salary(new)),
+            ChangeKind::SalaryChanged => format!(
+                "salary changed from {} to {}",
+                format_salary(old),
+                format_salary(new)
+            ),
+            ChangeKind::SalaryRemoved => format!("salary removed (was {})", format_salary(old)),
+            ChangeKind::TitleChanged => format!(
+                "title changed from {} to {}",
+                field(&old.title),
+                field(&new.title)
+            ),
+            ChangeKind::LocationChanged => format!(
+                "location changed from {} to {}",
+                field(&old.location),
+                field(&new.location)
+            ),
+            ChangeKind::ArrangementChanged => format!(
+                "arrangement changed from {} to {}",
+                field(&old.arrangement),
+                field(&new.arrangement)
+            ),
+            ChangeKind::ContentEdited => "listing text edited".to_string(),
+            ChangeKind::Closed => "listing closed".to_string(),
+            ChangeKind::Reopened => "listing reopened".to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("; ")
+}